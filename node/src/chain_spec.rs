@@ -1,13 +1,16 @@
 use hex_literal::hex;
 use node_template_runtime::{
-	AccountId, AuraConfig, BalancesConfig, GrandpaConfig, RuntimeGenesisConfig, Signature,
-	SudoConfig, SystemConfig, WASM_BINARY,
+	AccountId, AuraConfig, BalancesConfig, DefiConfig, GrandpaConfig, NativeAssetId, OracleConfig,
+	RuntimeGenesisConfig, Signature, SudoConfig, SystemConfig, EXISTENTIAL_DEPOSIT, WASM_BINARY,
 };
 use sc_service::ChainType;
 use sp_consensus_aura::sr25519::AuthorityId as AuraId;
 use sp_consensus_grandpa::AuthorityId as GrandpaId;
 use sp_core::{sr25519, Pair, Public};
-use sp_runtime::traits::{IdentifyAccount, Verify};
+use sp_runtime::{
+	traits::{Get, IdentifyAccount, Verify},
+	FixedU128,
+};
 
 // The URL for the telemetry server.
 // const STAGING_TELEMETRY_URL: &str = "wss://telemetry.polkadot.io/submit/";
@@ -57,10 +60,19 @@ pub fn development_config() -> Result<ChainSpec, String> {
 				vec![
 					get_account_id_from_seed::<sr25519::Public>("Alice"),
 					get_account_id_from_seed::<sr25519::Public>("Bob"),
+					get_account_id_from_seed::<sr25519::Public>("Charlie"),
 					get_account_id_from_seed::<sr25519::Public>("Alice//stash"),
 					get_account_id_from_seed::<sr25519::Public>("Bob//stash"),
 					hex!("fed77d0df3f5068d8a875e5ae7c3248ba7c602439623cab507206af8e50edd4b").into(),
 				],
+				// Defi authority and pause guardian
+				hex!("fed77d0df3f5068d8a875e5ae7c3248ba7c602439623cab507206af8e50edd4b").into(),
+				// Oracle feeders, as many as a price requires
+				vec![
+					get_account_id_from_seed::<sr25519::Public>("Alice"),
+					get_account_id_from_seed::<sr25519::Public>("Bob"),
+					get_account_id_from_seed::<sr25519::Public>("Charlie"),
+				],
 				true,
 			)
 		},
@@ -110,6 +122,14 @@ pub fn local_testnet_config() -> Result<ChainSpec, String> {
 					get_account_id_from_seed::<sr25519::Public>("Ferdie//stash"),
 					hex!("fed77d0df3f5068d8a875e5ae7c3248ba7c602439623cab507206af8e50edd4b").into(),
 				],
+				// Defi authority and pause guardian
+				hex!("fed77d0df3f5068d8a875e5ae7c3248ba7c602439623cab507206af8e50edd4b").into(),
+				// Oracle feeders, as many as a price requires
+				vec![
+					get_account_id_from_seed::<sr25519::Public>("Alice"),
+					get_account_id_from_seed::<sr25519::Public>("Bob"),
+					get_account_id_from_seed::<sr25519::Public>("Charlie"),
+				],
 				true,
 			)
 		},
@@ -133,6 +153,8 @@ fn testnet_genesis(
 	initial_authorities: Vec<(AuraId, GrandpaId)>,
 	root_key: AccountId,
 	endowed_accounts: Vec<AccountId>,
+	defi_authority: AccountId,
+	oracle_feeders: Vec<AccountId>,
 	_enable_println: bool,
 ) -> RuntimeGenesisConfig {
 	RuntimeGenesisConfig {
@@ -154,8 +176,25 @@ fn testnet_genesis(
 		},
 		sudo: SudoConfig {
 			// Assign network admin rights.
-			key: Some(root_key),
+			key: Some(root_key.clone()),
 		},
 		transaction_payment: Default::default(),
+		assets: Default::default(),
+		oracle: OracleConfig { feeders: oracle_feeders },
+		defi: DefiConfig {
+			authority: Some(defi_authority.clone()),
+			pause_guardian: Some(defi_authority),
+			// List the native market with the default pallet rates, accrued per second.
+			markets: vec![(
+				NativeAssetId::get(),
+				FixedU128::from_rational(487_903_968, 315_360_000_000_000_000),
+				FixedU128::from_rational(676_589_112, 315_360_000_000_000_000),
+				FixedU128::from_rational(75, 100),
+				FixedU128::from_rational(10, 100),
+			)],
+			pallet_balance: EXISTENTIAL_DEPOSIT,
+			// Seed the native pool with liquidity from the root account.
+			positions: vec![(root_key, NativeAssetId::get(), 1 << 50, 0)],
+		},
 	}
 }
@@ -0,0 +1,182 @@
+//! RPC interface of the defi pallet.
+//!
+//! Values are computed by the runtime with interest accrued up to the queried block, so they
+//! match what the runtime charges at that block. Balances are returned as `NumberOrHex`, since
+//! they don't fit the range of numbers JSON clients can represent.
+
+use std::{fmt::Debug, sync::Arc};
+
+use codec::Codec;
+use jsonrpsee::{
+	core::RpcResult,
+	proc_macros::rpc,
+	types::error::{CallError, ErrorObject},
+};
+pub use pallet_defi_runtime_api::DefiApi as DefiRuntimeApi;
+use serde::{Deserialize, Serialize};
+use sp_api::ProvideRuntimeApi;
+use sp_blockchain::HeaderBackend;
+use sp_rpc::number::NumberOrHex;
+use sp_runtime::traits::Block as BlockT;
+
+/// Position of an account in a market
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Position<Balance> {
+	/// Deposit balance including accrued interest
+	pub lending_amount: Balance,
+	/// Collateral held in the account without being lent out
+	pub held_collateral: Balance,
+	/// Debt including accrued interest
+	pub debt_amount: Balance,
+	/// Amount which can still be borrowed
+	pub allowed_borrowing_amount: Balance,
+}
+
+/// Yearly rates of a market as fixed point numbers with 18 decimals
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Apy<Balance> {
+	/// Yearly rate earned by depositors
+	pub deposit_apy: Balance,
+	/// Yearly rate paid by borrowers
+	pub borrowing_apy: Balance,
+}
+
+#[rpc(client, server)]
+pub trait DefiApi<BlockHash, AccountId, AssetId> {
+	/// Position of the account in the market at the given block, best block by default
+	#[method(name = "defi_getPosition")]
+	fn get_position(
+		&self,
+		who: AccountId,
+		asset_id: AssetId,
+		at: Option<BlockHash>,
+	) -> RpcResult<Position<NumberOrHex>>;
+
+	/// Deposit and borrowing APY of the market at the given block, best block by default
+	#[method(name = "defi_getApy")]
+	fn get_apy(&self, asset_id: AssetId, at: Option<BlockHash>) -> RpcResult<Apy<NumberOrHex>>;
+
+	/// Health factor of the account at the given block, best block by default. The account
+	/// can be liquidated once it drops below one, `None` if the account has no debt.
+	#[method(name = "defi_getHealthFactor")]
+	fn get_health_factor(
+		&self,
+		who: AccountId,
+		at: Option<BlockHash>,
+	) -> RpcResult<Option<NumberOrHex>>;
+}
+
+/// Serves the defi RPC methods by calling into the runtime
+pub struct Defi<C, Block, Balance> {
+	client: Arc<C>,
+	_marker: std::marker::PhantomData<(Block, Balance)>,
+}
+
+impl<C, Block, Balance> Defi<C, Block, Balance> {
+	/// Create new `Defi` with the given reference to the client.
+	pub fn new(client: Arc<C>) -> Self {
+		Self { client, _marker: Default::default() }
+	}
+}
+
+/// Error type of this RPC api.
+pub enum Error {
+	/// The call to runtime failed.
+	RuntimeError,
+	/// The runtime could not compute the value.
+	DispatchError,
+}
+
+impl From<Error> for i32 {
+	fn from(e: Error) -> i32 {
+		match e {
+			Error::RuntimeError => 1,
+			Error::DispatchError => 2,
+		}
+	}
+}
+
+impl<C, Block, AccountId, AssetId, Balance>
+	DefiApiServer<<Block as BlockT>::Hash, AccountId, AssetId> for Defi<C, Block, Balance>
+where
+	Block: BlockT,
+	C: ProvideRuntimeApi<Block> + HeaderBackend<Block> + Send + Sync + 'static,
+	C::Api: DefiRuntimeApi<Block, AccountId, AssetId, Balance>,
+	AccountId: Codec + Clone + Send + Sync + 'static,
+	AssetId: Codec + Copy + Send + Sync + 'static,
+	Balance: Codec + Into<NumberOrHex> + Send + Sync + 'static,
+{
+	fn get_position(
+		&self,
+		who: AccountId,
+		asset_id: AssetId,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> RpcResult<Position<NumberOrHex>> {
+		let api = self.client.runtime_api();
+		let at_hash = at.unwrap_or_else(|| self.client.info().best_hash);
+
+		Ok(Position {
+			lending_amount: api
+				.get_lending_amount(at_hash, asset_id, who.clone())
+				.map_err(runtime_error)?
+				.into(),
+			held_collateral: api
+				.get_held_collateral(at_hash, asset_id, who.clone())
+				.map_err(runtime_error)?
+				.into(),
+			debt_amount: api
+				.get_debt_amount(at_hash, asset_id, who.clone())
+				.map_err(runtime_error)?
+				.into(),
+			allowed_borrowing_amount: api
+				.get_allowed_borrowing_amount(at_hash, asset_id, who)
+				.map_err(runtime_error)?
+				.into(),
+		})
+	}
+
+	fn get_apy(
+		&self,
+		asset_id: AssetId,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> RpcResult<Apy<NumberOrHex>> {
+		let api = self.client.runtime_api();
+		let at_hash = at.unwrap_or_else(|| self.client.info().best_hash);
+
+		Ok(Apy {
+			deposit_apy: api.get_deposit_apy(at_hash, asset_id).map_err(runtime_error)?.into(),
+			borrowing_apy: api.get_borrowing_apy(at_hash, asset_id).map_err(runtime_error)?.into(),
+		})
+	}
+
+	fn get_health_factor(
+		&self,
+		who: AccountId,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> RpcResult<Option<NumberOrHex>> {
+		let api = self.client.runtime_api();
+		let at_hash = at.unwrap_or_else(|| self.client.info().best_hash);
+
+		let health_factor =
+			api.get_health_factor(at_hash, who).map_err(runtime_error)?.map_err(|e| {
+				CallError::Custom(ErrorObject::owned(
+					Error::DispatchError.into(),
+					"Unable to compute health factor.",
+					Some(format!("{:?}", e)),
+				))
+			})?;
+
+		Ok(health_factor.map(Into::into))
+	}
+}
+
+/// Convert a failed runtime call into an RPC error
+fn runtime_error(e: impl Debug) -> CallError {
+	CallError::Custom(ErrorObject::owned(
+		Error::RuntimeError.into(),
+		"Unable to query the defi runtime api.",
+		Some(format!("{:?}", e)),
+	))
+}
@@ -0,0 +1,42 @@
+//! Runtime API of the defi pallet, used by the `defi_*` RPC methods.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use codec::Codec;
+pub use pallet_defi::Operation;
+use sp_runtime::DispatchError;
+
+sp_api::decl_runtime_apis! {
+	/// Lending positions and rates with interest accrued up to the queried block
+	pub trait DefiApi<AccountId, AssetId, Balance>
+	where
+		AccountId: Codec,
+		AssetId: Codec,
+		Balance: Codec,
+	{
+		/// Deposit balance of the account in the market
+		fn get_lending_amount(asset_id: AssetId, who: AccountId) -> Balance;
+
+		/// Collateral held in the account without being lent out
+		fn get_held_collateral(asset_id: AssetId, who: AccountId) -> Balance;
+
+		/// Debt of the account in the market
+		fn get_debt_amount(asset_id: AssetId, who: AccountId) -> Balance;
+
+		/// Amount the account can still borrow in the market
+		fn get_allowed_borrowing_amount(asset_id: AssetId, who: AccountId) -> Balance;
+
+		/// Deposit APY of the market as a fixed point number with 18 decimals
+		fn get_deposit_apy(asset_id: AssetId) -> Balance;
+
+		/// Borrowing APY of the market as a fixed point number with 18 decimals
+		fn get_borrowing_apy(asset_id: AssetId) -> Balance;
+
+		/// Borrowing power / debt value of the account as a fixed point number with
+		/// 18 decimals, `None` if the account has no debt
+		fn get_health_factor(who: AccountId) -> Result<Option<Balance>, DispatchError>;
+
+		/// Whether the operation is paused by the pause origin
+		fn is_paused(operation: Operation) -> bool;
+	}
+}
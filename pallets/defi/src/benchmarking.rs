@@ -4,18 +4,20 @@ use super::*;
 
 use crate::Pallet as Defi;
 use codec::Decode;
-use frame_benchmarking::benchmarks;
-use frame_support::{sp_runtime::FixedU128, traits::Hooks};
+use frame_benchmarking::{account, benchmarks};
+use frame_support::{
+	sp_runtime::{
+		traits::{One, Zero},
+		FixedU128,
+	},
+	traits::{fungible::Mutate, fungibles, EnsureOrigin, Hooks, OnRuntimeUpgrade, StorageVersion},
+	weights::Weight,
+	BoundedVec,
+};
 use frame_system::{EventRecord, RawOrigin};
 use hex_literal::hex;
 
 // Define helper function
-fn authority<T: Config>() -> T::AccountId {
-	let bytes = hex!("fed77d0df3f5068d8a875e5ae7c3248ba7c602439623cab507206af8e50edd4b");
-
-	T::AccountId::decode(&mut &bytes[..]).unwrap()
-}
-
 fn alice<T: Config>() -> T::AccountId {
 	let bytes = hex!("d43593c715fdd31c61141abd04a99fd6822c8558854ccde39a5684e7a56da27d");
 
@@ -38,6 +40,72 @@ fn assert_last_event<T: Config>(generic_event: <T as Config>::RuntimeEvent) {
 	assert_eq!(event, &system_event);
 }
 
+// Set bounds wide enough for any benchmarked value, so that they're checked
+fn set_wide_bounds<T: Config>(parameter: MarketParameter) {
+	Bounds::<T>::insert(
+		parameter,
+		ParameterBounds {
+			min: FixedU128::from_inner(0),
+			max: FixedU128::one(),
+			max_change: FixedU128::one(),
+		},
+	);
+}
+
+// Check that the parameter change is pending or, without delay, applied
+fn assert_parameter_change<T: Config>(parameter: MarketParameter, value: FixedU128) {
+	let asset_id = T::NativeAssetId::get();
+
+	match PendingParameterChanges::<T>::get(asset_id, parameter) {
+		Some(change) => assert_eq!(change.value, value),
+		None => assert_eq!(Defi::<T>::parameter_value(asset_id, parameter), value),
+	}
+}
+
+// List markets besides the native one, so that `m` markets are listed in total. Every market
+// is priced, so that positions in it are valued.
+fn create_markets<T: Config>(m: u32) {
+	T::BenchmarkHelper::set_price(T::NativeAssetId::get(), FixedU128::one());
+
+	for i in 1..m {
+		let asset_id = T::BenchmarkHelper::asset_id(i);
+
+		if !<T::Assets as fungibles::Inspect<_>>::asset_exists(asset_id) {
+			<T::Assets as fungibles::Create<_>>::create(
+				asset_id,
+				Defi::<T>::account_id(),
+				true,
+				1u32.into(),
+			)
+			.unwrap();
+		}
+		T::BenchmarkHelper::set_price(asset_id, FixedU128::one());
+
+		Defi::<T>::ensure_receipt_asset(asset_id).unwrap();
+		Markets::<T>::insert(asset_id, true);
+	}
+}
+
+// Deposit into each of the `m` markets, so that the account has a position to value in all of
+// them
+fn deposit_into_markets<T: Config>(user: &T::AccountId, m: u32) {
+	T::Currency::set_balance(user, 1_000u32.into());
+	Defi::<T>::deposit(
+		RawOrigin::Signed(user.clone()).into(),
+		T::NativeAssetId::get(),
+		100u32.into(),
+	)
+	.unwrap();
+
+	for i in 1..m {
+		let asset_id = T::BenchmarkHelper::asset_id(i);
+
+		<T::Assets as fungibles::Mutate<_>>::mint_into(asset_id, user, 1_000u32.into()).unwrap();
+		Defi::<T>::deposit(RawOrigin::Signed(user.clone()).into(), asset_id, 100u32.into())
+			.unwrap();
+	}
+}
+
 fn run_to_block<T: Config>(n: u32) {
 	while frame_system::Pallet::<T>::block_number() < n.into() {
 		frame_system::Pallet::<T>::on_finalize(frame_system::Pallet::<T>::block_number().into());
@@ -46,69 +114,78 @@ fn run_to_block<T: Config>(n: u32) {
 		);
 		frame_system::Pallet::<T>::on_initialize(frame_system::Pallet::<T>::block_number().into());
 		Defi::<T>::on_initialize(frame_system::Pallet::<T>::block_number().into());
+		Defi::<T>::on_idle(frame_system::Pallet::<T>::block_number().into(), Weight::MAX);
 	}
 }
 
 benchmarks! {
 	deposit {
+		let m in 1 .. T::MaxMarkets::get();
+
 		let user = alice::<T>();
 		let amount = 1u32;
+
+		// The global supply cap is checked against the deposits in every market
+		create_markets::<T>(m);
+		deposit_into_markets::<T>(&bob::<T>(), m);
+		GlobalSupplyCap::<T>::put(T::Balance::from(1_000_000u32));
 	} : {
 		Defi::<T>::deposit(
 			RawOrigin::Signed(user.clone()).into(),
+			T::NativeAssetId::get(),
 			amount.into(),
 		).unwrap();
 	} verify {
 		let current_block = frame_system::Pallet::<T>::block_number();
 
-		assert_last_event::<T>(Event::<T>::Deposited(user.clone(), amount.into(), current_block).into());
+		assert_last_event::<T>(Event::<T>::Deposited(user.clone(), T::NativeAssetId::get(), amount.into(), current_block).into());
 	}
 
 	withdraw {
+		let m in 1 .. T::MaxMarkets::get();
+
 		let user = alice::<T>();
-		let deposit_amount = 10u32;
 		let withdraw_amount = 5u32;
-	} : {
-		Defi::<T>::deposit(
-			RawOrigin::Signed(user.clone()).into(),
-			deposit_amount.into(),
-		).unwrap();
 
-		let _ =Defi::<T>::withdraw(
+		// A withdrawal of an account with debt is checked against its positions in every market
+		create_markets::<T>(m);
+		run_to_block::<T>(1);
+
+		deposit_into_markets::<T>(&user, m);
+		Defi::<T>::borrow(RawOrigin::Signed(user.clone()).into(), T::NativeAssetId::get(), 5u32.into()).unwrap();
+	} : {
+		let _ = Defi::<T>::withdraw(
 			RawOrigin::Signed(user.clone()).into(),
+			T::NativeAssetId::get(),
 			withdraw_amount.into(),
 		);
 	} verify {
 		let current_block = frame_system::Pallet::<T>::block_number();
 
-		assert_last_event::<T>(Event::<T>::Withdrawn(user.clone(), withdraw_amount.into(), current_block).into());
+		assert_last_event::<T>(Event::<T>::Withdrawn(user.clone(), T::NativeAssetId::get(), withdraw_amount.into(), current_block).into());
 	}
 
 	borrow {
+		let m in 1 .. T::MaxMarkets::get();
+
 		let borrowing_user = alice::<T>();
-		let depositing_user = bob::<T>();
 		let borrowing_amount: u32 = 5;
-		let depositing_amount_1: u32 = 10;
-		let depositing_amount_2: u32 = 100;
-	} : {
-		Defi::<T>::deposit(
-			RawOrigin::Signed(depositing_user.clone()).into(),
-			depositing_amount_2.into(),
-		).unwrap();
 
-		Defi::<T>::deposit(
-			RawOrigin::Signed(borrowing_user.clone()).into(),
-			depositing_amount_1.into(),
-		).unwrap();
+		// The borrowing power is valued across the positions in every market
+		create_markets::<T>(m);
+		run_to_block::<T>(1);
 
+		deposit_into_markets::<T>(&borrowing_user, m);
+	} : {
 		let _ = Defi::<T>::borrow(
 			RawOrigin::Signed(borrowing_user.clone()).into(),
+			T::NativeAssetId::get(),
 			borrowing_amount.into(),
 		);
 	} verify {
 		let current_block = frame_system::Pallet::<T>::block_number();
 
-		assert_last_event::<T>(Event::<T>::Borrowed(borrowing_user.clone(), borrowing_amount.into(), current_block).into());
+		assert_last_event::<T>(Event::<T>::Borrowed(borrowing_user.clone(), T::NativeAssetId::get(), borrowing_amount.into(), current_block).into());
 	}
 
 	repay {
@@ -120,11 +197,13 @@ benchmarks! {
 
 		Defi::<T>::deposit(
 			RawOrigin::Signed(user.clone()).into(),
+			T::NativeAssetId::get(),
 			depositing_amount.into(),
 		).unwrap();
 
 		Defi::<T>::borrow(
 			RawOrigin::Signed(user.clone()).into(),
+			T::NativeAssetId::get(),
 			borrowing_amount.into(),
 		).unwrap();
 
@@ -132,48 +211,422 @@ benchmarks! {
 
 		let _ = Defi::<T>::repay(
 			RawOrigin::Signed(user.clone()).into(),
+			T::NativeAssetId::get(),
 			borrowing_amount.into(),
 		);
 	} verify {
 		let current_block = frame_system::Pallet::<T>::block_number();
 
-		assert_last_event::<T>(Event::<T>::LoanRepaid(user.clone(), borrowing_amount.into(), current_block).into());
+		assert_last_event::<T>(Event::<T>::LoanRepaid(user.clone(), T::NativeAssetId::get(), borrowing_amount.into(), current_block).into());
 	}
 
 	update_deposit_rate {
-		let authority = authority::<T>();
+		let origin = T::AdminOrigin::try_successful_origin().unwrap();
 		let new_rate = FixedU128::from_inner(1);
+
+		set_wide_bounds::<T>(MarketParameter::DepositRate);
 	} : {
 		let _ = Defi::<T>::update_deposit_rate(
-			RawOrigin::Signed(authority).into(),
+			origin,
+			T::NativeAssetId::get(),
 			new_rate,
 		);
 	} verify {
-		assert_last_event::<T>(Event::<T>::DepositRateUpdated(new_rate).into());
+		assert_parameter_change::<T>(MarketParameter::DepositRate, new_rate);
 	}
 
 	update_borrowing_rate {
-		let authority = authority::<T>();
+		let origin = T::AdminOrigin::try_successful_origin().unwrap();
 		let new_rate = FixedU128::from_inner(1);
+
+		set_wide_bounds::<T>(MarketParameter::BorrowingRate);
 	} : {
 		let _ = Defi::<T>::update_borrowing_rate(
-			RawOrigin::Signed(authority).into(),
+			origin,
+			T::NativeAssetId::get(),
 			new_rate,
 		);
 	} verify {
-		assert_last_event::<T>(Event::<T>::BorrowingRateUpdated(new_rate).into());
+		assert_parameter_change::<T>(MarketParameter::BorrowingRate, new_rate);
 	}
 
 	update_collateral_factor {
-		let authority = authority::<T>();
+		let origin = T::AdminOrigin::try_successful_origin().unwrap();
 		let new_factor = FixedU128::from_inner(1);
+
+		set_wide_bounds::<T>(MarketParameter::CollateralFactor);
 	} : {
 		let _ = Defi::<T>::update_collateral_factor(
-			RawOrigin::Signed(authority).into(),
+			origin,
+			T::NativeAssetId::get(),
 			new_factor,
 		);
 	} verify {
-		assert_last_event::<T>(Event::<T>::CollateralFactorUpdated(new_factor).into());
+		assert_parameter_change::<T>(MarketParameter::CollateralFactor, new_factor);
+	}
+
+	create_market {
+		let m in 1 .. T::MaxMarkets::get() - 1;
+
+		let origin = T::AdminOrigin::try_successful_origin().unwrap();
+		let asset_id = T::BenchmarkHelper::asset_id(m);
+		let rate = FixedU128::from_inner(1);
+
+		create_markets::<T>(m);
+	} : {
+		let _ = Defi::<T>::create_market(
+			origin,
+			asset_id,
+			rate,
+			rate,
+			rate,
+		);
+	} verify {
+		assert_last_event::<T>(Event::<T>::MarketCreated(asset_id).into());
+	}
+
+	update_reserve_factor {
+		let origin = T::AdminOrigin::try_successful_origin().unwrap();
+		let new_factor = FixedU128::from_inner(1);
+	} : {
+		let _ = Defi::<T>::update_reserve_factor(
+			origin,
+			T::NativeAssetId::get(),
+			new_factor,
+		);
+	} verify {
+		assert_last_event::<T>(Event::<T>::ReserveFactorUpdated(T::NativeAssetId::get(), new_factor).into());
+	}
+
+	add_reserves {
+		let origin = T::ReserveOrigin::try_successful_origin().unwrap();
+		let who = T::ReserveOrigin::ensure_origin(origin.clone()).unwrap();
+		let amount: u32 = 10;
+
+		T::Currency::set_balance(&who, 100u32.into());
+	} : {
+		Defi::<T>::add_reserves(origin, T::NativeAssetId::get(), amount.into()).unwrap();
+	} verify {
+		assert_last_event::<T>(Event::<T>::ReservesAdded(who, T::NativeAssetId::get(), amount.into()).into());
+	}
+
+	reduce_reserves {
+		let origin = T::ReserveOrigin::try_successful_origin().unwrap();
+		let who = T::ReserveOrigin::ensure_origin(origin.clone()).unwrap();
+		let to = bob::<T>();
+		let amount: u32 = 5;
+
+		T::Currency::set_balance(&who, 100u32.into());
+		Defi::<T>::add_reserves(origin.clone(), T::NativeAssetId::get(), 10u32.into()).unwrap();
+	} : {
+		Defi::<T>::reduce_reserves(origin, T::NativeAssetId::get(), to.clone(), amount.into()).unwrap();
+	} verify {
+		assert_last_event::<T>(Event::<T>::ReservesReduced(to, T::NativeAssetId::get(), amount.into()).into());
+	}
+
+	fund_insurance {
+		let origin = T::ReserveOrigin::try_successful_origin().unwrap();
+		let who = T::ReserveOrigin::ensure_origin(origin.clone()).unwrap();
+		let amount: u32 = 5;
+
+		T::Currency::set_balance(&who, 100u32.into());
+		Defi::<T>::add_reserves(origin.clone(), T::NativeAssetId::get(), 10u32.into()).unwrap();
+	} : {
+		Defi::<T>::fund_insurance(origin, T::NativeAssetId::get(), amount.into()).unwrap();
+	} verify {
+		assert_last_event::<T>(Event::<T>::InsuranceFunded(T::NativeAssetId::get(), amount.into()).into());
+	}
+
+	liquidate {
+		let m in 1 .. T::MaxMarkets::get();
+
+		let borrower = alice::<T>();
+		let liquidator = bob::<T>();
+		let asset_id = T::NativeAssetId::get();
+		let repay_amount: u32 = 1;
+
+		T::Currency::set_balance(&liquidator, 1_000u32.into());
+
+		create_markets::<T>(m);
+		run_to_block::<T>(1);
+
+		deposit_into_markets::<T>(&borrower, m);
+		Defi::<T>::borrow(RawOrigin::Signed(borrower.clone()).into(), asset_id, 50u32.into()).unwrap();
+
+		// Shrink the borrowing power below the debt, the deposits in other markets still being
+		// valued but not counted
+		CollateralFactor::<T>::insert(asset_id, FixedU128::from_rational(1, 4));
+		for i in 1 .. m {
+			CollateralFactor::<T>::insert(T::BenchmarkHelper::asset_id(i), FixedU128::zero());
+		}
+	} : {
+		Defi::<T>::liquidate(
+			RawOrigin::Signed(liquidator.clone()).into(),
+			borrower.clone(),
+			asset_id,
+			asset_id,
+			repay_amount.into(),
+		).unwrap();
+	} verify {
+		assert!(Defi::<T>::get_lending_amount(asset_id, liquidator) > 0u32.into());
+	}
+
+	set_authority {
+		let origin = T::AdminOrigin::try_successful_origin().unwrap();
+		let authority = alice::<T>();
+	} : {
+		let _ = Defi::<T>::set_authority(origin, Some(authority.clone()));
+	} verify {
+		assert_last_event::<T>(Event::<T>::AuthoritySet(Some(authority)).into());
+	}
+
+	set_pause_guardian {
+		let origin = T::AdminOrigin::try_successful_origin().unwrap();
+		let guardian = alice::<T>();
+	} : {
+		let _ = Defi::<T>::set_pause_guardian(origin, Some(guardian.clone()));
+	} verify {
+		assert_last_event::<T>(Event::<T>::PauseGuardianSet(Some(guardian)).into());
+	}
+
+	set_parameter_bounds {
+		let origin = T::AdminOrigin::try_successful_origin().unwrap();
+		let bounds = ParameterBounds {
+			min: FixedU128::from_inner(0),
+			max: FixedU128::one(),
+			max_change: FixedU128::one(),
+		};
+	} : {
+		Defi::<T>::set_parameter_bounds(origin, MarketParameter::CollateralFactor, Some(bounds.clone())).unwrap();
+	} verify {
+		assert_last_event::<T>(Event::<T>::ParameterBoundsSet(MarketParameter::CollateralFactor, Some(bounds)).into());
+	}
+
+	cancel_parameter_change {
+		let origin = T::AdminOrigin::try_successful_origin().unwrap();
+		let asset_id = T::NativeAssetId::get();
+
+		PendingParameterChanges::<T>::insert(
+			asset_id,
+			MarketParameter::BorrowingRate,
+			PendingChange { value: FixedU128::from_inner(1), effective_at: 1u32.into() },
+		);
+	} : {
+		Defi::<T>::cancel_parameter_change(origin, asset_id, MarketParameter::BorrowingRate).unwrap();
+	} verify {
+		assert_last_event::<T>(Event::<T>::ParameterChangeCancelled(asset_id, MarketParameter::BorrowingRate).into());
+	}
+
+	apply_parameter_changes {
+		let n in 0 .. 100;
+
+		let now = frame_system::Pallet::<T>::block_number();
+
+		// Every pending change is due and accrues interest of its market
+		for i in 0 .. n {
+			PendingParameterChanges::<T>::insert(
+				T::BenchmarkHelper::asset_id(i),
+				MarketParameter::BorrowingRate,
+				PendingChange { value: FixedU128::from_inner(1), effective_at: now },
+			);
+		}
+	} : {
+		Defi::<T>::apply_parameter_changes(now);
+	} verify {
+		assert_eq!(PendingParameterChanges::<T>::iter().count(), 0);
+	}
+
+	pause_operation {
+		let origin = T::PauseOrigin::try_successful_origin().unwrap();
+	} : {
+		Defi::<T>::pause_operation(origin, Operation::Borrow).unwrap();
+	} verify {
+		assert_last_event::<T>(Event::<T>::OperationPaused(Operation::Borrow).into());
+	}
+
+	unpause_operation {
+		let origin = T::PauseOrigin::try_successful_origin().unwrap();
+
+		PausedOperations::<T>::insert(Operation::Borrow, true);
+	} : {
+		Defi::<T>::unpause_operation(origin, Operation::Borrow).unwrap();
+	} verify {
+		assert_last_event::<T>(Event::<T>::OperationUnpaused(Operation::Borrow).into());
+	}
+
+	set_caps {
+		let origin = T::AdminOrigin::try_successful_origin().unwrap();
+		let cap = 1_000u32;
+	} : {
+		Defi::<T>::set_caps(origin, T::NativeAssetId::get(), Some(cap.into()), Some(cap.into())).unwrap();
+	} verify {
+		assert_last_event::<T>(Event::<T>::CapsSet(T::NativeAssetId::get(), Some(cap.into()), Some(cap.into())).into());
+	}
+
+	set_global_caps {
+		let origin = T::AdminOrigin::try_successful_origin().unwrap();
+		let cap = 1_000u32;
+	} : {
+		Defi::<T>::set_global_caps(origin, Some(cap.into()), Some(cap.into())).unwrap();
+	} verify {
+		assert_last_event::<T>(Event::<T>::GlobalCapsSet(Some(cap.into()), Some(cap.into())).into());
+	}
+
+	set_liquidity_buffer {
+		let origin = T::AdminOrigin::try_successful_origin().unwrap();
+		let buffer = FixedU128::from_rational(10, 100);
+	} : {
+		Defi::<T>::set_liquidity_buffer(origin, T::NativeAssetId::get(), buffer).unwrap();
+	} verify {
+		assert_last_event::<T>(Event::<T>::LiquidityBufferSet(T::NativeAssetId::get(), buffer).into());
+	}
+
+	flash_loan {
+		let user = alice::<T>();
+		let depositor = bob::<T>();
+		let asset_id = T::NativeAssetId::get();
+		let amount: u32 = 100;
+
+		T::Currency::set_balance(&user, 1_000u32.into());
+		T::Currency::set_balance(&depositor, 1_000u32.into());
+
+		run_to_block::<T>(1);
+
+		Defi::<T>::deposit(RawOrigin::Signed(depositor).into(), asset_id, 500u32.into()).unwrap();
+
+		let call: <T as Config>::RuntimeCall = frame_system::Call::<T>::remark { remark: vec![] }.into();
+	} : {
+		Defi::<T>::flash_loan(
+			RawOrigin::Signed(user.clone()).into(),
+			asset_id,
+			amount.into(),
+			Box::new(call),
+		).unwrap();
+	} verify {
+		let fee: u32 = 0;
+
+		assert_last_event::<T>(Event::<T>::FlashLoanRepaid(user, asset_id, amount.into(), fee.into()).into());
+	}
+
+	set_flash_loan_fee {
+		let origin = T::AdminOrigin::try_successful_origin().unwrap();
+		let fee = FixedU128::from_rational(1, 100);
+	} : {
+		Defi::<T>::set_flash_loan_fee(origin, T::NativeAssetId::get(), fee).unwrap();
+	} verify {
+		assert_last_event::<T>(Event::<T>::FlashLoanFeeSet(T::NativeAssetId::get(), fee).into());
+	}
+
+	check_liquidity {
+		let n in 0 .. 100;
+		let m in 1 .. T::MaxMarkets::get();
+
+		let asset_id = T::NativeAssetId::get();
+		let debt = 200 * m;
+
+		// Every checked position is valued across `m` markets
+		create_markets::<T>(m);
+
+		// Every checked position owes more than its deposits, so its debt is written off and its
+		// deposit in every market is absorbed
+		for i in 0 .. n {
+			let user: T::AccountId = account("user", i, 0);
+
+			deposit_into_markets::<T>(&user, m);
+			Defi::<T>::increase_debt(asset_id, &user, debt.into());
+		}
+	} : {
+		Defi::<T>::check_liquidity(n);
+	} verify {
+		assert!(LiquidationCursor::<T>::get().is_some() || n == 0);
+		assert_eq!(BadDebt::<T>::get(asset_id), T::Balance::from(debt * n));
+	}
+
+	migrate_positions {
+		let n in 0 .. 100;
+
+		// Every migrated position has a deposit and a debt to convert
+		StorageVersion::new(0).put::<Defi<T>>();
+		for i in 0 .. n {
+			let user: T::AccountId = account("user", i, 0);
+
+			migrations::v0::Accounts::<T>::insert(
+				user,
+				migrations::v0::AddressInfo {
+					deposit_principal: 100u32.into(),
+					deposit_date: Zero::zero(),
+					borrow_principal: 50u32.into(),
+					borrow_date: Zero::zero(),
+				},
+			);
+		}
+		migrations::v1::MigrateToV1::<T>::on_runtime_upgrade();
+	} : {
+		// Converted entries may be visited again after the old ones
+		migrations::v1::migrate_accounts::<T>(2 * n + 1);
+	} verify {
+		assert!(AccountsMigration::<T>::get().is_none());
+	}
+
+	migrate_accounts {
+		let n in 0 .. 100;
+
+		let asset_id = T::NativeAssetId::get();
+
+		// Every migrated position is keyed by the raw account id
+		for i in 0 .. n {
+			let user: T::AccountId = account("user", i, 0);
+
+			migrations::v2::OldAccounts::<T>::insert(
+				asset_id,
+				user,
+				AddressInfo { borrow_principal: 50u32.into(), borrow_index: FixedU128::one() },
+			);
+		}
+		AccountsMigration::<T>::put(BoundedVec::default());
+	} : {
+		// Moved entries may be visited again after the old ones
+		migrations::v2::migrate_accounts::<T>(2 * n + 1);
+	} verify {
+		assert!(AccountsMigration::<T>::get().is_none());
+	}
+
+	deposit_collateral {
+		let user = alice::<T>();
+		let amount: u32 = 10;
+
+		T::Currency::set_balance(&user, 1_000u32.into());
+	} : {
+		Defi::<T>::deposit_collateral(
+			RawOrigin::Signed(user.clone()).into(),
+			T::NativeAssetId::get(),
+			amount.into(),
+		).unwrap();
+	} verify {
+		assert_last_event::<T>(Event::<T>::CollateralDeposited(user, T::NativeAssetId::get(), amount.into()).into());
+	}
+
+	withdraw_collateral {
+		let m in 1 .. T::MaxMarkets::get();
+
+		let user = alice::<T>();
+		let amount: u32 = 5;
+
+		// A withdrawal of an account with debt is checked against its positions in every market
+		create_markets::<T>(m);
+		run_to_block::<T>(1);
+
+		deposit_into_markets::<T>(&user, m);
+		Defi::<T>::deposit_collateral(RawOrigin::Signed(user.clone()).into(), T::NativeAssetId::get(), 10u32.into()).unwrap();
+		Defi::<T>::borrow(RawOrigin::Signed(user.clone()).into(), T::NativeAssetId::get(), 5u32.into()).unwrap();
+	} : {
+		Defi::<T>::withdraw_collateral(
+			RawOrigin::Signed(user.clone()).into(),
+			T::NativeAssetId::get(),
+			amount.into(),
+		).unwrap();
+	} verify {
+		assert_last_event::<T>(Event::<T>::CollateralWithdrawn(user, T::NativeAssetId::get(), amount.into()).into());
 	}
 
 	impl_benchmark_test_suite!(
@@ -181,4 +634,4 @@ benchmarks! {
 		crate::mock::ExtBuilder::default().build(),
 		crate::mock::Runtime,
 	);
-}
\ No newline at end of file
+}
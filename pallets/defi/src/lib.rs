@@ -1,6 +1,15 @@
 #![cfg_attr(not(feature = "std"), no_std)]
 
+use codec::MaxEncodedLen;
 pub use codec::{Decode, Encode};
+pub use defi_primitives::{BenchmarkHelper, PriceOracle};
+use frame_support::{
+	sp_runtime::{
+		traits::One, FixedU128, KeyTypeId, RuntimeDebug, SaturatedConversion, Saturating,
+	},
+	sp_std::marker::PhantomData,
+	traits::{EnsureOrigin, Get, UnixTime},
+};
 pub use pallet::*;
 
 #[cfg(test)]
@@ -11,81 +20,475 @@ mod tests;
 
 mod benchmarking;
 
+pub mod migrations;
 pub mod weights;
 pub use weights::WeightInfo;
 
-#[derive(Encode, Decode, Default, PartialEq, Eq, scale_info::TypeInfo)]
+/// Number of seconds in a year of 365 days
+pub const SECONDS_PER_YEAR: u32 = 31_536_000;
+
+/// Key type of the accounts signing liquidations submitted by the offchain worker
+pub const KEY_TYPE: KeyTypeId = KeyTypeId(*b"defi");
+
+/// Crypto used by the offchain worker to sign liquidations. Liquidator keys are inserted into
+/// the node keystore under `KEY_TYPE`.
+pub mod crypto {
+	use super::KEY_TYPE;
+	use frame_support::sp_runtime::{
+		app_crypto::{app_crypto, sr25519},
+		MultiSignature, MultiSigner,
+	};
+
+	app_crypto!(sr25519, KEY_TYPE);
+
+	pub struct LiquidatorAuthId;
+
+	impl frame_system::offchain::AppCrypto<MultiSigner, MultiSignature> for LiquidatorAuthId {
+		type RuntimeAppPublic = Public;
+		type GenericSignature = sr25519::Signature;
+		type GenericPublic = sr25519::Public;
+	}
+}
+
+#[derive(Encode, Decode, Default, PartialEq, Eq, MaxEncodedLen, scale_info::TypeInfo)]
 #[cfg_attr(feature = "std", derive(Debug))]
-pub struct AddressInfo<Balance, BlockNumber> {
-	/// The deposit balance of the account after last adjustment
-	deposit_principal: Balance,
-	/// The time (block height) at which the deposit balance was last adjusted
-	deposit_date: BlockNumber,
+pub struct AddressInfo<Balance> {
 	/// The borrowing balance of the account after last adjustment
 	borrow_principal: Balance,
-	/// The time (block height) at which the borrowing balance was last adjusted
-	borrow_date: BlockNumber,
+	/// The market borrow index at which the borrowing balance was last adjusted
+	borrow_index: FixedU128,
+}
+
+/// Indexes and totals of a market accrued up to a block
+#[derive(Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "std", derive(Debug))]
+pub struct MarketState<Balance> {
+	/// Accumulated borrowing interest of the market
+	pub borrow_index: FixedU128,
+	/// Accumulated deposit interest of the market
+	pub supply_index: FixedU128,
+	/// Total debt of the market
+	pub total_borrows: Balance,
+	/// Total deposit balance of the market
+	pub total_deposits: Balance,
+	/// Share of the interest kept by the protocol
+	pub total_reserves: Balance,
+}
+
+/// User operation which can be paused during an incident
+#[derive(
+	Encode, Decode, Clone, Copy, PartialEq, Eq, RuntimeDebug, MaxEncodedLen, scale_info::TypeInfo,
+)]
+pub enum Operation {
+	/// Depositing funds into a market, lent out or held as collateral only
+	Deposit,
+	/// Withdrawing deposited funds or releasing held collateral
+	Withdraw,
+	/// Borrowing from a market
+	Borrow,
+	/// Repaying a loan
+	Repay,
+	/// Liquidating undercollateralized borrowers, signed or submitted by the offchain worker
+	Liquidate,
+	/// Borrowing without collateral for the duration of a single call
+	FlashLoan,
+}
+
+/// Market parameter which changes within its bounds and after `ParameterChangeDelay`
+#[derive(
+	Encode, Decode, Clone, Copy, PartialEq, Eq, RuntimeDebug, MaxEncodedLen, scale_info::TypeInfo,
+)]
+pub enum MarketParameter {
+	/// Rate earned by depositors per period of the accrual clock
+	DepositRate,
+	/// Rate paid by borrowers per period of the accrual clock
+	BorrowingRate,
+	/// Share of a deposit which counts towards the borrowing power
+	CollateralFactor,
+}
+
+/// Limits of a market parameter set by governance
+#[derive(
+	Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug, MaxEncodedLen, scale_info::TypeInfo,
+)]
+pub struct ParameterBounds {
+	/// Lowest allowed value
+	pub min: FixedU128,
+	/// Highest allowed value
+	pub max: FixedU128,
+	/// Largest allowed difference between the current and the new value
+	pub max_change: FixedU128,
+}
+
+/// Parameter change waiting for its delay to pass
+#[derive(
+	Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug, MaxEncodedLen, scale_info::TypeInfo,
+)]
+pub struct PendingChange<BlockNumber> {
+	/// Value the parameter is set to
+	pub value: FixedU128,
+	/// Block at which the change is applied
+	pub effective_at: BlockNumber,
+}
+
+/// Liquidation submitted by the offchain worker, signed with the liquidator's key
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug, scale_info::TypeInfo)]
+pub struct LiquidationPayload<Public, AccountId, AssetId, Balance, BlockNumber> {
+	/// Key of the liquidator repaying the debt
+	pub public: Public,
+	/// Undercollateralized account being liquidated
+	pub borrower: AccountId,
+	/// Market in which the debt is repaid
+	pub debt_asset_id: AssetId,
+	/// Market from which the collateral is seized
+	pub collateral_asset_id: AssetId,
+	/// Amount of debt repaid by the liquidator
+	pub repay_amount: Balance,
+	/// Block at which the liquidation was found
+	pub block_number: BlockNumber,
+	/// Nonce of the liquidator's liquidations, each of them is executed only once
+	pub nonce: u32,
+}
+
+/// Length of the longest raw storage key of `Accounts`, which bounds the liquidation cursor
+pub struct MaxAccountsKeyLen<T>(PhantomData<T>);
+
+impl<T: Config> Get<u32> for MaxAccountsKeyLen<T> {
+	fn get() -> u32 {
+		// Pallet and storage prefix hashes followed by both keys, the account id with its hash
+		(32 + T::AssetId::max_encoded_len() + 16 + T::AccountId::max_encoded_len()) as u32
+	}
+}
+
+/// Ensures the origin is signed by the account stored in `PauseGuardian`
+pub struct EnsurePauseGuardian<T>(PhantomData<T>);
+
+impl<T: Config> EnsureOrigin<T::RuntimeOrigin> for EnsurePauseGuardian<T> {
+	type Success = T::AccountId;
+
+	fn try_origin(o: T::RuntimeOrigin) -> Result<Self::Success, T::RuntimeOrigin> {
+		o.into().and_then(|o| match o {
+			frame_system::RawOrigin::Signed(who)
+				if PauseGuardian::<T>::get().as_ref() == Some(&who) =>
+				Ok(who),
+			r => Err(T::RuntimeOrigin::from(r)),
+		})
+	}
+
+	#[cfg(feature = "runtime-benchmarks")]
+	fn try_successful_origin() -> Result<T::RuntimeOrigin, ()> {
+		PauseGuardian::<T>::get()
+			.map(|who| frame_system::RawOrigin::Signed(who).into())
+			.ok_or(())
+	}
+}
+
+/// Measures the time interest is accrued over. Market rates are expressed per period of the
+/// clock.
+pub trait AccrualClock {
+	/// Current time in periods
+	fn now() -> u64;
+	/// Number of periods in a year, used to annualise rates
+	fn periods_per_year() -> u32;
+}
+
+/// Accrues interest per block, assuming `BlocksPerYear` blocks are produced in a year
+pub struct BlockClock<T, BlocksPerYear>(PhantomData<(T, BlocksPerYear)>);
+
+impl<T: frame_system::Config, BlocksPerYear: Get<u32>> AccrualClock
+	for BlockClock<T, BlocksPerYear>
+{
+	fn now() -> u64 {
+		frame_system::Pallet::<T>::block_number().saturated_into()
+	}
+
+	fn periods_per_year() -> u32 {
+		BlocksPerYear::get()
+	}
+}
+
+/// Accrues interest per second of the given time source, regardless of block production
+pub struct TimestampClock<Time>(PhantomData<Time>);
+
+impl<Time: UnixTime> AccrualClock for TimestampClock<Time> {
+	fn now() -> u64 {
+		Time::now().as_secs()
+	}
+
+	fn periods_per_year() -> u32 {
+		SECONDS_PER_YEAR
+	}
+}
+
+/// Derives interest rates of a market per period of the accrual clock from its utilization
+pub trait InterestRateModel<AssetId> {
+	/// Rate paid by borrowers per period
+	fn borrow_rate(asset_id: &AssetId, utilization: FixedU128) -> FixedU128;
+	/// Rate earned by depositors per period, net of the reserve factor
+	fn supply_rate(
+		asset_id: &AssetId,
+		utilization: FixedU128,
+		reserve_factor: FixedU128,
+	) -> FixedU128;
+}
+
+/// Uses the rates set by the authority in `DepositRate` and `BorrowingRate`, regardless of
+/// utilization
+pub struct FixedRateModel<T>(PhantomData<T>);
+
+impl<T: Config> InterestRateModel<T::AssetId> for FixedRateModel<T> {
+	fn borrow_rate(asset_id: &T::AssetId, _utilization: FixedU128) -> FixedU128 {
+		BorrowingRate::<T>::get(asset_id)
+	}
+
+	fn supply_rate(
+		asset_id: &T::AssetId,
+		_utilization: FixedU128,
+		_reserve_factor: FixedU128,
+	) -> FixedU128 {
+		DepositRate::<T>::get(asset_id)
+	}
+}
+
+/// Borrow rate grows with utilization by `Multiplier` up to `Kink` and by `JumpMultiplier`
+/// above it. Depositors receive the interest paid by borrowers minus the reserve factor share.
+pub struct JumpRateModel<BaseRate, Multiplier, Kink, JumpMultiplier>(
+	PhantomData<(BaseRate, Multiplier, Kink, JumpMultiplier)>,
+);
+
+impl<AssetId, BaseRate, Multiplier, Kink, JumpMultiplier> InterestRateModel<AssetId>
+	for JumpRateModel<BaseRate, Multiplier, Kink, JumpMultiplier>
+where
+	BaseRate: Get<FixedU128>,
+	Multiplier: Get<FixedU128>,
+	Kink: Get<FixedU128>,
+	JumpMultiplier: Get<FixedU128>,
+{
+	fn borrow_rate(_asset_id: &AssetId, utilization: FixedU128) -> FixedU128 {
+		let kink = Kink::get();
+
+		if utilization <= kink {
+			BaseRate::get().saturating_add(utilization.saturating_mul(Multiplier::get()))
+		} else {
+			BaseRate::get()
+				.saturating_add(kink.saturating_mul(Multiplier::get()))
+				.saturating_add(
+					utilization.saturating_sub(kink).saturating_mul(JumpMultiplier::get()),
+				)
+		}
+	}
+
+	fn supply_rate(
+		asset_id: &AssetId,
+		utilization: FixedU128,
+		reserve_factor: FixedU128,
+	) -> FixedU128 {
+		Self::borrow_rate(asset_id, utilization)
+			.saturating_mul(utilization)
+			.saturating_mul(FixedU128::one().saturating_sub(reserve_factor))
+	}
 }
 
-#[frame_support::pallet(dev_mode)]
+#[frame_support::pallet]
 pub mod pallet {
 	use super::*;
 	use crate::WeightInfo;
 	use frame_support::{
+		dispatch::{GetDispatchInfo, PostDispatchInfo},
 		pallet_prelude::*,
 		sp_runtime::{
-			traits::{AccountIdConversion, One, Zero},
-			FixedU128, SaturatedConversion, Saturating,
+			traits::{
+				AccountIdConversion, CheckedDiv, Convert, Dispatchable, IdentifyAccount,
+				MaybeSerializeDeserialize, Zero,
+			},
+			transaction_validity::{
+				InvalidTransaction, TransactionLongevity, TransactionPriority, TransactionSource,
+				TransactionValidity, TransactionValidityError, ValidTransaction,
+			},
+			SaturatedConversion, Saturating,
+		},
+		sp_std::{collections::btree_set::BTreeSet, prelude::*},
+		traits::{
+			fungible, fungibles,
+			tokens::{self, Fortitude, Precision, Preservation, Restriction},
+			Contains,
 		},
-		traits::{Currency, ExistenceRequirement, ReservableCurrency},
 		transactional, PalletId,
 	};
-	use frame_system::pallet_prelude::*;
-	use hex_literal::hex;
+	use frame_system::{
+		offchain::{
+			AppCrypto, SendTransactionTypes, SendUnsignedTransaction, SignedPayload, Signer,
+			SigningTypes,
+		},
+		pallet_prelude::*,
+	};
 
 	const PALLET_ID: PalletId = PalletId(*b"defipllt");
 
 	/// Configure the pallet by specifying the parameters and types on which it depends.
 	#[pallet::config]
-	pub trait Config: frame_system::Config {
+	pub trait Config:
+		frame_system::Config + SigningTypes + SendTransactionTypes<Call<Self>>
+	{
 		/// Because this pallet emits events, it depends on the runtime's definition of an event.
 		type RuntimeEvent: From<Event<Self>> + IsType<<Self as frame_system::Config>::RuntimeEvent>;
 
-		/// The currency in which deposit/borrowing work
-		type Currency: ReservableCurrency<Self::AccountId>;
+		/// The overarching call type, dispatched with the funds of a flash loan
+		type RuntimeCall: Parameter
+			+ Dispatchable<RuntimeOrigin = Self::RuntimeOrigin, PostInfo = PostDispatchInfo>
+			+ GetDispatchInfo
+			+ From<frame_system::Call<Self>>
+			+ IsType<<Self as frame_system::Config>::RuntimeCall>;
+
+		/// Calls allowed to be dispatched with a flash loan. It must reject the calls of this
+		/// pallet, which would otherwise run against the lent funds.
+		type CallFilter: Contains<<Self as Config>::RuntimeCall>;
+
+		/// Balance of the native currency and the assets
+		type Balance: tokens::Balance + MaybeSerializeDeserialize;
+
+		/// The native currency in which deposit/borrowing work, holding collateral-only
+		/// deposits in the depositor's account
+		type Currency: fungible::Inspect<Self::AccountId, Balance = Self::Balance>
+			+ fungible::Mutate<Self::AccountId>
+			+ fungible::MutateHold<Self::AccountId, Reason = Self::RuntimeHoldReason>;
+
+		/// The overarching hold reason
+		type RuntimeHoldReason: From<HoldReason>;
+
+		/// Identifier of the assets which can be listed as lending markets
+		type AssetId: Member + Parameter + Copy + MaxEncodedLen + MaybeSerializeDeserialize;
+
+		/// The non-native assets in which deposit/borrowing work and receipt tokens are minted
+		type Assets: fungibles::Inspect<Self::AccountId, AssetId = Self::AssetId, Balance = BalanceOf<Self>>
+			+ fungibles::Mutate<Self::AccountId>
+			+ fungibles::Create<Self::AccountId>;
+
+		/// Asset id of the receipt token created for a market, which must be out of the range
+		/// of regular assets
+		type ReceiptAssetId: Convert<Self::AssetId, Self::AssetId>;
+
+		/// Asset id under which the native currency market is listed
+		#[pallet::constant]
+		type NativeAssetId: Get<Self::AssetId>;
+
+		/// Source of asset prices used to value collateral and debt across markets
+		type PriceOracle: PriceOracle<Self::AssetId>;
+
+		/// Model deriving deposit and borrowing rates of a market from its utilization
+		type InterestRateModel: InterestRateModel<Self::AssetId>;
+
+		/// Origin allowed to update market parameters and set the authority account
+		type AdminOrigin: EnsureOrigin<Self::RuntimeOrigin>;
+
+		/// Origin allowed to add and reduce reserves, resolving to the account paying for
+		/// added reserves
+		type ReserveOrigin: EnsureOrigin<Self::RuntimeOrigin, Success = Self::AccountId>;
+
+		/// Origin allowed to pause and unpause user operations
+		type PauseOrigin: EnsureOrigin<Self::RuntimeOrigin>;
+
+		/// Maximum share of a borrower's debt in a market which can be repaid in a single
+		/// liquidation
+		#[pallet::constant]
+		type CloseFactor: Get<FixedU128>;
+
+		/// Bonus share of collateral a liquidator receives on top of the repaid value
+		#[pallet::constant]
+		type LiquidationIncentive: Get<FixedU128>;
 
-		/// Number of blocks on yearly basis
-		type NumberOfBlocksYearly: Get<u32>;
+		/// Maximum number of listed markets, including the native one. Borrowing power is
+		/// valued across every market, so the number of markets bounds the weight of user
+		/// operations.
+		#[pallet::constant]
+		type MaxMarkets: Get<u32>;
+
+		/// Maximum number of positions checked for liquidation in a single block
+		#[pallet::constant]
+		type MaxLiquidationChecksPerBlock: Get<u32>;
+
+		/// Maximum number of `Accounts` entries visited by a storage migration in a single block
+		#[pallet::constant]
+		type MaxAccountsMigratedPerBlock: Get<u32>;
+
+		/// Crypto of the liquidator keys used by the offchain worker
+		type AuthorityId: AppCrypto<Self::Public, Self::Signature>;
+
+		/// Maximum number of liquidations submitted by the offchain worker in a single block
+		#[pallet::constant]
+		type MaxUnsignedLiquidations: Get<u32>;
+
+		/// Priority of liquidations submitted by the offchain worker
+		#[pallet::constant]
+		type UnsignedPriority: Get<TransactionPriority>;
+
+		/// Number of blocks a liquidation submitted by the offchain worker stays valid
+		#[pallet::constant]
+		type UnsignedLongevity: Get<TransactionLongevity>;
+
+		/// Number of blocks a change of a market parameter waits before it's applied, giving
+		/// users time to adjust their positions. Changes are applied immediately when zero.
+		#[pallet::constant]
+		type ParameterChangeDelay: Get<BlockNumberFor<Self>>;
+
+		/// Clock interest is accrued by, market rates are expressed per period of the clock
+		type AccrualClock: AccrualClock;
 
 		/// Extrinsics weight Info
 		type WeightInfo: WeightInfo;
+
+		/// Helper for creating asset ids in benchmarks
+		#[cfg(feature = "runtime-benchmarks")]
+		type BenchmarkHelper: BenchmarkHelper<Self::AssetId>;
 	}
 
 	type AccountIdOf<T> = <T as frame_system::Config>::AccountId;
-	type BalanceOf<T> = <<T as Config>::Currency as Currency<AccountIdOf<T>>>::Balance;
+	type BalanceOf<T> = <T as Config>::Balance;
 	type BlockNumber<T> = BlockNumberFor<T>;
+	type AssetIdOf<T> = <T as Config>::AssetId;
+	pub type LiquidationPayloadOf<T> = LiquidationPayload<
+		<T as SigningTypes>::Public,
+		AccountIdOf<T>,
+		AssetIdOf<T>,
+		BalanceOf<T>,
+		BlockNumber<T>,
+	>;
+
+	impl<T: Config> SignedPayload<T> for LiquidationPayloadOf<T> {
+		fn public(&self) -> T::Public {
+			self.public.clone()
+		}
+	}
+
+	/// The current storage version
+	const STORAGE_VERSION: StorageVersion = StorageVersion::new(3);
 
 	#[pallet::pallet]
-	#[pallet::without_storage_info]
+	#[pallet::storage_version(STORAGE_VERSION)]
 	pub struct Pallet<T>(_);
 
-	// Default authority account
+	// Deposit rate default value, about 4.88% a year
 	#[pallet::type_value]
-	pub fn DefaultForAuthorityAccount<T: Config>() -> AccountIdOf<T> {
-		let bytes = hex!("fed77d0df3f5068d8a875e5ae7c3248ba7c602439623cab507206af8e50edd4b");
-		AccountIdOf::<T>::decode(&mut &bytes[..]).unwrap()
+	pub fn DefaultDepositRate<T: Config>() -> FixedU128 {
+		FixedU128::from_rational(
+			487_903_968u128,
+			10_000_000_000u128.saturating_mul(T::AccrualClock::periods_per_year().into()),
+		)
 	}
 
-	// Deposit rate default value
+	// Borrowing rate default value, about 6.77% a year
 	#[pallet::type_value]
-	pub fn DefaultDepositRate<T: Config>() -> FixedU128 {
-		FixedU128::from_inner(92828) / FixedU128::from_inner(10000000000000)
+	pub fn DefaultBorrowingRate<T: Config>() -> FixedU128 {
+		FixedU128::from_rational(
+			676_589_112u128,
+			10_000_000_000u128.saturating_mul(T::AccrualClock::periods_per_year().into()),
+		)
 	}
 
-	// Borrowing rate default value
+	// Borrow and supply index initial value
 	#[pallet::type_value]
-	pub fn DefaultBorrowingRate<T: Config>() -> FixedU128 {
-		FixedU128::from_inner(128727) / FixedU128::from_inner(10000000000000)
+	pub fn DefaultIndex<T: Config>() -> FixedU128 {
+		FixedU128::one()
 	}
 
 	// Collateral factor default value
@@ -94,54 +497,375 @@ pub mod pallet {
 		FixedU128::from_inner(75) / FixedU128::from_inner(100)
 	}
 
+	// Flash loan fee default value
+	#[pallet::type_value]
+	pub fn DefaultFlashLoanFee<T: Config>() -> FixedU128 {
+		FixedU128::from_inner(9) / FixedU128::from_inner(10000)
+	}
+
+	/// Account allowed to update market parameters besides `AdminOrigin`. Unset by default.
 	#[pallet::storage]
 	#[pallet::getter(fn authority_account)]
-	pub type AuthorityAccount<T: Config> =
-		StorageValue<_, AccountIdOf<T>, ValueQuery, DefaultForAuthorityAccount<T>>;
+	pub type AuthorityAccount<T: Config> = StorageValue<_, AccountIdOf<T>, OptionQuery>;
+
+	/// Account allowed to pause and unpause user operations besides `AdminOrigin`. Unset by
+	/// default.
+	#[pallet::storage]
+	#[pallet::getter(fn pause_guardian)]
+	pub type PauseGuardian<T: Config> = StorageValue<_, AccountIdOf<T>, OptionQuery>;
+
+	/// Listed non-native markets. The native market is always listed.
+	#[pallet::storage]
+	#[pallet::getter(fn markets)]
+	pub type Markets<T: Config> = StorageMap<_, Identity, AssetIdOf<T>, bool, ValueQuery>;
 
 	#[pallet::storage]
 	#[pallet::getter(fn deposit_rate)]
-	pub type DepositRate<T: Config> = StorageValue<_, FixedU128, ValueQuery, DefaultDepositRate<T>>;
+	pub type DepositRate<T: Config> =
+		StorageMap<_, Identity, AssetIdOf<T>, FixedU128, ValueQuery, DefaultDepositRate<T>>;
 
 	#[pallet::storage]
 	#[pallet::getter(fn borrowing_rate)]
 	pub type BorrowingRate<T: Config> =
-		StorageValue<_, FixedU128, ValueQuery, DefaultBorrowingRate<T>>;
+		StorageMap<_, Identity, AssetIdOf<T>, FixedU128, ValueQuery, DefaultBorrowingRate<T>>;
 
 	#[pallet::storage]
 	#[pallet::getter(fn collateral_factor)]
 	pub type CollateralFactor<T: Config> =
-		StorageValue<_, FixedU128, ValueQuery, DefaultCollateralFactor<T>>;
+		StorageMap<_, Identity, AssetIdOf<T>, FixedU128, ValueQuery, DefaultCollateralFactor<T>>;
+
+	/// Total debt of each market including interest accrued up to `LastAccrued`
+	#[pallet::storage]
+	#[pallet::getter(fn total_borrows)]
+	pub type TotalBorrows<T: Config> =
+		StorageMap<_, Identity, AssetIdOf<T>, BalanceOf<T>, ValueQuery>;
+
+	/// Share of the borrowing interest of each market which goes to reserves
+	#[pallet::storage]
+	#[pallet::getter(fn reserve_factor)]
+	pub type ReserveFactor<T: Config> =
+		StorageMap<_, Identity, AssetIdOf<T>, FixedU128, ValueQuery>;
+
+	/// Total deposit balance of each market including interest accrued up to `LastAccrued`
+	#[pallet::storage]
+	#[pallet::getter(fn total_deposits)]
+	pub type TotalDeposits<T: Config> =
+		StorageMap<_, Identity, AssetIdOf<T>, BalanceOf<T>, ValueQuery>;
+
+	/// Reserve factor share of the interest and seized collateral kept by the protocol in each
+	/// market
+	#[pallet::storage]
+	#[pallet::getter(fn total_reserves)]
+	pub type TotalReserves<T: Config> =
+		StorageMap<_, Identity, AssetIdOf<T>, BalanceOf<T>, ValueQuery>;
+
+	/// Reserves of each market set aside to cover bad debt before it's socialised
+	#[pallet::storage]
+	#[pallet::getter(fn insurance_fund)]
+	pub type InsuranceFund<T: Config> =
+		StorageMap<_, Identity, AssetIdOf<T>, BalanceOf<T>, ValueQuery>;
+
+	/// Total debt of insolvent accounts written off in each market
+	#[pallet::storage]
+	#[pallet::getter(fn bad_debt)]
+	pub type BadDebt<T: Config> = StorageMap<_, Identity, AssetIdOf<T>, BalanceOf<T>, ValueQuery>;
+
+	/// Bad debt of each market left once the insurance fund and the deposits were exhausted,
+	/// which the pallet doesn't hold the funds for
+	#[pallet::storage]
+	#[pallet::getter(fn uncovered_bad_debt)]
+	pub type UncoveredBadDebt<T: Config> =
+		StorageMap<_, Identity, AssetIdOf<T>, BalanceOf<T>, ValueQuery>;
+
+	/// Accumulated borrowing interest of each market since its creation
+	#[pallet::storage]
+	#[pallet::getter(fn borrow_index)]
+	pub type BorrowIndex<T: Config> =
+		StorageMap<_, Identity, AssetIdOf<T>, FixedU128, ValueQuery, DefaultIndex<T>>;
+
+	/// Accumulated deposit interest of each market since its creation
+	#[pallet::storage]
+	#[pallet::getter(fn supply_index)]
+	pub type SupplyIndex<T: Config> =
+		StorageMap<_, Identity, AssetIdOf<T>, FixedU128, ValueQuery, DefaultIndex<T>>;
+
+	/// Time of the accrual clock up to which the indexes of each market were accrued
+	#[pallet::storage]
+	#[pallet::getter(fn last_accrued)]
+	pub type LastAccrued<T: Config> = StorageMap<_, Identity, AssetIdOf<T>, u64, ValueQuery>;
+
+	/// Receipt token of each market. Its balance is the account's deposit and its exchange rate
+	/// to the underlying asset is the market supply index.
+	#[pallet::storage]
+	#[pallet::getter(fn receipt_assets)]
+	pub type ReceiptAssets<T: Config> =
+		StorageMap<_, Identity, AssetIdOf<T>, AssetIdOf<T>, OptionQuery>;
+
+	/// Set only while the pallet burns receipt tokens itself, which isn't limited by the freeze
+	/// of the receipt tokens backing debt
+	#[pallet::storage]
+	pub(super) type ReceiptsUnfrozen<T: Config> = StorageValue<_, bool, ValueQuery>;
 
 	#[pallet::storage]
 	#[pallet::getter(fn accounts)]
-	pub(super) type Accounts<T: Config> = StorageMap<
+	pub(super) type Accounts<T: Config> = StorageDoubleMap<
 		_,
 		Identity,
+		AssetIdOf<T>,
+		Blake2_128Concat,
 		AccountIdOf<T>,
-		AddressInfo<BalanceOf<T>, BlockNumber<T>>,
+		AddressInfo<BalanceOf<T>>,
 		ValueQuery,
 	>;
 
+	/// Number of markets each account has a debt in, so accounts without debt are told apart
+	/// without pricing their positions
+	#[pallet::storage]
+	#[pallet::getter(fn borrowed_markets)]
+	pub type BorrowedMarkets<T: Config> =
+		StorageMap<_, Blake2_128Concat, AccountIdOf<T>, u32, ValueQuery>;
+
+	/// Nonce of the next liquidation submitted by the offchain worker on behalf of each
+	/// liquidator
+	#[pallet::storage]
+	#[pallet::getter(fn liquidator_nonce)]
+	pub type LiquidatorNonces<T: Config> =
+		StorageMap<_, Blake2_128Concat, AccountIdOf<T>, u32, ValueQuery>;
+
+	/// Raw key of the last `Accounts` entry visited by the hasher migration, empty before the
+	/// first one. User operations are paused while it's present.
+	#[pallet::storage]
+	#[pallet::getter(fn accounts_migration)]
+	pub type AccountsMigration<T: Config> =
+		StorageValue<_, BoundedVec<u8, MaxAccountsKeyLen<T>>, OptionQuery>;
+
+	/// Raw key of the last position checked for liquidation, the next check resumes after it
+	#[pallet::storage]
+	#[pallet::getter(fn liquidation_cursor)]
+	pub type LiquidationCursor<T: Config> =
+		StorageValue<_, BoundedVec<u8, MaxAccountsKeyLen<T>>, OptionQuery>;
+
+	/// User operations paused by `PauseOrigin`
+	#[pallet::storage]
+	#[pallet::getter(fn paused_operations)]
+	pub type PausedOperations<T: Config> = StorageMap<_, Identity, Operation, bool, ValueQuery>;
+
+	/// Maximum total deposit balance of each market, unlimited if unset
+	#[pallet::storage]
+	#[pallet::getter(fn supply_cap)]
+	pub type SupplyCap<T: Config> =
+		StorageMap<_, Identity, AssetIdOf<T>, BalanceOf<T>, OptionQuery>;
+
+	/// Maximum total debt of each market, unlimited if unset
+	#[pallet::storage]
+	#[pallet::getter(fn borrow_cap)]
+	pub type BorrowCap<T: Config> =
+		StorageMap<_, Identity, AssetIdOf<T>, BalanceOf<T>, OptionQuery>;
+
+	/// Maximum value of the deposits of all markets in the common unit of account, unlimited
+	/// if unset
+	#[pallet::storage]
+	#[pallet::getter(fn global_supply_cap)]
+	pub type GlobalSupplyCap<T: Config> = StorageValue<_, BalanceOf<T>, OptionQuery>;
+
+	/// Maximum value of the debt of all markets in the common unit of account, unlimited if
+	/// unset
+	#[pallet::storage]
+	#[pallet::getter(fn global_borrow_cap)]
+	pub type GlobalBorrowCap<T: Config> = StorageValue<_, BalanceOf<T>, OptionQuery>;
+
+	/// Share of the deposits of each market which can't be borrowed, kept for withdrawals
+	#[pallet::storage]
+	#[pallet::getter(fn liquidity_buffer)]
+	pub type LiquidityBuffer<T: Config> =
+		StorageMap<_, Identity, AssetIdOf<T>, FixedU128, ValueQuery>;
+
+	/// Share of a flash loan of each market charged as fee, split between reserves and
+	/// depositors by the reserve factor
+	#[pallet::storage]
+	#[pallet::getter(fn flash_loan_fee)]
+	pub type FlashLoanFee<T: Config> =
+		StorageMap<_, Identity, AssetIdOf<T>, FixedU128, ValueQuery, DefaultFlashLoanFee<T>>;
+
+	/// Limits of each market parameter, a parameter without bounds can take any value
+	#[pallet::storage]
+	#[pallet::getter(fn bounds)]
+	pub type Bounds<T: Config> =
+		StorageMap<_, Identity, MarketParameter, ParameterBounds, OptionQuery>;
+
+	/// Parameter changes of each market waiting for their delay to pass
+	#[pallet::storage]
+	#[pallet::getter(fn pending_parameter_changes)]
+	pub type PendingParameterChanges<T: Config> = StorageDoubleMap<
+		_,
+		Identity,
+		AssetIdOf<T>,
+		Identity,
+		MarketParameter,
+		PendingChange<BlockNumber<T>>,
+		OptionQuery,
+	>;
+
+	/// Reason for which the pallet holds funds in the owner's account
+	#[pallet::composite_enum]
+	pub enum HoldReason {
+		/// Collateral-only deposit, which isn't lent out and earns no interest
+		#[codec(index = 0)]
+		Collateral,
+	}
+
+	#[pallet::genesis_config]
+	#[derive(frame_support::DefaultNoBound)]
+	pub struct GenesisConfig<T: Config> {
+		/// Account allowed to update market parameters besides the admin origin
+		pub authority: Option<AccountIdOf<T>>,
+		/// Account allowed to pause and unpause user operations
+		pub pause_guardian: Option<AccountIdOf<T>>,
+		/// Markets listed at genesis [asset_id, deposit_rate, borrowing_rate, collateral_factor,
+		/// reserve_factor]. Listing the native market overrides its default parameters.
+		pub markets: Vec<(AssetIdOf<T>, FixedU128, FixedU128, FixedU128, FixedU128)>,
+		/// Native balance minted into the pallet account, keeping it alive
+		pub pallet_balance: BalanceOf<T>,
+		/// Positions opened at genesis in the given order [who, asset_id, deposit, debt].
+		/// Deposits are paid by the account, debts are paid out of the pool.
+		pub positions: Vec<(AccountIdOf<T>, AssetIdOf<T>, BalanceOf<T>, BalanceOf<T>)>,
+	}
+
+	#[pallet::genesis_build]
+	impl<T: Config> BuildGenesisConfig for GenesisConfig<T> {
+		fn build(&self) {
+			if let Some(authority) = &self.authority {
+				AuthorityAccount::<T>::put(authority);
+			}
+			if let Some(pause_guardian) = &self.pause_guardian {
+				PauseGuardian::<T>::put(pause_guardian);
+			}
+
+			for (asset_id, deposit_rate, borrowing_rate, collateral_factor, reserve_factor) in
+				&self.markets
+			{
+				assert!(*reserve_factor <= FixedU128::one(), "Reserve factor exceeds one");
+				assert!(*collateral_factor <= FixedU128::one(), "Collateral factor exceeds one");
+
+				if *asset_id != T::NativeAssetId::get() {
+					Markets::<T>::insert(asset_id, true);
+				}
+				Pallet::<T>::ensure_receipt_asset(*asset_id)
+					.expect("Receipt asset id of the market is free");
+				DepositRate::<T>::insert(asset_id, deposit_rate);
+				BorrowingRate::<T>::insert(asset_id, borrowing_rate);
+				CollateralFactor::<T>::insert(asset_id, collateral_factor);
+				ReserveFactor::<T>::insert(asset_id, reserve_factor);
+			}
+			assert!(
+				Pallet::<T>::market_ids().len() as u32 <= T::MaxMarkets::get(),
+				"Too many markets listed at genesis"
+			);
+
+			let pallet_account = Pallet::<T>::account_id();
+			if !self.pallet_balance.is_zero() {
+				<T::Currency as fungible::Mutate<_>>::mint_into(
+					&pallet_account,
+					self.pallet_balance,
+				)
+				.expect("Pallet balance is at least the existential deposit");
+			}
+
+			for (who, asset_id, deposit, debt) in &self.positions {
+				assert!(Pallet::<T>::market_exists(*asset_id), "Position in an unlisted market");
+
+				if !deposit.is_zero() {
+					Pallet::<T>::transfer(*asset_id, who, &pallet_account, *deposit)
+						.expect("Genesis deposit is funded by the account");
+					Pallet::<T>::increase_deposit(*asset_id, who, *deposit)
+						.expect("Genesis deposit receipt is minted");
+				}
+
+				if !debt.is_zero() {
+					Pallet::<T>::transfer(*asset_id, &pallet_account, who, *debt)
+						.expect("Genesis debt is covered by the pool");
+					Pallet::<T>::increase_debt(*asset_id, who, *debt);
+				}
+			}
+		}
+	}
+
 	#[pallet::event]
 	#[pallet::generate_deposit(pub(super) fn deposit_event)]
 	pub enum Event<T: Config> {
-		/// Funds deposited [who, amount, block]
-		Deposited(AccountIdOf<T>, BalanceOf<T>, BlockNumber<T>),
-		/// Funds withdrawn [who, amount, block]
-		Withdrawn(AccountIdOf<T>, BalanceOf<T>, BlockNumber<T>),
-		/// Loan repaid [who, amount, block]
-		LoanRepaid(AccountIdOf<T>, BalanceOf<T>, BlockNumber<T>),
-		/// Funds borrowed [who, amount, block]
-		Borrowed(AccountIdOf<T>, BalanceOf<T>, BlockNumber<T>),
-		/// Address liquidated [who]
-		AddressLiquidated(AccountIdOf<T>),
-		/// Deposit rate updated [rate]
-		DepositRateUpdated(FixedU128),
-		/// Borrowing rate updated [rate]
-		BorrowingRateUpdated(FixedU128),
-		/// Collateral factor updated [factor]
-		CollateralFactorUpdated(FixedU128),
+		/// Funds deposited [who, asset_id, amount, block]
+		Deposited(AccountIdOf<T>, AssetIdOf<T>, BalanceOf<T>, BlockNumber<T>),
+		/// Funds withdrawn [who, asset_id, amount, block]
+		Withdrawn(AccountIdOf<T>, AssetIdOf<T>, BalanceOf<T>, BlockNumber<T>),
+		/// Loan repaid [who, asset_id, amount, block]
+		LoanRepaid(AccountIdOf<T>, AssetIdOf<T>, BalanceOf<T>, BlockNumber<T>),
+		/// Funds borrowed [who, asset_id, amount, block]
+		Borrowed(AccountIdOf<T>, AssetIdOf<T>, BalanceOf<T>, BlockNumber<T>),
+		/// Address can be liquidated [who]
+		AddressUndercollateralized(AccountIdOf<T>),
+		/// Address liquidated [liquidator, borrower, debt_asset_id, repaid_amount,
+		/// collateral_asset_id, seized_amount]
+		AddressLiquidated(
+			AccountIdOf<T>,
+			AccountIdOf<T>,
+			AssetIdOf<T>,
+			BalanceOf<T>,
+			AssetIdOf<T>,
+			BalanceOf<T>,
+		),
+		/// Parameter change scheduled [asset_id, parameter, value, effective_at]
+		ParameterChangeScheduled(AssetIdOf<T>, MarketParameter, FixedU128, BlockNumber<T>),
+		/// Parameter change applied [asset_id, parameter, value]
+		ParameterChangeApplied(AssetIdOf<T>, MarketParameter, FixedU128),
+		/// Pending parameter change cancelled [asset_id, parameter]
+		ParameterChangeCancelled(AssetIdOf<T>, MarketParameter),
+		/// Market created [asset_id]
+		MarketCreated(AssetIdOf<T>),
+		/// Reserve factor updated [asset_id, factor]
+		ReserveFactorUpdated(AssetIdOf<T>, FixedU128),
+		/// Reserves added [who, asset_id, amount]
+		ReservesAdded(AccountIdOf<T>, AssetIdOf<T>, BalanceOf<T>),
+		/// Reserves reduced [to, asset_id, amount]
+		ReservesReduced(AccountIdOf<T>, AssetIdOf<T>, BalanceOf<T>),
+		/// Authority account set or removed [authority]
+		AuthoritySet(Option<AccountIdOf<T>>),
+		/// Pause guardian set or removed [pause_guardian]
+		PauseGuardianSet(Option<AccountIdOf<T>>),
+		/// Parameter bounds set or removed [parameter, bounds]
+		ParameterBoundsSet(MarketParameter, Option<ParameterBounds>),
+		/// Operation paused [operation]
+		OperationPaused(Operation),
+		/// Operation unpaused [operation]
+		OperationUnpaused(Operation),
+		/// Market caps set [asset_id, supply_cap, borrow_cap]
+		CapsSet(AssetIdOf<T>, Option<BalanceOf<T>>, Option<BalanceOf<T>>),
+		/// Global caps set [supply_cap, borrow_cap]
+		GlobalCapsSet(Option<BalanceOf<T>>, Option<BalanceOf<T>>),
+		/// Liquidity buffer set [asset_id, buffer]
+		LiquidityBufferSet(AssetIdOf<T>, FixedU128),
+		/// Receipt token of the market created [asset_id, receipt_asset_id]
+		ReceiptAssetCreated(AssetIdOf<T>, AssetIdOf<T>),
+		/// Flash loan repaid with its fee [who, asset_id, amount, fee]
+		FlashLoanRepaid(AccountIdOf<T>, AssetIdOf<T>, BalanceOf<T>, BalanceOf<T>),
+		/// Flash loan fee of the market set [asset_id, fee]
+		FlashLoanFeeSet(AssetIdOf<T>, FixedU128),
+		/// Reserves moved to the insurance fund [asset_id, amount]
+		InsuranceFunded(AssetIdOf<T>, BalanceOf<T>),
+		/// Remaining collateral of an insolvent account moved to reserves [who, asset_id,
+		/// amount]
+		CollateralAbsorbed(AccountIdOf<T>, AssetIdOf<T>, BalanceOf<T>),
+		/// Debt of an insolvent account written off [who, asset_id, amount]
+		BadDebtRecorded(AccountIdOf<T>, AssetIdOf<T>, BalanceOf<T>),
+		/// Bad debt covered by the insurance fund [asset_id, amount]
+		BadDebtCovered(AssetIdOf<T>, BalanceOf<T>),
+		/// Bad debt not covered by the insurance fund taken from depositors [asset_id, amount]
+		BadDebtSocialised(AssetIdOf<T>, BalanceOf<T>),
+		/// Bad debt exceeding the insurance fund and the deposits [asset_id, amount]
+		BadDebtUncovered(AssetIdOf<T>, BalanceOf<T>),
+		/// Funds held as collateral only [who, asset_id, amount]
+		CollateralDeposited(AccountIdOf<T>, AssetIdOf<T>, BalanceOf<T>),
+		/// Held collateral released [who, asset_id, amount]
+		CollateralWithdrawn(AccountIdOf<T>, AssetIdOf<T>, BalanceOf<T>),
 	}
 
 	#[pallet::error]
@@ -162,6 +886,66 @@ pub mod pallet {
 		NothingToRepay,
 		/// Unauthorized user action
 		UnauthorizedUserAction,
+		/// Market for the given asset is not listed
+		MarketNotFound,
+		/// Market for the given asset is already listed
+		MarketAlreadyExists,
+		/// Price of the asset is not available
+		PriceUnavailable,
+		/// Reserve factor is greater than one
+		InvalidReserveFactor,
+		/// Market has not as much reserves as requested
+		InsufficientReserves,
+		/// Invalid repay amount
+		InvalidRepayAmount,
+		/// Borrower can't liquidate his own position
+		SelfLiquidation,
+		/// Borrower's debt is covered by his borrowing power
+		AddressNotLiquidatable,
+		/// Repay amount exceeds the close factor share of the debt
+		ExcessiveRepayAmount,
+		/// Borrower has not enough collateral to be seized
+		InsufficientCollateral,
+		/// Collateral factor is greater than one
+		InvalidCollateralFactor,
+		/// Minimum bound is greater than the maximum or the bounds allow an invalid value
+		InvalidParameterBounds,
+		/// New parameter value is outside of its bounds
+		ParameterOutOfBounds,
+		/// New parameter value differs from the current one by more than allowed
+		ParameterChangeTooLarge,
+		/// Market has no pending change of the parameter
+		NoPendingParameterChange,
+		/// Operation is paused
+		OperationPaused,
+		/// Deposit exceeds the supply cap of the market
+		SupplyCapExceeded,
+		/// Borrow exceeds the borrow cap of the market
+		BorrowCapExceeded,
+		/// Deposit exceeds the supply cap of all markets
+		GlobalSupplyCapExceeded,
+		/// Borrow exceeds the borrow cap of all markets
+		GlobalBorrowCapExceeded,
+		/// Liquidity buffer is greater than one
+		InvalidLiquidityBuffer,
+		/// Borrow would leave less of the deposits than the liquidity buffer of the market
+		LiquidityBufferBreached,
+		/// Receipt asset id of the market is taken by another asset
+		ReceiptAssetUnavailable,
+		/// Invalid flash loan amount
+		InvalidFlashLoanAmount,
+		/// Flash loan fee is greater than one
+		InvalidFlashLoanFee,
+		/// Accounts are being migrated, user operations are paused until it's finished
+		MigrationInProgress,
+		/// Market does not support collateral-only deposits
+		CollateralOnlyUnsupported,
+		/// Withdrawal would leave the debt uncovered by the borrowing power
+		UnallowedWithdrawAmount,
+		/// Maximum number of markets is already listed
+		TooManyMarkets,
+		/// Call is not allowed to be dispatched with a flash loan
+		FlashLoanCallFiltered,
 	}
 
 	#[pallet::call]
@@ -169,47 +953,45 @@ pub mod pallet {
 		/// Deposit funds
 		#[transactional]
 		#[pallet::call_index(0)]
-		#[pallet::weight(<T as Config>::WeightInfo::deposit())]
-		pub fn deposit(origin: OriginFor<T>, amount: BalanceOf<T>) -> DispatchResult {
+		#[pallet::weight(<T as Config>::WeightInfo::deposit(T::MaxMarkets::get()))]
+		pub fn deposit(
+			origin: OriginFor<T>,
+			asset_id: AssetIdOf<T>,
+			amount: BalanceOf<T>,
+		) -> DispatchResult {
 			// Check that the extrinsic was signed and get the signer.
 			// This function will return an error if the extrinsic is not signed.
 			let user = ensure_signed(origin)?;
 
+			// Check if the operation is paused
+			Self::ensure_not_paused(Operation::Deposit)?;
+
+			// Check if the market exists
+			ensure!(Self::market_exists(asset_id), Error::<T>::MarketNotFound);
+
 			// Check if the deposited amount is greater than 0
 			ensure!(amount > <BalanceOf<T>>::zero(), Error::<T>::InvalidDepositAmount);
 
 			// Check if user has enough funds
-			ensure!(T::Currency::free_balance(&user) >= amount, Error::<T>::InsufficientBalance);
+			ensure!(Self::free_balance(asset_id, &user) >= amount, Error::<T>::InsufficientBalance);
 
-			// Get address info of extrinsic caller
-			let mut address_info = Accounts::<T>::get(&user);
+			// Accrue interest of the market up to the current block
+			Self::accrue_interest(asset_id);
+
+			// Check if the deposit fits under the supply caps
+			Self::ensure_supply_caps(asset_id, amount)?;
 
 			// Get current block
 			let current_block = frame_system::Pallet::<T>::block_number();
 
 			// Deposit funds to pallet
-			T::Currency::transfer(
-				&user,
-				&Self::account_id(),
-				amount,
-				ExistenceRequirement::KeepAlive,
-			)?;
-
-			// Set address info
-			let deposit_principal_fixed =
-				FixedU128::from_inner(address_info.deposit_principal.saturated_into::<u128>());
-			let amount_fixed = FixedU128::from_inner(amount.saturated_into::<u128>());
-			address_info.deposit_principal = deposit_principal_fixed
-				.saturating_add(amount_fixed)
-				.into_inner()
-				.saturated_into();
-			address_info.deposit_date = current_block;
+			Self::transfer(asset_id, &user, &Self::account_id(), amount)?;
 
-			// Put updated address info into storage
-			Accounts::<T>::insert(&user, address_info);
+			// Mint receipt tokens for the deposit
+			Self::increase_deposit(asset_id, &user, amount)?;
 
 			// Emit an event
-			Self::deposit_event(Event::Deposited(user, amount, current_block));
+			Self::deposit_event(Event::Deposited(user, asset_id, amount, current_block));
 
 			// Return a successful DispatchResult
 			Ok(())
@@ -218,27 +1000,38 @@ pub mod pallet {
 		/// Withdraw funds
 		#[transactional]
 		#[pallet::call_index(1)]
-		#[pallet::weight(<T as Config>::WeightInfo::withdraw())]
-		pub fn withdraw(origin: OriginFor<T>, amount: BalanceOf<T>) -> DispatchResult {
+		#[pallet::weight(<T as Config>::WeightInfo::withdraw(T::MaxMarkets::get()))]
+		pub fn withdraw(
+			origin: OriginFor<T>,
+			asset_id: AssetIdOf<T>,
+			amount: BalanceOf<T>,
+		) -> DispatchResult {
 			// Check that the extrinsic was signed and get the signer.
 			// This function will return an error if the extrinsic is not signed.
 			let user = ensure_signed(origin)?;
 
-			// Get address info of extrinsic caller and check if it has deposited funds
-			let mut address_info = Accounts::<T>::get(&user);
+			// Check if the operation is paused
+			Self::ensure_not_paused(Operation::Withdraw)?;
+
+			// Check if the market exists
+			ensure!(Self::market_exists(asset_id), Error::<T>::MarketNotFound);
 
+			// Check if extrinsic caller holds receipt tokens of the market
 			ensure!(
-				address_info.deposit_principal != <BalanceOf<T>>::zero(),
+				!Self::receipt_balance(asset_id, &user).is_zero(),
 				Error::<T>::NoFundsDeposited
 			);
 
+			// Accrue interest of the market up to the current block
+			Self::accrue_interest(asset_id);
+
 			// Check if user and pallet have enough funds
-			let balance_info = Self::get_lending_amount(user.clone());
+			let balance_info = Self::get_lending_amount(asset_id, user.clone());
 
 			ensure!(amount <= balance_info, Error::<T>::UserHasNotEnoughFunds);
 
 			ensure!(
-				amount <= T::Currency::free_balance(&Self::account_id()),
+				amount <= Self::free_balance(asset_id, &Self::account_id()),
 				Error::<T>::PalletHasNotEnoughFunds
 			);
 
@@ -246,33 +1039,19 @@ pub mod pallet {
 			let current_block = frame_system::Pallet::<T>::block_number();
 
 			// Withdraw funds from pallet
-			T::Currency::transfer(
-				&Self::account_id(),
-				&user,
-				amount,
-				ExistenceRequirement::KeepAlive,
-			)?;
+			Self::transfer(asset_id, &Self::account_id(), &user, amount)?;
 
-			// Update address info
-			if amount == address_info.deposit_principal {
-				address_info.deposit_date = BlockNumber::<T>::zero();
-			} else {
-				address_info.deposit_date = current_block;
-			}
-
-			let deposit_principal_fixed =
-				FixedU128::from_inner(address_info.deposit_principal.saturated_into::<u128>());
-			let amount_fixed = FixedU128::from_inner(amount.saturated_into::<u128>());
-			address_info.deposit_principal = deposit_principal_fixed
-				.saturating_sub(amount_fixed)
-				.into_inner()
-				.saturated_into();
+			// Burn receipt tokens worth the withdrawn funds
+			Self::decrease_deposit(asset_id, &user, amount)?;
 
-			// Put updated address info into storage
-			Accounts::<T>::insert(&user, address_info);
+			// Check if the remaining borrowing power covers the debt, if any
+			if Self::has_debt(&user) {
+				let (borrowing_power, debt_value) = Self::get_account_liquidity(&user)?;
+				ensure!(debt_value <= borrowing_power, Error::<T>::UnallowedWithdrawAmount);
+			}
 
 			// Emit an event
-			Self::deposit_event(Event::Withdrawn(user, amount, current_block));
+			Self::deposit_event(Event::Withdrawn(user, asset_id, amount, current_block));
 
 			// Return a successful DispatchResult
 			Ok(())
@@ -281,53 +1060,66 @@ pub mod pallet {
 		/// Borrow funds
 		#[transactional]
 		#[pallet::call_index(2)]
-		#[pallet::weight(<T as Config>::WeightInfo::borrow())]
-		pub fn borrow(origin: OriginFor<T>, amount: BalanceOf<T>) -> DispatchResult {
+		#[pallet::weight(<T as Config>::WeightInfo::borrow(T::MaxMarkets::get()))]
+		pub fn borrow(
+			origin: OriginFor<T>,
+			asset_id: AssetIdOf<T>,
+			amount: BalanceOf<T>,
+		) -> DispatchResult {
 			// Check that the extrinsic was signed and get the signer.
 			// This function will return an error if the extrinsic is not signed.
 			let user = ensure_signed(origin)?;
 
+			// Check if the operation is paused
+			Self::ensure_not_paused(Operation::Borrow)?;
+
+			// Check if the market exists
+			ensure!(Self::market_exists(asset_id), Error::<T>::MarketNotFound);
+
 			// Check if pallet has enough funds
 			ensure!(
-				amount <= T::Currency::free_balance(&Self::account_id()),
+				amount <= Self::free_balance(asset_id, &Self::account_id()),
 				Error::<T>::PalletHasNotEnoughFunds
 			);
 
-			// Get address info of extrinsic caller
-			let mut address_info = Accounts::<T>::get(&user);
+			// Accrue interest of the market up to the current block
+			Self::accrue_interest(asset_id);
 
-			// Get allowed borrowing amount
-			let borrowing_balance = Self::get_debt_amount(user.clone());
+			// Get allowed borrowing amount across all markets
+			let price = T::PriceOracle::get_price(&asset_id).ok_or(Error::<T>::PriceUnavailable)?;
+			let (borrowing_power, debt_value) = Self::get_account_liquidity(&user)?;
 			let borrowing_info =
-				Self::get_allowed_borrowing_amount(user.clone(), borrowing_balance, false);
+				Self::from_value(borrowing_power.saturating_sub(debt_value), price);
 			ensure!(amount <= borrowing_info, Error::<T>::UnallowedBorrowAmount);
 
+			// Check if the borrow fits under the borrow caps
+			Self::ensure_borrow_caps(asset_id, amount)?;
+
+			// Check if the market keeps its liquidity buffer for withdrawals. Only deposits are
+			// lent out, reserves and the insurance fund are kept for bad debt.
+			let total_deposits = TotalDeposits::<T>::get(asset_id);
+			let buffer: BalanceOf<T> =
+				FixedU128::from_inner(total_deposits.saturated_into::<u128>())
+					.saturating_mul(LiquidityBuffer::<T>::get(asset_id))
+					.into_inner()
+					.saturated_into();
+			ensure!(
+				amount.saturating_add(buffer) <=
+					total_deposits.saturating_sub(TotalBorrows::<T>::get(asset_id)),
+				Error::<T>::LiquidityBufferBreached
+			);
+
 			// Get current block
 			let current_block = frame_system::Pallet::<T>::block_number();
 
 			// Borrow funds from pallet
-			T::Currency::transfer(
-				&Self::account_id(),
-				&user,
-				amount,
-				ExistenceRequirement::KeepAlive,
-			)?;
+			Self::transfer(asset_id, &Self::account_id(), &user, amount)?;
 
 			// Update address info
-			let borrowing_balance_fixed =
-				FixedU128::from_inner(borrowing_balance.saturated_into::<u128>());
-			let amount_fixed = FixedU128::from_inner(amount.saturated_into::<u128>());
-			address_info.borrow_principal = borrowing_balance_fixed
-				.saturating_add(amount_fixed)
-				.into_inner()
-				.saturated_into();
-			address_info.borrow_date = current_block;
-
-			// Put updated address info into storage
-			Accounts::<T>::insert(&user, address_info);
+			Self::increase_debt(asset_id, &user, amount);
 
 			// Emit an event
-			Self::deposit_event(Event::Borrowed(user, amount, current_block));
+			Self::deposit_event(Event::Borrowed(user, asset_id, amount, current_block));
 
 			// Return a successful DispatchResult
 			Ok(())
@@ -337,278 +1129,2200 @@ pub mod pallet {
 		#[transactional]
 		#[pallet::call_index(3)]
 		#[pallet::weight(<T as Config>::WeightInfo::repay())]
-		pub fn repay(origin: OriginFor<T>, mut amount: BalanceOf<T>) -> DispatchResult {
+		pub fn repay(
+			origin: OriginFor<T>,
+			asset_id: AssetIdOf<T>,
+			mut amount: BalanceOf<T>,
+		) -> DispatchResult {
 			// Check that the extrinsic was signed and get the signer.
 			// This function will return an error if the extrinsic is not signed.
 			let user = ensure_signed(origin)?;
 
+			// Check if the operation is paused
+			Self::ensure_not_paused(Operation::Repay)?;
+
+			// Check if the market exists
+			ensure!(Self::market_exists(asset_id), Error::<T>::MarketNotFound);
+
 			// Check if the user has enough on his balance
-			ensure!(T::Currency::free_balance(&user) >= amount, Error::<T>::InsufficientBalance);
+			ensure!(Self::free_balance(asset_id, &user) >= amount, Error::<T>::InsufficientBalance);
 
 			// Check if the user has anything to repay
-			let mut address_info = Accounts::<T>::get(&user);
+			let address_info = Accounts::<T>::get(asset_id, &user);
 
 			ensure!(
 				address_info.borrow_principal > <BalanceOf<T>>::zero(),
 				Error::<T>::NothingToRepay
 			);
 
+			// Accrue interest of the market up to the current block
+			Self::accrue_interest(asset_id);
+
 			// Check if there is repay overflow
-			let balance_info = Self::get_debt_amount(user.clone());
+			let balance_info = Self::get_debt_amount(asset_id, user.clone());
 			if amount > balance_info {
 				amount = balance_info;
 			}
 
 			// Transfer funds from user to pallet
-			T::Currency::transfer(
-				&user,
-				&Self::account_id(),
-				amount,
-				ExistenceRequirement::KeepAlive,
-			)?;
+			Self::transfer(asset_id, &user, &Self::account_id(), amount)?;
 
-			// Get principal with accrued interest
+			// Get current block
 			let current_block = frame_system::Pallet::<T>::block_number();
 
 			// Update address info
-			if amount == balance_info {
-				address_info.borrow_date = BlockNumber::<T>::zero();
-			} else {
-				address_info.borrow_date = current_block;
-			}
-
-			let balance_fixed = FixedU128::from_inner(balance_info.saturated_into::<u128>());
-			let amount_fixed = FixedU128::from_inner(amount.saturated_into::<u128>());
-			address_info.borrow_principal =
-				balance_fixed.saturating_sub(amount_fixed).into_inner().saturated_into();
-
-			// Put updated address info into storage
-			Accounts::<T>::insert(&user, address_info);
+			Self::decrease_debt(asset_id, &user, amount);
 
 			// Emit an event
-			Self::deposit_event(Event::LoanRepaid(user, amount, current_block));
+			Self::deposit_event(Event::LoanRepaid(user, asset_id, amount, current_block));
 
 			// Return a successful DispatchResult
 			Ok(())
 		}
 
-		/// Update deposit rate
+		/// Schedule an update of the deposit rate
 		#[pallet::call_index(4)]
 		#[pallet::weight(<T as Config>::WeightInfo::update_deposit_rate())]
-		pub fn update_deposit_rate(origin: OriginFor<T>, new_rate: FixedU128) -> DispatchResult {
-			// Check that the extrinsic was signed and get the signer.
-			// This function will return an error if the extrinsic is not signed.
-			let user = ensure_signed(origin)?;
-
-			// Check if the caller is the authority account
-			ensure!(user == AuthorityAccount::<T>::get(), Error::<T>::UnauthorizedUserAction);
-
-			// Update deposit rate
-			DepositRate::<T>::put(new_rate);
+		pub fn update_deposit_rate(
+			origin: OriginFor<T>,
+			asset_id: AssetIdOf<T>,
+			new_rate: FixedU128,
+		) -> DispatchResult {
+			// Check if the caller is the admin origin or the authority account
+			Self::ensure_admin(origin)?;
 
-			// Emit an event
-			Self::deposit_event(Event::DepositRateUpdated(new_rate));
+			// Check if the market exists
+			ensure!(Self::market_exists(asset_id), Error::<T>::MarketNotFound);
 
-			Ok(())
+			// Schedule the change, it's applied after the delay
+			Self::schedule_parameter_change(asset_id, MarketParameter::DepositRate, new_rate)
 		}
 
-		/// Update borrowing rate
+		/// Schedule an update of the borrowing rate
 		#[pallet::call_index(5)]
 		#[pallet::weight(<T as Config>::WeightInfo::update_borrowing_rate())]
-		pub fn update_borrowing_rate(origin: OriginFor<T>, new_rate: FixedU128) -> DispatchResult {
-			// Check that the extrinsic was signed and get the signer.
-			// This function will return an error if the extrinsic is not signed.
-			let user = ensure_signed(origin)?;
-
-			// Check if the caller is the authority account
-			ensure!(user == AuthorityAccount::<T>::get(), Error::<T>::UnauthorizedUserAction);
-
-			// Update deposit rate
-			BorrowingRate::<T>::put(new_rate);
+		pub fn update_borrowing_rate(
+			origin: OriginFor<T>,
+			asset_id: AssetIdOf<T>,
+			new_rate: FixedU128,
+		) -> DispatchResult {
+			// Check if the caller is the admin origin or the authority account
+			Self::ensure_admin(origin)?;
 
-			// Emit an event
-			Self::deposit_event(Event::BorrowingRateUpdated(new_rate));
+			// Check if the market exists
+			ensure!(Self::market_exists(asset_id), Error::<T>::MarketNotFound);
 
-			Ok(())
+			// Schedule the change, it's applied after the delay
+			Self::schedule_parameter_change(asset_id, MarketParameter::BorrowingRate, new_rate)
 		}
 
-		/// Update collateral factor
+		/// Schedule an update of the collateral factor
 		#[pallet::call_index(6)]
 		#[pallet::weight(<T as Config>::WeightInfo::update_collateral_factor())]
 		pub fn update_collateral_factor(
 			origin: OriginFor<T>,
+			asset_id: AssetIdOf<T>,
 			new_factor: FixedU128,
 		) -> DispatchResult {
-			// Check that the extrinsic was signed and get the signer.
-			// This function will return an error if the extrinsic is not signed.
-			let user = ensure_signed(origin)?;
+			// Check if the caller is the admin origin or the authority account
+			Self::ensure_admin(origin)?;
 
-			// Check if the caller is the authority account
-			ensure!(user == AuthorityAccount::<T>::get(), Error::<T>::UnauthorizedUserAction);
+			// Check if the market exists
+			ensure!(Self::market_exists(asset_id), Error::<T>::MarketNotFound);
 
-			// Update deposit rate
-			CollateralFactor::<T>::put(new_factor);
+			// Schedule the change, it's applied after the delay
+			Self::schedule_parameter_change(asset_id, MarketParameter::CollateralFactor, new_factor)
+		}
 
-			// Emit an event
-			Self::deposit_event(Event::CollateralFactorUpdated(new_factor));
+		/// Create a lending market for a non-native asset
+		#[pallet::call_index(7)]
+		#[pallet::weight(<T as Config>::WeightInfo::create_market(T::MaxMarkets::get()))]
+		pub fn create_market(
+			origin: OriginFor<T>,
+			asset_id: AssetIdOf<T>,
+			deposit_rate: FixedU128,
+			borrowing_rate: FixedU128,
+			collateral_factor: FixedU128,
+		) -> DispatchResult {
+			// Check if the caller is the admin origin or the authority account
+			Self::ensure_admin(origin)?;
+
+			// Check if the market is already listed
+			ensure!(!Self::market_exists(asset_id), Error::<T>::MarketAlreadyExists);
+
+			// Check if another market can be listed
+			ensure!(
+				(Self::market_ids().len() as u32) < T::MaxMarkets::get(),
+				Error::<T>::TooManyMarkets
+			);
+
+			// Check if the parameters are within their bounds
+			Self::ensure_within_bounds(MarketParameter::DepositRate, deposit_rate, None)?;
+			Self::ensure_within_bounds(MarketParameter::BorrowingRate, borrowing_rate, None)?;
+			Self::ensure_within_bounds(MarketParameter::CollateralFactor, collateral_factor, None)?;
+
+			// Create the receipt token minted to depositors
+			Self::ensure_receipt_asset(asset_id)?;
+
+			// List the market with its parameters
+			Markets::<T>::insert(asset_id, true);
+			DepositRate::<T>::insert(asset_id, deposit_rate);
+			BorrowingRate::<T>::insert(asset_id, borrowing_rate);
+			CollateralFactor::<T>::insert(asset_id, collateral_factor);
+			LastAccrued::<T>::insert(asset_id, T::AccrualClock::now());
+
+			// Emit an event
+			Self::deposit_event(Event::MarketCreated(asset_id));
+
+			Ok(())
+		}
+
+		/// Update reserve factor
+		#[pallet::call_index(8)]
+		#[pallet::weight(<T as Config>::WeightInfo::update_reserve_factor())]
+		pub fn update_reserve_factor(
+			origin: OriginFor<T>,
+			asset_id: AssetIdOf<T>,
+			new_factor: FixedU128,
+		) -> DispatchResult {
+			// Check if the caller is the admin origin or the authority account
+			Self::ensure_admin(origin)?;
+
+			// Check if the market exists
+			ensure!(Self::market_exists(asset_id), Error::<T>::MarketNotFound);
+
+			// Check if the factor is not greater than one
+			ensure!(new_factor <= FixedU128::one(), Error::<T>::InvalidReserveFactor);
+
+			// Accrue interest at the previous factor
+			Self::accrue_interest(asset_id);
+
+			// Update reserve factor
+			ReserveFactor::<T>::insert(asset_id, new_factor);
+
+			// Emit an event
+			Self::deposit_event(Event::ReserveFactorUpdated(asset_id, new_factor));
+
+			Ok(())
+		}
+
+		/// Add funds to the reserves of a market
+		#[transactional]
+		#[pallet::call_index(9)]
+		#[pallet::weight(<T as Config>::WeightInfo::add_reserves())]
+		pub fn add_reserves(
+			origin: OriginFor<T>,
+			asset_id: AssetIdOf<T>,
+			amount: BalanceOf<T>,
+		) -> DispatchResult {
+			// Check if the caller is allowed to manage reserves and get the paying account
+			let who = T::ReserveOrigin::ensure_origin(origin)?;
+
+			// Check if the market exists
+			ensure!(Self::market_exists(asset_id), Error::<T>::MarketNotFound);
+
+			// Check if the paying account has enough funds
+			ensure!(Self::free_balance(asset_id, &who) >= amount, Error::<T>::InsufficientBalance);
+
+			// Accrue interest of the market up to the current block
+			Self::accrue_interest(asset_id);
+
+			// Transfer funds to pallet
+			Self::transfer(asset_id, &who, &Self::account_id(), amount)?;
+
+			// Update reserves
+			TotalReserves::<T>::mutate(asset_id, |total| *total = total.saturating_add(amount));
+
+			// Emit an event
+			Self::deposit_event(Event::ReservesAdded(who, asset_id, amount));
+
+			Ok(())
+		}
+
+		/// Withdraw funds from the reserves of a market
+		#[transactional]
+		#[pallet::call_index(10)]
+		#[pallet::weight(<T as Config>::WeightInfo::reduce_reserves())]
+		pub fn reduce_reserves(
+			origin: OriginFor<T>,
+			asset_id: AssetIdOf<T>,
+			to: AccountIdOf<T>,
+			amount: BalanceOf<T>,
+		) -> DispatchResult {
+			// Check if the caller is allowed to manage reserves
+			T::ReserveOrigin::ensure_origin(origin)?;
+
+			// Check if the market exists
+			ensure!(Self::market_exists(asset_id), Error::<T>::MarketNotFound);
+
+			// Accrue interest of the market up to the current block
+			Self::accrue_interest(asset_id);
+
+			// Check if market and pallet have enough funds
+			ensure!(amount <= TotalReserves::<T>::get(asset_id), Error::<T>::InsufficientReserves);
+
+			ensure!(
+				amount <= Self::free_balance(asset_id, &Self::account_id()),
+				Error::<T>::PalletHasNotEnoughFunds
+			);
+
+			// Transfer funds from pallet
+			Self::transfer(asset_id, &Self::account_id(), &to, amount)?;
+
+			// Update reserves
+			TotalReserves::<T>::mutate(asset_id, |total| *total = total.saturating_sub(amount));
+
+			// Emit an event
+			Self::deposit_event(Event::ReservesReduced(to, asset_id, amount));
+
+			Ok(())
+		}
+
+		/// Repay part of an undercollateralized borrower's debt and seize his collateral
+		#[transactional]
+		#[pallet::call_index(11)]
+		#[pallet::weight(<T as Config>::WeightInfo::liquidate(T::MaxMarkets::get()))]
+		pub fn liquidate(
+			origin: OriginFor<T>,
+			borrower: AccountIdOf<T>,
+			debt_asset_id: AssetIdOf<T>,
+			collateral_asset_id: AssetIdOf<T>,
+			repay_amount: BalanceOf<T>,
+		) -> DispatchResult {
+			// Check that the extrinsic was signed and get the signer.
+			// This function will return an error if the extrinsic is not signed.
+			let liquidator = ensure_signed(origin)?;
+
+			Self::do_liquidate(
+				liquidator,
+				borrower,
+				debt_asset_id,
+				collateral_asset_id,
+				repay_amount,
+			)
+		}
+
+		/// Liquidation submitted by the offchain worker on behalf of the liquidator whose key
+		/// signed the payload. The signature and the nonce are checked in `validate_unsigned`.
+		#[transactional]
+		#[pallet::call_index(12)]
+		#[pallet::weight({
+			// The nonce of the liquidator is bumped on top of the liquidation
+			<T as Config>::WeightInfo::liquidate(T::MaxMarkets::get())
+				.saturating_add(T::DbWeight::get().reads_writes(1, 1))
+		})]
+		pub fn liquidate_unsigned(
+			origin: OriginFor<T>,
+			payload: LiquidationPayloadOf<T>,
+			_signature: T::Signature,
+		) -> DispatchResult {
+			// Check that the extrinsic is unsigned
+			ensure_none(origin)?;
+
+			let liquidator = payload.public.into_account();
+
+			Self::do_liquidate(
+				liquidator.clone(),
+				payload.borrower,
+				payload.debt_asset_id,
+				payload.collateral_asset_id,
+				payload.repay_amount,
+			)?;
+
+			// The payload can't be executed again
+			LiquidatorNonces::<T>::mutate(&liquidator, |nonce| *nonce = nonce.saturating_add(1));
+
+			Ok(())
+		}
+
+		/// Set or remove the account allowed to update market parameters besides the admin
+		/// origin
+		#[pallet::call_index(13)]
+		#[pallet::weight(<T as Config>::WeightInfo::set_authority())]
+		pub fn set_authority(
+			origin: OriginFor<T>,
+			new_authority: Option<AccountIdOf<T>>,
+		) -> DispatchResult {
+			// Check if the caller is the admin origin
+			T::AdminOrigin::ensure_origin(origin)?;
+
+			// Update authority account
+			AuthorityAccount::<T>::set(new_authority.clone());
+
+			// Emit an event
+			Self::deposit_event(Event::AuthoritySet(new_authority));
+
+			Ok(())
+		}
+
+		/// Set or remove the account allowed to pause and unpause user operations besides the
+		/// admin origin
+		#[pallet::call_index(26)]
+		#[pallet::weight(<T as Config>::WeightInfo::set_pause_guardian())]
+		pub fn set_pause_guardian(
+			origin: OriginFor<T>,
+			new_guardian: Option<AccountIdOf<T>>,
+		) -> DispatchResult {
+			// Check if the caller is the admin origin
+			T::AdminOrigin::ensure_origin(origin)?;
+
+			// Update pause guardian account
+			PauseGuardian::<T>::set(new_guardian.clone());
+
+			// Emit an event
+			Self::deposit_event(Event::PauseGuardianSet(new_guardian));
+
+			Ok(())
+		}
+
+		/// Set or remove the limits of a market parameter
+		#[pallet::call_index(14)]
+		#[pallet::weight(<T as Config>::WeightInfo::set_parameter_bounds())]
+		pub fn set_parameter_bounds(
+			origin: OriginFor<T>,
+			parameter: MarketParameter,
+			bounds: Option<ParameterBounds>,
+		) -> DispatchResult {
+			// Check if the caller is the admin origin
+			T::AdminOrigin::ensure_origin(origin)?;
+
+			// Check if the bounds are consistent
+			if let Some(bounds) = &bounds {
+				ensure!(bounds.min <= bounds.max, Error::<T>::InvalidParameterBounds);
+				ensure!(
+					parameter != MarketParameter::CollateralFactor ||
+						bounds.max <= FixedU128::one(),
+					Error::<T>::InvalidParameterBounds
+				);
+			}
+
+			// Update bounds
+			Bounds::<T>::mutate(parameter, |current| *current = bounds.clone());
+
+			// Emit an event
+			Self::deposit_event(Event::ParameterBoundsSet(parameter, bounds));
+
+			Ok(())
+		}
+
+		/// Cancel a pending change of a market parameter
+		#[pallet::call_index(15)]
+		#[pallet::weight(<T as Config>::WeightInfo::cancel_parameter_change())]
+		pub fn cancel_parameter_change(
+			origin: OriginFor<T>,
+			asset_id: AssetIdOf<T>,
+			parameter: MarketParameter,
+		) -> DispatchResult {
+			// Check if the caller is the admin origin or the authority account
+			Self::ensure_admin(origin)?;
+
+			// Check if the change is pending
+			ensure!(
+				PendingParameterChanges::<T>::contains_key(asset_id, parameter),
+				Error::<T>::NoPendingParameterChange
+			);
+
+			// Remove pending change
+			PendingParameterChanges::<T>::remove(asset_id, parameter);
+
+			// Emit an event
+			Self::deposit_event(Event::ParameterChangeCancelled(asset_id, parameter));
+
+			Ok(())
+		}
+
+		/// Pause an operation until it's unpaused
+		#[pallet::call_index(16)]
+		#[pallet::weight(<T as Config>::WeightInfo::pause_operation())]
+		pub fn pause_operation(origin: OriginFor<T>, operation: Operation) -> DispatchResult {
+			// Check if the caller is the pause origin
+			T::PauseOrigin::ensure_origin(origin)?;
+
+			// Pause operation
+			PausedOperations::<T>::insert(operation, true);
+
+			// Emit an event
+			Self::deposit_event(Event::OperationPaused(operation));
+
+			Ok(())
+		}
+
+		/// Unpause a paused operation
+		#[pallet::call_index(17)]
+		#[pallet::weight(<T as Config>::WeightInfo::unpause_operation())]
+		pub fn unpause_operation(origin: OriginFor<T>, operation: Operation) -> DispatchResult {
+			// Check if the caller is the pause origin
+			T::PauseOrigin::ensure_origin(origin)?;
+
+			// Unpause operation
+			PausedOperations::<T>::remove(operation);
+
+			// Emit an event
+			Self::deposit_event(Event::OperationUnpaused(operation));
+
+			Ok(())
+		}
+
+		/// Set or remove the supply and borrow caps of a market
+		#[pallet::call_index(18)]
+		#[pallet::weight(<T as Config>::WeightInfo::set_caps())]
+		pub fn set_caps(
+			origin: OriginFor<T>,
+			asset_id: AssetIdOf<T>,
+			supply_cap: Option<BalanceOf<T>>,
+			borrow_cap: Option<BalanceOf<T>>,
+		) -> DispatchResult {
+			// Check if the caller is the admin origin or the authority account
+			Self::ensure_admin(origin)?;
+
+			// Check if the market exists
+			ensure!(Self::market_exists(asset_id), Error::<T>::MarketNotFound);
+
+			// Update caps
+			SupplyCap::<T>::mutate(asset_id, |cap| *cap = supply_cap);
+			BorrowCap::<T>::mutate(asset_id, |cap| *cap = borrow_cap);
+
+			// Emit an event
+			Self::deposit_event(Event::CapsSet(asset_id, supply_cap, borrow_cap));
+
+			Ok(())
+		}
+
+		/// Set or remove the supply and borrow caps of all markets
+		#[pallet::call_index(19)]
+		#[pallet::weight(<T as Config>::WeightInfo::set_global_caps())]
+		pub fn set_global_caps(
+			origin: OriginFor<T>,
+			supply_cap: Option<BalanceOf<T>>,
+			borrow_cap: Option<BalanceOf<T>>,
+		) -> DispatchResult {
+			// Check if the caller is the admin origin or the authority account
+			Self::ensure_admin(origin)?;
+
+			// Update caps
+			GlobalSupplyCap::<T>::set(supply_cap);
+			GlobalBorrowCap::<T>::set(borrow_cap);
+
+			// Emit an event
+			Self::deposit_event(Event::GlobalCapsSet(supply_cap, borrow_cap));
+
+			Ok(())
+		}
+
+		/// Set the share of a market's deposits which can't be borrowed
+		#[pallet::call_index(20)]
+		#[pallet::weight(<T as Config>::WeightInfo::set_liquidity_buffer())]
+		pub fn set_liquidity_buffer(
+			origin: OriginFor<T>,
+			asset_id: AssetIdOf<T>,
+			buffer: FixedU128,
+		) -> DispatchResult {
+			// Check if the caller is the admin origin or the authority account
+			Self::ensure_admin(origin)?;
+
+			// Check if the market exists
+			ensure!(Self::market_exists(asset_id), Error::<T>::MarketNotFound);
+
+			// Check if the buffer is not greater than one
+			ensure!(buffer <= FixedU128::one(), Error::<T>::InvalidLiquidityBuffer);
+
+			// Update buffer
+			LiquidityBuffer::<T>::insert(asset_id, buffer);
+
+			// Emit an event
+			Self::deposit_event(Event::LiquidityBufferSet(asset_id, buffer));
+
+			Ok(())
+		}
+
+		/// Lend pool funds without collateral for the duration of the call, which is dispatched
+		/// with the caller's origin. The amount plus the flash loan fee is paid back from the
+		/// caller's balance once the call returns, otherwise the whole loan is reverted. Only
+		/// calls allowed by `CallFilter` can be dispatched.
+		#[transactional]
+		#[pallet::call_index(21)]
+		#[pallet::weight({
+			let dispatch_info = call.get_dispatch_info();
+			<T as Config>::WeightInfo::flash_loan().saturating_add(dispatch_info.weight)
+		})]
+		pub fn flash_loan(
+			origin: OriginFor<T>,
+			asset_id: AssetIdOf<T>,
+			amount: BalanceOf<T>,
+			call: Box<<T as Config>::RuntimeCall>,
+		) -> DispatchResult {
+			// Check that the extrinsic was signed and get the signer.
+			// This function will return an error if the extrinsic is not signed.
+			let user = ensure_signed(origin)?;
+
+			// Check if the operation is paused
+			Self::ensure_not_paused(Operation::FlashLoan)?;
+
+			// Check if the market exists
+			ensure!(Self::market_exists(asset_id), Error::<T>::MarketNotFound);
+
+			// Check if the loan amount is greater than 0
+			ensure!(amount > <BalanceOf<T>>::zero(), Error::<T>::InvalidFlashLoanAmount);
+
+			// Check if the call is allowed to be dispatched with the loan
+			ensure!(T::CallFilter::contains(&call), Error::<T>::FlashLoanCallFiltered);
+
+			// Check if pallet has enough funds
+			ensure!(
+				amount <= Self::free_balance(asset_id, &Self::account_id()),
+				Error::<T>::PalletHasNotEnoughFunds
+			);
+
+			// Accrue interest of the market up to the current block
+			Self::accrue_interest(asset_id);
+
+			// Calculate the fee
+			let fee: BalanceOf<T> = FixedU128::from_inner(amount.saturated_into::<u128>())
+				.saturating_mul(FlashLoanFee::<T>::get(asset_id))
+				.into_inner()
+				.saturated_into();
+
+			// Lend funds and dispatch the call with them
+			Self::transfer(asset_id, &Self::account_id(), &user, amount)?;
+
+			call.dispatch(frame_system::RawOrigin::Signed(user.clone()).into())
+				.map_err(|e| e.error)?;
+
+			// Take back the loan with its fee, reverting everything if the user can't pay
+			Self::transfer(asset_id, &user, &Self::account_id(), amount.saturating_add(fee))?;
+
+			// Split the fee between reserves and depositors
+			Self::distribute_flash_loan_fee(asset_id, fee);
+
+			// Emit an event
+			Self::deposit_event(Event::FlashLoanRepaid(user, asset_id, amount, fee));
+
+			Ok(())
+		}
+
+		/// Set the share of a flash loan charged as fee
+		#[pallet::call_index(22)]
+		#[pallet::weight(<T as Config>::WeightInfo::set_flash_loan_fee())]
+		pub fn set_flash_loan_fee(
+			origin: OriginFor<T>,
+			asset_id: AssetIdOf<T>,
+			fee: FixedU128,
+		) -> DispatchResult {
+			// Check if the caller is the admin origin or the authority account
+			Self::ensure_admin(origin)?;
+
+			// Check if the market exists
+			ensure!(Self::market_exists(asset_id), Error::<T>::MarketNotFound);
+
+			// Check if the fee is not greater than one
+			ensure!(fee <= FixedU128::one(), Error::<T>::InvalidFlashLoanFee);
+
+			// Update fee
+			FlashLoanFee::<T>::insert(asset_id, fee);
+
+			// Emit an event
+			Self::deposit_event(Event::FlashLoanFeeSet(asset_id, fee));
+
+			Ok(())
+		}
+
+		/// Move reserves of a market into its insurance fund
+		#[pallet::call_index(23)]
+		#[pallet::weight(<T as Config>::WeightInfo::fund_insurance())]
+		pub fn fund_insurance(
+			origin: OriginFor<T>,
+			asset_id: AssetIdOf<T>,
+			amount: BalanceOf<T>,
+		) -> DispatchResult {
+			// Check if the caller is allowed to manage reserves
+			T::ReserveOrigin::ensure_origin(origin)?;
+
+			// Check if the market exists
+			ensure!(Self::market_exists(asset_id), Error::<T>::MarketNotFound);
+
+			// Accrue interest of the market up to the current block
+			Self::accrue_interest(asset_id);
+
+			// Check if market has enough reserves
+			ensure!(amount <= TotalReserves::<T>::get(asset_id), Error::<T>::InsufficientReserves);
+
+			// Move reserves to the insurance fund, funds stay in the pallet
+			TotalReserves::<T>::mutate(asset_id, |total| *total = total.saturating_sub(amount));
+			InsuranceFund::<T>::mutate(asset_id, |total| *total = total.saturating_add(amount));
+
+			// Emit an event
+			Self::deposit_event(Event::InsuranceFunded(asset_id, amount));
+
+			Ok(())
+		}
+
+		/// Put funds on hold in the caller's account as collateral only. Held funds count
+		/// towards the borrowing power and can be seized by liquidators, but they aren't lent out
+		/// and earn no interest. Only supported in the native market.
+		#[transactional]
+		#[pallet::call_index(24)]
+		#[pallet::weight(<T as Config>::WeightInfo::deposit_collateral())]
+		pub fn deposit_collateral(
+			origin: OriginFor<T>,
+			asset_id: AssetIdOf<T>,
+			amount: BalanceOf<T>,
+		) -> DispatchResult {
+			// Check that the extrinsic was signed and get the signer.
+			// This function will return an error if the extrinsic is not signed.
+			let user = ensure_signed(origin)?;
+
+			// Check if the operation is paused
+			Self::ensure_not_paused(Operation::Deposit)?;
+
+			// Check if the market exists and holds collateral
+			ensure!(Self::market_exists(asset_id), Error::<T>::MarketNotFound);
+			ensure!(asset_id == T::NativeAssetId::get(), Error::<T>::CollateralOnlyUnsupported);
+
+			// Check if the deposited amount is greater than 0
+			ensure!(amount > <BalanceOf<T>>::zero(), Error::<T>::InvalidDepositAmount);
+
+			// Check if user has enough funds
+			ensure!(Self::free_balance(asset_id, &user) >= amount, Error::<T>::InsufficientBalance);
+
+			// Hold funds in the user's account
+			<T::Currency as fungible::MutateHold<_>>::hold(
+				&HoldReason::Collateral.into(),
+				&user,
+				amount,
+			)?;
+
+			// Emit an event
+			Self::deposit_event(Event::CollateralDeposited(user, asset_id, amount));
+
+			Ok(())
+		}
+
+		/// Release collateral held in the caller's account, as long as the remaining borrowing
+		/// power covers the caller's debt
+		#[transactional]
+		#[pallet::call_index(25)]
+		#[pallet::weight(<T as Config>::WeightInfo::withdraw_collateral(T::MaxMarkets::get()))]
+		pub fn withdraw_collateral(
+			origin: OriginFor<T>,
+			asset_id: AssetIdOf<T>,
+			amount: BalanceOf<T>,
+		) -> DispatchResult {
+			// Check that the extrinsic was signed and get the signer.
+			// This function will return an error if the extrinsic is not signed.
+			let user = ensure_signed(origin)?;
+
+			// Check if the operation is paused
+			Self::ensure_not_paused(Operation::Withdraw)?;
+
+			// Check if the market exists
+			ensure!(Self::market_exists(asset_id), Error::<T>::MarketNotFound);
+
+			// Check if user holds enough collateral
+			let held = Self::held_collateral(asset_id, &user);
+			ensure!(!held.is_zero(), Error::<T>::NoFundsDeposited);
+			ensure!(amount <= held, Error::<T>::UserHasNotEnoughFunds);
+
+			// Release funds in the user's account
+			<T::Currency as fungible::MutateHold<_>>::release(
+				&HoldReason::Collateral.into(),
+				&user,
+				amount,
+				Precision::Exact,
+			)?;
+
+			// Check if the remaining borrowing power covers the debt, if any
+			if Self::has_debt(&user) {
+				let (borrowing_power, debt_value) = Self::get_account_liquidity(&user)?;
+				ensure!(debt_value <= borrowing_power, Error::<T>::UnallowedWithdrawAmount);
+			}
+
+			// Emit an event
+			Self::deposit_event(Event::CollateralWithdrawn(user, asset_id, amount));
+
+			Ok(())
+		}
+	}
+
+	impl<T: Config> Pallet<T> {
+		/// Check if the origin is the admin origin or signed by the authority account
+		fn ensure_admin(origin: OriginFor<T>) -> DispatchResult {
+			let origin = match T::AdminOrigin::try_origin(origin) {
+				Ok(_) => return Ok(()),
+				Err(origin) => origin,
+			};
+
+			// Check that the extrinsic was signed and get the signer.
+			// This function will return an error if the extrinsic is not signed.
+			let user = ensure_signed(origin)?;
+
+			ensure!(
+				AuthorityAccount::<T>::get().as_ref() == Some(&user),
+				Error::<T>::UnauthorizedUserAction
+			);
+
+			Ok(())
+		}
+
+		/// Check if depositing the amount keeps the market and all markets under their supply
+		/// caps
+		fn ensure_supply_caps(asset_id: AssetIdOf<T>, amount: BalanceOf<T>) -> DispatchResult {
+			if let Some(cap) = SupplyCap::<T>::get(asset_id) {
+				ensure!(
+					TotalDeposits::<T>::get(asset_id).saturating_add(amount) <= cap,
+					Error::<T>::SupplyCapExceeded
+				);
+			}
+
+			if let Some(cap) = GlobalSupplyCap::<T>::get() {
+				let value = Self::get_total_value(asset_id, amount, |state| state.total_deposits)?;
+				ensure!(
+					value <= FixedU128::from_inner(cap.saturated_into::<u128>()),
+					Error::<T>::GlobalSupplyCapExceeded
+				);
+			}
+
+			Ok(())
+		}
+
+		/// Check if borrowing the amount keeps the market and all markets under their borrow
+		/// caps
+		fn ensure_borrow_caps(asset_id: AssetIdOf<T>, amount: BalanceOf<T>) -> DispatchResult {
+			if let Some(cap) = BorrowCap::<T>::get(asset_id) {
+				ensure!(
+					TotalBorrows::<T>::get(asset_id).saturating_add(amount) <= cap,
+					Error::<T>::BorrowCapExceeded
+				);
+			}
+
+			if let Some(cap) = GlobalBorrowCap::<T>::get() {
+				let value = Self::get_total_value(asset_id, amount, |state| state.total_borrows)?;
+				ensure!(
+					value <= FixedU128::from_inner(cap.saturated_into::<u128>()),
+					Error::<T>::GlobalBorrowCapExceeded
+				);
+			}
+
+			Ok(())
+		}
+
+		/// Get value of a market total across all markets with accrued interest, after adding
+		/// the amount to the given market
+		fn get_total_value(
+			asset_id: AssetIdOf<T>,
+			amount: BalanceOf<T>,
+			total: impl Fn(&MarketState<BalanceOf<T>>) -> BalanceOf<T>,
+		) -> Result<FixedU128, DispatchError> {
+			let mut value = FixedU128::from_inner(0);
+
+			for market_id in Self::market_ids() {
+				let mut market_total = total(&Self::accrued_market_state(market_id));
+				if market_id == asset_id {
+					market_total = market_total.saturating_add(amount);
+				}
+
+				if market_total.is_zero() {
+					continue
+				}
+
+				let price =
+					T::PriceOracle::get_price(&market_id).ok_or(Error::<T>::PriceUnavailable)?;
+				value = value.saturating_add(Self::to_value(market_total, price));
+			}
+
+			Ok(value)
+		}
+
+		/// Check if the operation is paused, every operation is paused while accounts are
+		/// migrated
+		pub fn is_paused(operation: Operation) -> bool {
+			PausedOperations::<T>::get(operation) || AccountsMigration::<T>::exists()
+		}
+
+		/// Fail with `MigrationInProgress` while accounts are migrated and with
+		/// `OperationPaused` if the operation is paused
+		fn ensure_not_paused(operation: Operation) -> DispatchResult {
+			ensure!(!AccountsMigration::<T>::exists(), Error::<T>::MigrationInProgress);
+			ensure!(!PausedOperations::<T>::get(operation), Error::<T>::OperationPaused);
+
+			Ok(())
+		}
+
+		/// Current value of a market parameter
+		pub fn parameter_value(asset_id: AssetIdOf<T>, parameter: MarketParameter) -> FixedU128 {
+			match parameter {
+				MarketParameter::DepositRate => DepositRate::<T>::get(asset_id),
+				MarketParameter::BorrowingRate => BorrowingRate::<T>::get(asset_id),
+				MarketParameter::CollateralFactor => CollateralFactor::<T>::get(asset_id),
+			}
+		}
+
+		/// Check if the new value of a parameter is within its bounds and, if the parameter is
+		/// already set, close enough to its current value
+		fn ensure_within_bounds(
+			parameter: MarketParameter,
+			value: FixedU128,
+			current_value: Option<FixedU128>,
+		) -> DispatchResult {
+			// Collateral can't be worth more than the deposit
+			ensure!(
+				parameter != MarketParameter::CollateralFactor || value <= FixedU128::one(),
+				Error::<T>::InvalidCollateralFactor
+			);
+
+			let Some(bounds) = Bounds::<T>::get(parameter) else { return Ok(()) };
+
+			ensure!(bounds.min <= value && value <= bounds.max, Error::<T>::ParameterOutOfBounds);
+
+			if let Some(current_value) = current_value {
+				let change = if value > current_value {
+					value.saturating_sub(current_value)
+				} else {
+					current_value.saturating_sub(value)
+				};
+				ensure!(change <= bounds.max_change, Error::<T>::ParameterChangeTooLarge);
+			}
+
+			Ok(())
+		}
+
+		/// Queue a change of a market parameter to be applied after `ParameterChangeDelay`,
+		/// replacing the pending change of the parameter
+		fn schedule_parameter_change(
+			asset_id: AssetIdOf<T>,
+			parameter: MarketParameter,
+			value: FixedU128,
+		) -> DispatchResult {
+			// Check if the value is within the bounds of the parameter
+			Self::ensure_within_bounds(
+				parameter,
+				value,
+				Some(Self::parameter_value(asset_id, parameter)),
+			)?;
+
+			let delay = T::ParameterChangeDelay::get();
+			if delay.is_zero() {
+				PendingParameterChanges::<T>::remove(asset_id, parameter);
+				Self::apply_parameter_change(asset_id, parameter, value);
+				return Ok(())
+			}
+
+			let effective_at = frame_system::Pallet::<T>::block_number().saturating_add(delay);
+			PendingParameterChanges::<T>::insert(
+				asset_id,
+				parameter,
+				PendingChange { value, effective_at },
+			);
+
+			// Emit an event
+			Self::deposit_event(Event::ParameterChangeScheduled(
+				asset_id,
+				parameter,
+				value,
+				effective_at,
+			));
+
+			Ok(())
+		}
+
+		/// Set a market parameter, accruing interest at the previous rate first
+		fn apply_parameter_change(
+			asset_id: AssetIdOf<T>,
+			parameter: MarketParameter,
+			value: FixedU128,
+		) {
+			match parameter {
+				MarketParameter::DepositRate => {
+					Self::accrue_interest(asset_id);
+					DepositRate::<T>::insert(asset_id, value);
+				},
+				MarketParameter::BorrowingRate => {
+					Self::accrue_interest(asset_id);
+					BorrowingRate::<T>::insert(asset_id, value);
+				},
+				MarketParameter::CollateralFactor => {
+					CollateralFactor::<T>::insert(asset_id, value);
+				},
+			}
+
+			// Emit an event
+			Self::deposit_event(Event::ParameterChangeApplied(asset_id, parameter, value));
+		}
+
+		/// Apply the pending parameter changes whose delay has passed, returns the number of
+		/// pending changes
+		pub(crate) fn apply_parameter_changes(now: BlockNumber<T>) -> u32 {
+			let mut pending = 0u32;
+			let due = PendingParameterChanges::<T>::iter()
+				.inspect(|_| pending = pending.saturating_add(1))
+				.filter(|(_, _, change)| change.effective_at <= now)
+				.collect::<Vec<_>>();
+
+			for (asset_id, parameter, change) in due {
+				PendingParameterChanges::<T>::remove(asset_id, parameter);
+				Self::apply_parameter_change(asset_id, parameter, change.value);
+			}
+
+			pending
+		}
+
+		/// Repay `repay_amount` of the borrower's debt and move the seized collateral to the
+		/// liquidator
+		fn do_liquidate(
+			liquidator: AccountIdOf<T>,
+			borrower: AccountIdOf<T>,
+			debt_asset_id: AssetIdOf<T>,
+			collateral_asset_id: AssetIdOf<T>,
+			repay_amount: BalanceOf<T>,
+		) -> DispatchResult {
+			// Check if the operation is paused
+			Self::ensure_not_paused(Operation::Liquidate)?;
+
+			// Check if the liquidator is not the borrower
+			ensure!(liquidator != borrower, Error::<T>::SelfLiquidation);
+
+			// Check if both markets exist
+			ensure!(Self::market_exists(debt_asset_id), Error::<T>::MarketNotFound);
+			ensure!(Self::market_exists(collateral_asset_id), Error::<T>::MarketNotFound);
+
+			// Check if the repay amount is greater than 0
+			ensure!(repay_amount > <BalanceOf<T>>::zero(), Error::<T>::InvalidRepayAmount);
+
+			// Check if the liquidator has enough funds
+			ensure!(
+				Self::free_balance(debt_asset_id, &liquidator) >= repay_amount,
+				Error::<T>::InsufficientBalance
+			);
+
+			// Accrue interest of both markets up to the current block
+			Self::accrue_interest(debt_asset_id);
+			Self::accrue_interest(collateral_asset_id);
+
+			// Check if the borrower is undercollateralized
+			ensure!(Self::is_liquidatable(&borrower)?, Error::<T>::AddressNotLiquidatable);
+
+			// Check if the repay amount is within the close factor share of the debt
+			let debt = Self::get_debt_amount(debt_asset_id, borrower.clone());
+			ensure!(!debt.is_zero(), Error::<T>::NothingToRepay);
+
+			let max_repay_amount: BalanceOf<T> =
+				FixedU128::from_inner(debt.saturated_into::<u128>())
+					.saturating_mul(T::CloseFactor::get())
+					.into_inner()
+					.saturated_into();
+			ensure!(repay_amount <= max_repay_amount, Error::<T>::ExcessiveRepayAmount);
+
+			// Calculate collateral worth the repaid value plus the liquidation incentive
+			let seize_amount =
+				Self::get_seize_amount(debt_asset_id, collateral_asset_id, repay_amount)?;
+
+			ensure!(
+				seize_amount <= Self::get_collateral_amount(collateral_asset_id, &borrower),
+				Error::<T>::InsufficientCollateral
+			);
+
+			// Seize deposits first and the collateral held in the borrower's account for the rest
+			let seized_deposit =
+				seize_amount.min(Self::get_lending_amount(collateral_asset_id, borrower.clone()));
+			let seized_held = seize_amount.saturating_sub(seized_deposit);
+
+			// Transfer repaid funds from liquidator to pallet
+			Self::transfer(debt_asset_id, &liquidator, &Self::account_id(), repay_amount)?;
+
+			// Update positions of both accounts, seized deposits stay deposited and seized held
+			// collateral is paid out to the liquidator
+			Self::decrease_debt(debt_asset_id, &borrower, repay_amount);
+			if !seized_deposit.is_zero() {
+				Self::decrease_deposit(collateral_asset_id, &borrower, seized_deposit)?;
+				Self::increase_deposit(collateral_asset_id, &liquidator, seized_deposit)?;
+			}
+			if !seized_held.is_zero() {
+				Self::seize_held_collateral(
+					collateral_asset_id,
+					&borrower,
+					&liquidator,
+					seized_held,
+				)?;
+			}
+
+			// Emit an event
+			Self::deposit_event(Event::AddressLiquidated(
+				liquidator,
+				borrower,
+				debt_asset_id,
+				repay_amount,
+				collateral_asset_id,
+				seize_amount,
+			));
+
+			Ok(())
+		}
+
+		/// Get user's balance
+		pub fn get_lending_amount(asset_id: AssetIdOf<T>, user: T::AccountId) -> BalanceOf<T> {
+			// Get receipt token balance and current supply index
+			let receipt_balance = Self::receipt_balance(asset_id, &user);
+			let supply_index = Self::accrued_market_state(asset_id).supply_index;
+
+			// Convert receipt tokens into the underlying asset
+			Self::apply_multiplier(receipt_balance, supply_index)
+		}
+
+		/// Get user's receipt token balance in the given market
+		pub fn receipt_balance(asset_id: AssetIdOf<T>, user: &T::AccountId) -> BalanceOf<T> {
+			ReceiptAssets::<T>::get(asset_id)
+				.map(|receipt_asset_id| {
+					<T::Assets as fungibles::Inspect<_>>::balance(receipt_asset_id, user)
+				})
+				.unwrap_or_else(Zero::zero)
+		}
+
+		/// Get the receipt tokens of the account which can't be transferred because they back
+		/// its debt. Meant to be used as the freezer of the assets pallet, it returns `None` for
+		/// other assets and while the pallet burns receipt tokens itself.
+		pub fn frozen_receipts(
+			receipt_asset_id: AssetIdOf<T>,
+			user: &T::AccountId,
+		) -> Option<BalanceOf<T>> {
+			if ReceiptsUnfrozen::<T>::get() {
+				return None
+			}
+
+			// Accounts without debt are never frozen, unless their debts are still migrated
+			let migrating = AccountsMigration::<T>::exists();
+			if !migrating && !Self::has_debt(user) {
+				return None
+			}
+
+			let asset_id = Self::market_ids()
+				.into_iter()
+				.find(|asset_id| ReceiptAssets::<T>::get(asset_id) == Some(receipt_asset_id))?;
+			let receipt_balance = Self::receipt_balance(asset_id, user);
+			if receipt_balance.is_zero() {
+				return None
+			}
+
+			// Freeze all receipt tokens while the debts can't be read
+			if migrating {
+				return Some(receipt_balance)
+			}
+
+			let collateral_factor = CollateralFactor::<T>::get(asset_id);
+			if collateral_factor.is_zero() {
+				return None
+			}
+
+			// Freeze all receipt tokens if the account liquidity can't be priced
+			let (borrowing_power, debt_value) = match Self::get_account_liquidity(user) {
+				Ok(liquidity) => liquidity,
+				Err(_) => return Some(receipt_balance),
+			};
+			if debt_value.is_zero() {
+				return None
+			}
+			let price = match T::PriceOracle::get_price(&asset_id) {
+				Some(price) => price,
+				None => return Some(receipt_balance),
+			};
+
+			// Only receipt tokens worth the borrowing power in excess of the debt can be
+			// transferred
+			let surplus = borrowing_power
+				.saturating_sub(debt_value)
+				.checked_div(&collateral_factor)
+				.unwrap_or_default();
+			let transferable = Self::to_receipt_amount(
+				Self::from_value(surplus, price),
+				Self::accrued_market_state(asset_id).supply_index,
+			);
+
+			Some(receipt_balance.saturating_sub(transferable))
+		}
+
+		/// Whether the account has a debt in any market
+		pub fn has_debt(user: &T::AccountId) -> bool {
+			!BorrowedMarkets::<T>::get(user).is_zero()
+		}
+
+		/// Get user's collateral held in his account
+		pub fn held_collateral(asset_id: AssetIdOf<T>, user: &T::AccountId) -> BalanceOf<T> {
+			if asset_id != T::NativeAssetId::get() {
+				return Zero::zero()
+			}
+
+			<T::Currency as fungible::InspectHold<_>>::balance_on_hold(
+				&HoldReason::Collateral.into(),
+				user,
+			)
+		}
+
+		/// Get user's collateral in the given market, i.e. deposit balance + held collateral
+		pub fn get_collateral_amount(asset_id: AssetIdOf<T>, user: &T::AccountId) -> BalanceOf<T> {
+			Self::get_lending_amount(asset_id, user.clone())
+				.saturating_add(Self::held_collateral(asset_id, user))
+		}
+
+		/// Get user's debt
+		pub fn get_debt_amount(asset_id: AssetIdOf<T>, user: T::AccountId) -> BalanceOf<T> {
+			// Get address info and current borrow index
+			let address_info = Accounts::<T>::get(asset_id, user);
+			let borrow_index = Self::accrued_market_state(asset_id).borrow_index;
+
+			// Calculate principal with accrued interest
+			Self::get_principal_with_accrued_interest(
+				address_info.borrow_principal,
+				address_info.borrow_index,
+				borrow_index,
+			)
+		}
+
+		/// Get user's allowed borrowing amount in the given market
+		pub fn get_allowed_borrowing_amount(
+			asset_id: AssetIdOf<T>,
+			user: T::AccountId,
+		) -> BalanceOf<T> {
+			// Get price of the borrowed asset
+			let price = match T::PriceOracle::get_price(&asset_id) {
+				Some(price) => price,
+				None => return <BalanceOf<T>>::zero(),
+			};
 
-			Ok(())
+			// Calculate allowed borrowing amount
+			match Self::get_account_liquidity(&user) {
+				Ok((borrowing_power, debt_value)) =>
+					Self::from_value(borrowing_power.saturating_sub(debt_value), price),
+				Err(_) => <BalanceOf<T>>::zero(),
+			}
 		}
-	}
 
-	impl<T: Config> Pallet<T> {
-		/// Get user's balance
-		pub fn get_lending_amount(user: T::AccountId) -> BalanceOf<T> {
-			// Get address info and check if deposit principal is zero
-			let address_info = Accounts::<T>::get(user);
+		/// Get user's borrowing power (collateral value weighted by collateral factors) and
+		/// debt value across all markets
+		pub fn get_account_liquidity(
+			user: &T::AccountId,
+		) -> Result<(FixedU128, FixedU128), DispatchError> {
+			let mut borrowing_power = FixedU128::zero();
+			let mut debt_value = FixedU128::zero();
 
-			if address_info.deposit_principal == <BalanceOf<T>>::zero() {
-				return <BalanceOf<T>>::zero()
-			}
+			for asset_id in Self::market_ids() {
+				let deposit_balance = Self::get_collateral_amount(asset_id, user);
+				let debt_balance = Self::get_debt_amount(asset_id, user.clone());
 
-			// Calculate principal with accrued interest
-			let current_block = frame_system::Pallet::<T>::block_number();
-			let balance = Self::get_principal_with_accrued_interest(
-				current_block,
-				address_info.deposit_date,
-				address_info.deposit_principal,
-				DepositRate::<T>::get(),
-			);
+				if deposit_balance.is_zero() && debt_balance.is_zero() {
+					continue
+				}
+
+				let price =
+					T::PriceOracle::get_price(&asset_id).ok_or(Error::<T>::PriceUnavailable)?;
+
+				borrowing_power = borrowing_power.saturating_add(
+					Self::to_value(deposit_balance, price)
+						.saturating_mul(CollateralFactor::<T>::get(asset_id)),
+				);
+				debt_value = debt_value.saturating_add(Self::to_value(debt_balance, price));
+			}
 
-			balance
+			Ok((borrowing_power, debt_value))
 		}
 
-		/// Get user's debt
-		pub fn get_debt_amount(user: T::AccountId) -> BalanceOf<T> {
-			// Get address info and check if borrow principal is zero
-			let address_info = Accounts::<T>::get(user);
+		/// Check if the user's debt exceeds the value of all his collateral, so that it can't be
+		/// repaid by liquidations
+		pub fn is_insolvent(user: &T::AccountId) -> Result<bool, DispatchError> {
+			let mut collateral_value = FixedU128::zero();
+			let mut debt_value = FixedU128::zero();
 
-			if address_info.borrow_principal == <BalanceOf<T>>::zero() {
-				return <BalanceOf<T>>::zero()
-			}
+			for asset_id in Self::market_ids() {
+				let deposit_balance = Self::get_collateral_amount(asset_id, user);
+				let debt_balance = Self::get_debt_amount(asset_id, user.clone());
 
-			// Calculate elapsed blocks
-			let current_block = frame_system::Pallet::<T>::block_number();
-			let balance = Self::get_principal_with_accrued_interest(
-				current_block,
-				address_info.borrow_date,
-				address_info.borrow_principal,
-				BorrowingRate::<T>::get(),
-			);
+				if deposit_balance.is_zero() && debt_balance.is_zero() {
+					continue
+				}
 
-			balance
-		}
+				let price =
+					T::PriceOracle::get_price(&asset_id).ok_or(Error::<T>::PriceUnavailable)?;
 
-		/// Get user's allowed borrowing amount
-		pub fn get_allowed_borrowing_amount(
-			user: T::AccountId,
-			mut borrowing_balance: BalanceOf<T>,
-			is_rpc: bool,
-		) -> BalanceOf<T> {
-			// Get borrowing balance and deposit principal
-			let deposit_balance = Self::get_lending_amount(user.clone());
-			if is_rpc {
-				borrowing_balance = Self::get_debt_amount(user.clone());
+				collateral_value =
+					collateral_value.saturating_add(Self::to_value(deposit_balance, price));
+				debt_value = debt_value.saturating_add(Self::to_value(debt_balance, price));
 			}
 
-			let deposit_balance_fixed =
-				FixedU128::from_inner(deposit_balance.saturated_into::<u128>());
-			let borrowing_balance_fixed =
-				FixedU128::from_inner(borrowing_balance.saturated_into::<u128>());
+			Ok(debt_value > collateral_value)
+		}
 
-			// Calculate allowed borrowing amount
-			let allowed_borrowing_amount = (deposit_balance_fixed
-				.saturating_mul(CollateralFactor::<T>::get())
-				.saturating_sub(borrowing_balance_fixed))
-			.into_inner()
-			.saturated_into();
+		/// Check if the user's debt is not covered by his borrowing power
+		pub fn is_liquidatable(user: &T::AccountId) -> Result<bool, DispatchError> {
+			let (borrowing_power, debt_value) = Self::get_account_liquidity(user)?;
+
+			Ok(!debt_value.is_zero() && debt_value > borrowing_power)
+		}
 
-			allowed_borrowing_amount
+		/// Get user's health factor, i.e. borrowing power / debt value. The user can be
+		/// liquidated once it drops below one. Returns `None` if the user has no debt.
+		pub fn get_health_factor(
+			user: &T::AccountId,
+		) -> Result<Option<BalanceOf<T>>, DispatchError> {
+			let (borrowing_power, debt_value) = Self::get_account_liquidity(user)?;
+
+			Ok(borrowing_power
+				.checked_div(&debt_value)
+				.map(|health_factor| health_factor.into_inner().saturated_into()))
 		}
 
 		/// Get deposit APY
-		pub fn get_deposit_apy() -> BalanceOf<T> {
-			let deposit_apy = (FixedU128::one().saturating_add(DepositRate::<T>::get()))
-				.saturating_pow(T::NumberOfBlocksYearly::get() as usize)
+		pub fn get_deposit_apy(asset_id: AssetIdOf<T>) -> BalanceOf<T> {
+			let deposit_apy = (FixedU128::one().saturating_add(Self::get_deposit_rate(asset_id)))
+				.saturating_pow(T::AccrualClock::periods_per_year() as usize)
 				.saturating_sub(FixedU128::one());
 
 			deposit_apy.into_inner().saturated_into()
 		}
 
 		/// Get borrowing APY
-		pub fn get_borrowing_apy() -> BalanceOf<T> {
-			let borrowing_apy = (FixedU128::one().saturating_add(BorrowingRate::<T>::get()))
-				.saturating_pow(T::NumberOfBlocksYearly::get() as usize)
-				.saturating_sub(FixedU128::one());
+		pub fn get_borrowing_apy(asset_id: AssetIdOf<T>) -> BalanceOf<T> {
+			let borrowing_apy = (FixedU128::one()
+				.saturating_add(Self::get_borrowing_rate(asset_id)))
+			.saturating_pow(T::AccrualClock::periods_per_year() as usize)
+			.saturating_sub(FixedU128::one());
 
 			borrowing_apy.into_inner().saturated_into()
 		}
 
-		/// Get principal with accrued interest
+		/// Get deposit rate per period of the market at its current utilization
+		pub fn get_deposit_rate(asset_id: AssetIdOf<T>) -> FixedU128 {
+			T::InterestRateModel::supply_rate(
+				&asset_id,
+				Self::get_utilization(asset_id),
+				ReserveFactor::<T>::get(asset_id),
+			)
+		}
+
+		/// Get borrowing rate per period of the market at its current utilization
+		pub fn get_borrowing_rate(asset_id: AssetIdOf<T>) -> FixedU128 {
+			T::InterestRateModel::borrow_rate(&asset_id, Self::get_utilization(asset_id))
+		}
+
+		/// Get utilization of the market, i.e. total borrows / (cash + total borrows)
+		pub fn get_utilization(asset_id: AssetIdOf<T>) -> FixedU128 {
+			let borrows = TotalBorrows::<T>::get(asset_id).saturated_into::<u128>();
+
+			if borrows.is_zero() {
+				return FixedU128::zero()
+			}
+
+			let cash = Self::get_cash(asset_id).saturated_into::<u128>();
+
+			FixedU128::from_rational(borrows, cash.saturating_add(borrows))
+		}
+
+		/// Get funds of the market available for withdrawals and borrows, i.e.
+		/// total deposits + total reserves + insurance fund - total borrows - uncovered bad debt
+		pub fn get_cash(asset_id: AssetIdOf<T>) -> BalanceOf<T> {
+			TotalDeposits::<T>::get(asset_id)
+				.saturating_add(TotalReserves::<T>::get(asset_id))
+				.saturating_add(InsuranceFund::<T>::get(asset_id))
+				.saturating_sub(TotalBorrows::<T>::get(asset_id))
+				.saturating_sub(UncoveredBadDebt::<T>::get(asset_id))
+		}
+
+		/// Check if the market for the given asset is listed
+		pub fn market_exists(asset_id: AssetIdOf<T>) -> bool {
+			asset_id == T::NativeAssetId::get() || Markets::<T>::get(asset_id)
+		}
+
+		/// Get ids of all listed markets, starting with the native one
+		pub fn market_ids() -> Vec<AssetIdOf<T>> {
+			let mut market_ids = vec![T::NativeAssetId::get()];
+			market_ids.extend(
+				Markets::<T>::iter().filter_map(|(asset_id, listed)| listed.then_some(asset_id)),
+			);
+
+			market_ids
+		}
+
+		/// Convert an asset amount into its value in the common unit of account
+		fn to_value(amount: BalanceOf<T>, price: FixedU128) -> FixedU128 {
+			FixedU128::from_inner(amount.saturated_into::<u128>()).saturating_mul(price)
+		}
+
+		/// Convert a value in the common unit of account into an asset amount
+		fn from_value(value: FixedU128, price: FixedU128) -> BalanceOf<T> {
+			value.checked_div(&price).unwrap_or_default().into_inner().saturated_into()
+		}
+
+		/// Get amount of collateral worth the repaid value plus the liquidation incentive
+		fn get_seize_amount(
+			debt_asset_id: AssetIdOf<T>,
+			collateral_asset_id: AssetIdOf<T>,
+			repay_amount: BalanceOf<T>,
+		) -> Result<BalanceOf<T>, DispatchError> {
+			let debt_price =
+				T::PriceOracle::get_price(&debt_asset_id).ok_or(Error::<T>::PriceUnavailable)?;
+			let collateral_price = T::PriceOracle::get_price(&collateral_asset_id)
+				.ok_or(Error::<T>::PriceUnavailable)?;
+			let seize_value = Self::to_value(repay_amount, debt_price)
+				.saturating_mul(FixedU128::one().saturating_add(T::LiquidationIncentive::get()));
+
+			Ok(Self::from_value(seize_value, collateral_price))
+		}
+
+		/// Accrue interest of the market up to the current block
+		pub fn accrue_interest(asset_id: AssetIdOf<T>) {
+			let state = Self::accrued_market_state(asset_id);
+
+			BorrowIndex::<T>::insert(asset_id, state.borrow_index);
+			SupplyIndex::<T>::insert(asset_id, state.supply_index);
+			TotalBorrows::<T>::insert(asset_id, state.total_borrows);
+			TotalDeposits::<T>::insert(asset_id, state.total_deposits);
+			TotalReserves::<T>::insert(asset_id, state.total_reserves);
+			LastAccrued::<T>::insert(asset_id, T::AccrualClock::now());
+		}
+
+		/// Get indexes and totals of the market as if interest was accrued up to the current block
+		pub fn accrued_market_state(asset_id: AssetIdOf<T>) -> MarketState<BalanceOf<T>> {
+			let state = MarketState {
+				borrow_index: BorrowIndex::<T>::get(asset_id),
+				supply_index: SupplyIndex::<T>::get(asset_id),
+				total_borrows: TotalBorrows::<T>::get(asset_id),
+				total_deposits: TotalDeposits::<T>::get(asset_id),
+				total_reserves: TotalReserves::<T>::get(asset_id),
+			};
+
+			// Calculate elapsed periods, the market starts accruing at its first interaction
+			let last_accrued = LastAccrued::<T>::get(asset_id);
+			let elapsed_time = T::AccrualClock::now().saturating_sub(last_accrued);
+			if last_accrued.is_zero() || elapsed_time.is_zero() {
+				return state
+			}
+
+			// Calculate interest multipliers at the current rates
+			let borrow_multiplier = (FixedU128::one()
+				.saturating_add(Self::get_borrowing_rate(asset_id)))
+			.saturating_pow(elapsed_time as usize);
+			let supply_multiplier = (FixedU128::one()
+				.saturating_add(Self::get_deposit_rate(asset_id)))
+			.saturating_pow(elapsed_time as usize);
+
+			let total_borrows = Self::apply_multiplier(state.total_borrows, borrow_multiplier);
+			let total_deposits = Self::apply_multiplier(state.total_deposits, supply_multiplier);
+
+			// Reserve factor share of the interest paid by borrowers goes to reserves
+			let interest = total_borrows.saturating_sub(state.total_borrows);
+			let reserves_interest = FixedU128::from_inner(interest.saturated_into::<u128>())
+				.saturating_mul(ReserveFactor::<T>::get(asset_id))
+				.into_inner()
+				.saturated_into();
+			let total_reserves = state.total_reserves.saturating_add(reserves_interest);
+
+			MarketState {
+				borrow_index: state.borrow_index.saturating_mul(borrow_multiplier),
+				supply_index: state.supply_index.saturating_mul(supply_multiplier),
+				total_borrows,
+				total_deposits,
+				total_reserves,
+			}
+		}
+
+		/// Multiply a balance by an interest multiplier
+		fn apply_multiplier(amount: BalanceOf<T>, multiplier: FixedU128) -> BalanceOf<T> {
+			FixedU128::from_inner(amount.saturated_into::<u128>())
+				.saturating_mul(multiplier)
+				.into_inner()
+				.saturated_into()
+		}
+
+		/// Get principal with interest accrued since the index snapshot
 		fn get_principal_with_accrued_interest(
-			current_block: BlockNumber<T>,
-			date: BlockNumber<T>,
 			principal: BalanceOf<T>,
-			rate: FixedU128,
+			snapshot_index: FixedU128,
+			current_index: FixedU128,
 		) -> BalanceOf<T> {
-			// Calculate elapsed blocks
-			let elapsed_time_block_number = current_block - date;
-			let elapsed_time: u32 = TryInto::try_into(elapsed_time_block_number)
-				.ok()
-				.expect("blockchain will not exceed 2^32 blocks; qed");
+			if principal.is_zero() || snapshot_index.is_zero() {
+				return principal
+			}
 
-			// Calculate principal with accrued interest
-			let multiplier = (FixedU128::one().saturating_add(rate))
-				.saturating_pow(elapsed_time as usize)
-				.saturating_sub(FixedU128::one());
 			let principal_fixed = FixedU128::from_inner(principal.saturated_into::<u128>());
 
-			return principal_fixed
-				.saturating_add(principal_fixed.saturating_mul(multiplier))
+			principal_fixed
+				.saturating_mul(current_index)
+				.checked_div(&snapshot_index)
+				.unwrap_or_default()
+				.into_inner()
+				.saturated_into()
+		}
+
+		/// Get the receipt token of the market, creating it on first use. The token is owned by
+		/// the pallet account and doesn't require holders to have a native balance.
+		pub(crate) fn ensure_receipt_asset(
+			asset_id: AssetIdOf<T>,
+		) -> Result<AssetIdOf<T>, DispatchError> {
+			if let Some(receipt_asset_id) = ReceiptAssets::<T>::get(asset_id) {
+				return Ok(receipt_asset_id)
+			}
+
+			let receipt_asset_id = T::ReceiptAssetId::convert(asset_id);
+			ensure!(
+				!<T::Assets as fungibles::Inspect<_>>::asset_exists(receipt_asset_id),
+				Error::<T>::ReceiptAssetUnavailable
+			);
+
+			<T::Assets as fungibles::Create<_>>::create(
+				receipt_asset_id,
+				Self::account_id(),
+				true,
+				One::one(),
+			)?;
+			ReceiptAssets::<T>::insert(asset_id, receipt_asset_id);
+
+			Self::deposit_event(Event::ReceiptAssetCreated(asset_id, receipt_asset_id));
+
+			Ok(receipt_asset_id)
+		}
+
+		/// Add the reserve factor share of the fee to reserves and the rest to deposits by
+		/// raising the supply index. Everything goes to reserves when there are no deposits.
+		fn distribute_flash_loan_fee(asset_id: AssetIdOf<T>, fee: BalanceOf<T>) {
+			let total_deposits = TotalDeposits::<T>::get(asset_id);
+
+			let reserves_fee: BalanceOf<T> = if total_deposits.is_zero() {
+				fee
+			} else {
+				FixedU128::from_inner(fee.saturated_into::<u128>())
+					.saturating_mul(ReserveFactor::<T>::get(asset_id))
+					.into_inner()
+					.saturated_into()
+			};
+			let deposits_fee = fee.saturating_sub(reserves_fee);
+
+			if !deposits_fee.is_zero() {
+				let growth = FixedU128::from_rational(
+					deposits_fee.saturated_into::<u128>(),
+					total_deposits.saturated_into::<u128>(),
+				);
+				SupplyIndex::<T>::mutate(asset_id, |index| {
+					*index = index.saturating_mul(FixedU128::one().saturating_add(growth))
+				});
+				TotalDeposits::<T>::insert(asset_id, total_deposits.saturating_add(deposits_fee));
+			}
+
+			TotalReserves::<T>::mutate(asset_id, |total| {
+				*total = total.saturating_add(reserves_fee)
+			});
+		}
+
+		/// Convert an amount of the underlying asset into receipt tokens at the supply index,
+		/// rounding down
+		fn to_receipt_amount(amount: BalanceOf<T>, supply_index: FixedU128) -> BalanceOf<T> {
+			FixedU128::from_inner(amount.saturated_into::<u128>())
+				.checked_div(&supply_index)
+				.unwrap_or_default()
 				.into_inner()
 				.saturated_into()
 		}
 
+		/// Mint receipt tokens worth the amount at the current supply index to the account
+		pub(crate) fn increase_deposit(
+			asset_id: AssetIdOf<T>,
+			who: &T::AccountId,
+			amount: BalanceOf<T>,
+		) -> DispatchResult {
+			let receipt_asset_id = Self::ensure_receipt_asset(asset_id)?;
+			let receipt_amount = Self::to_receipt_amount(amount, SupplyIndex::<T>::get(asset_id));
+
+			<T::Assets as fungibles::Mutate<_>>::mint_into(receipt_asset_id, who, receipt_amount)?;
+
+			TotalDeposits::<T>::mutate(asset_id, |total| *total = total.saturating_add(amount));
+
+			Ok(())
+		}
+
+		/// Burn receipt tokens worth the amount at the current supply index from the account.
+		/// Rounds up so that the burnt tokens cover the amount.
+		fn decrease_deposit(
+			asset_id: AssetIdOf<T>,
+			who: &T::AccountId,
+			amount: BalanceOf<T>,
+		) -> DispatchResult {
+			let receipt_asset_id =
+				ReceiptAssets::<T>::get(asset_id).ok_or(Error::<T>::NoFundsDeposited)?;
+			let supply_index = SupplyIndex::<T>::get(asset_id);
+
+			let mut receipt_amount = Self::to_receipt_amount(amount, supply_index);
+			if Self::apply_multiplier(receipt_amount, supply_index) < amount {
+				receipt_amount = receipt_amount.saturating_add(One::one());
+			}
+			let receipt_amount = receipt_amount.min(Self::receipt_balance(asset_id, who));
+
+			// Lift the freeze of receipt tokens backing debt, callers check the account
+			// liquidity themselves
+			ReceiptsUnfrozen::<T>::put(true);
+			let burnt = <T::Assets as fungibles::Mutate<_>>::burn_from(
+				receipt_asset_id,
+				who,
+				receipt_amount,
+				Precision::Exact,
+				Fortitude::Force,
+			);
+			ReceiptsUnfrozen::<T>::kill();
+			burnt?;
+
+			TotalDeposits::<T>::mutate(asset_id, |total| *total = total.saturating_sub(amount));
+
+			Ok(())
+		}
+
+		/// Add to the debt of the account at the current borrow index
+		pub(crate) fn increase_debt(
+			asset_id: AssetIdOf<T>,
+			who: &T::AccountId,
+			amount: BalanceOf<T>,
+		) {
+			let borrow_index = BorrowIndex::<T>::get(asset_id);
+
+			let (had_debt, has_debt) = Accounts::<T>::mutate(asset_id, who, |address_info| {
+				let had_debt = !address_info.borrow_principal.is_zero();
+				address_info.borrow_principal = Self::get_principal_with_accrued_interest(
+					address_info.borrow_principal,
+					address_info.borrow_index,
+					borrow_index,
+				)
+				.saturating_add(amount);
+				address_info.borrow_index = borrow_index;
+				(had_debt, !address_info.borrow_principal.is_zero())
+			});
+			Self::count_borrowed_market(who, had_debt, has_debt);
+
+			TotalBorrows::<T>::mutate(asset_id, |total| *total = total.saturating_add(amount));
+		}
+
+		/// Subtract from the debt of the account at the current borrow index
+		fn decrease_debt(asset_id: AssetIdOf<T>, who: &T::AccountId, amount: BalanceOf<T>) {
+			let borrow_index = BorrowIndex::<T>::get(asset_id);
+
+			let (had_debt, has_debt) = Accounts::<T>::mutate(asset_id, who, |address_info| {
+				let had_debt = !address_info.borrow_principal.is_zero();
+				address_info.borrow_principal = Self::get_principal_with_accrued_interest(
+					address_info.borrow_principal,
+					address_info.borrow_index,
+					borrow_index,
+				)
+				.saturating_sub(amount);
+				address_info.borrow_index = borrow_index;
+				(had_debt, !address_info.borrow_principal.is_zero())
+			});
+			Self::count_borrowed_market(who, had_debt, has_debt);
+
+			TotalBorrows::<T>::mutate(asset_id, |total| *total = total.saturating_sub(amount));
+		}
+
+		/// Count the market in the borrowed markets of the account once its debt there starts,
+		/// and stop counting it once the debt is paid off
+		pub(crate) fn count_borrowed_market(who: &T::AccountId, had_debt: bool, has_debt: bool) {
+			match (had_debt, has_debt) {
+				(false, true) =>
+					BorrowedMarkets::<T>::mutate(who, |count| *count = count.saturating_add(1)),
+				(true, false) => BorrowedMarkets::<T>::mutate_exists(who, |count| {
+					*count = count.map(|count| count.saturating_sub(1)).filter(|count| *count > 0)
+				}),
+				_ => {},
+			}
+		}
+
+		/// Get balance of an account in the given asset which can be transferred keeping the
+		/// account alive, i.e. free balance minus the existential deposit
+		fn free_balance(asset_id: AssetIdOf<T>, who: &T::AccountId) -> BalanceOf<T> {
+			if asset_id == T::NativeAssetId::get() {
+				<T::Currency as fungible::Inspect<_>>::reducible_balance(
+					who,
+					Preservation::Preserve,
+					Fortitude::Polite,
+				)
+			} else {
+				<T::Assets as fungibles::Inspect<_>>::reducible_balance(
+					asset_id,
+					who,
+					Preservation::Preserve,
+					Fortitude::Polite,
+				)
+			}
+		}
+
+		/// Get balance of an account in the given asset, not counting funds on hold
+		#[cfg(any(feature = "try-runtime", test))]
+		fn total_balance(asset_id: AssetIdOf<T>, who: &T::AccountId) -> BalanceOf<T> {
+			if asset_id == T::NativeAssetId::get() {
+				<T::Currency as fungible::Inspect<_>>::balance(who)
+			} else {
+				<T::Assets as fungibles::Inspect<_>>::balance(asset_id, who)
+			}
+		}
+
+		/// Transfer funds of the given asset keeping the source account alive
+		fn transfer(
+			asset_id: AssetIdOf<T>,
+			source: &T::AccountId,
+			dest: &T::AccountId,
+			amount: BalanceOf<T>,
+		) -> DispatchResult {
+			if asset_id == T::NativeAssetId::get() {
+				<T::Currency as fungible::Mutate<_>>::transfer(
+					source,
+					dest,
+					amount,
+					Preservation::Preserve,
+				)
+			} else {
+				<T::Assets as fungibles::Mutate<_>>::transfer(
+					asset_id,
+					source,
+					dest,
+					amount,
+					Preservation::Preserve,
+				)
+			}
+			.map(|_| ())
+		}
+
+		/// Move collateral held in the account to the free balance of another account
+		fn seize_held_collateral(
+			asset_id: AssetIdOf<T>,
+			who: &T::AccountId,
+			dest: &T::AccountId,
+			amount: BalanceOf<T>,
+		) -> DispatchResult {
+			ensure!(asset_id == T::NativeAssetId::get(), Error::<T>::CollateralOnlyUnsupported);
+
+			<T::Currency as fungible::MutateHold<_>>::transfer_on_hold(
+				&HoldReason::Collateral.into(),
+				who,
+				dest,
+				amount,
+				Precision::Exact,
+				Restriction::Free,
+				Fortitude::Force,
+			)?;
+
+			Ok(())
+		}
+
 		/// The account ID of pallet
-		fn account_id() -> T::AccountId {
+		pub(crate) fn account_id() -> T::AccountId {
 			PALLET_ID.into_account_truncating()
 		}
 
-		/// Hook functions that is called on each initialized block
-		fn check_liquidity(_current_block: BlockNumber<T>) -> Weight {
-			let mut counter: u64 = 0;
+		/// Check up to `max_checks` borrowing positions for liquidation, resuming after the last
+		/// checked position. Returns the number of checked positions.
+		pub(crate) fn check_liquidity(max_checks: u32) -> u32 {
+			let mut iter = match LiquidationCursor::<T>::take() {
+				Some(cursor) => Accounts::<T>::iter_from(cursor.into_inner()),
+				None => Accounts::<T>::iter(),
+			};
+
+			// Bad debt is written off like a liquidation, so not while liquidations are paused
+			let liquidations_paused = Self::is_paused(Operation::Liquidate);
+
+			let mut checked: u32 = 0;
+			while checked < max_checks {
+				let (_, address, address_info) = match iter.next() {
+					Some(entry) => entry,
+					// Every position was checked, start over next time
+					None => return checked,
+				};
+
+				checked += 1;
+
+				// Write off debt of insolvent accounts and report accounts which can be
+				// liquidated
+				if !address_info.borrow_principal.is_zero() {
+					if !liquidations_paused && matches!(Self::is_insolvent(&address), Ok(true)) {
+						Self::realize_bad_debt(&address);
+					} else if let Ok(true) = Self::is_liquidatable(&address) {
+						Self::deposit_event(Event::AddressUndercollateralized(address));
+					}
+				}
+			}
+
+			// The raw key always fits the bound, otherwise the next check starts over
+			if let Ok(cursor) = BoundedVec::try_from(iter.last_raw_key().to_vec()) {
+				LiquidationCursor::<T>::put(cursor);
+			}
+
+			checked
+		}
+
+		/// Move the remaining collateral of an insolvent account to reserves and write off its
+		/// debt in every market
+		fn realize_bad_debt(who: &T::AccountId) {
+			for asset_id in Self::market_ids() {
+				Self::accrue_interest(asset_id);
+
+				let collateral = Self::get_lending_amount(asset_id, who.clone());
+				if !collateral.is_zero() &&
+					Self::decrease_deposit(asset_id, who, collateral).is_ok()
+				{
+					TotalReserves::<T>::mutate(asset_id, |total| {
+						*total = total.saturating_add(collateral)
+					});
+					Self::deposit_event(Event::CollateralAbsorbed(
+						who.clone(),
+						asset_id,
+						collateral,
+					));
+				}
+
+				let held = Self::held_collateral(asset_id, who);
+				if !held.is_zero() &&
+					Self::seize_held_collateral(asset_id, who, &Self::account_id(), held).is_ok()
+				{
+					TotalReserves::<T>::mutate(asset_id, |total| {
+						*total = total.saturating_add(held)
+					});
+					Self::deposit_event(Event::CollateralAbsorbed(who.clone(), asset_id, held));
+				}
+
+				let debt = Self::get_debt_amount(asset_id, who.clone());
+				if !debt.is_zero() {
+					Self::decrease_debt(asset_id, who, debt);
+					let address_info = Accounts::<T>::take(asset_id, who);
+					Self::count_borrowed_market(
+						who,
+						!address_info.borrow_principal.is_zero(),
+						false,
+					);
+
+					Self::deposit_event(Event::BadDebtRecorded(who.clone(), asset_id, debt));
+					Self::cover_bad_debt(asset_id, debt);
+				}
+			}
+		}
+
+		/// Record bad debt of a market and cover it from the insurance fund first. The rest is
+		/// taken from depositors by lowering the supply index, whatever exceeds the deposits is
+		/// kept as uncovered bad debt.
+		fn cover_bad_debt(asset_id: AssetIdOf<T>, amount: BalanceOf<T>) {
+			BadDebt::<T>::mutate(asset_id, |total| *total = total.saturating_add(amount));
+
+			let covered = amount.min(InsuranceFund::<T>::get(asset_id));
+			if !covered.is_zero() {
+				InsuranceFund::<T>::mutate(asset_id, |total| {
+					*total = total.saturating_sub(covered)
+				});
+				Self::deposit_event(Event::BadDebtCovered(asset_id, covered));
+			}
+
+			let total_deposits = TotalDeposits::<T>::get(asset_id);
+			let socialised = amount.saturating_sub(covered).min(total_deposits);
+			if !socialised.is_zero() {
+				let loss = FixedU128::from_rational(
+					socialised.saturated_into::<u128>(),
+					total_deposits.saturated_into::<u128>(),
+				);
+				SupplyIndex::<T>::mutate(asset_id, |index| {
+					*index = index.saturating_mul(FixedU128::one().saturating_sub(loss))
+				});
+				TotalDeposits::<T>::insert(asset_id, total_deposits.saturating_sub(socialised));
+				Self::deposit_event(Event::BadDebtSocialised(asset_id, socialised));
+			}
+
+			let uncovered = amount.saturating_sub(covered).saturating_sub(socialised);
+			if !uncovered.is_zero() {
+				UncoveredBadDebt::<T>::mutate(asset_id, |total| {
+					*total = total.saturating_add(uncovered)
+				});
+				Self::deposit_event(Event::BadDebtUncovered(asset_id, uncovered));
+			}
+		}
+
+		/// Submit liquidations of undercollateralized positions signed with the first
+		/// liquidator key found in the keystore
+		fn submit_liquidations(now: BlockNumber<T>) {
+			// Liquidations would be rejected by the pool while paused
+			if Self::is_paused(Operation::Liquidate) {
+				return
+			}
+
+			let signer = Signer::<T, T::AuthorityId>::any_account();
+			let liquidator = match signer.keystore_accounts().next() {
+				Some(account) => account.id,
+				// Liquidations are submitted only by nodes holding a liquidator key
+				None => return,
+			};
+			let nonce = LiquidatorNonces::<T>::get(&liquidator);
+
+			let borrowers: BTreeSet<AccountIdOf<T>> = Accounts::<T>::iter()
+				.filter(|(_, _, address_info)| !address_info.borrow_principal.is_zero())
+				.map(|(_, address, _)| address)
+				.collect();
+
+			let mut submitted: u32 = 0;
+			for borrower in borrowers {
+				if submitted >= T::MaxUnsignedLiquidations::get() {
+					break
+				}
+
+				let (debt_asset_id, collateral_asset_id, repay_amount) =
+					match Self::find_liquidation(&liquidator, &borrower) {
+						Some(liquidation) => liquidation,
+						None => continue,
+					};
+
+				// Transactions rejected by the pool are found again in the next block
+				let _ = signer.send_unsigned_transaction(
+					|account| LiquidationPayload {
+						public: account.public.clone(),
+						borrower: borrower.clone(),
+						debt_asset_id,
+						collateral_asset_id,
+						repay_amount,
+						block_number: now,
+						nonce: nonce.saturating_add(submitted),
+					},
+					|payload, signature| Call::liquidate_unsigned { payload, signature },
+				);
+
+				submitted += 1;
+			}
+		}
+
+		/// Find the liquidation of the borrower's largest debt against his largest collateral
+		/// which the liquidator can afford. Returns debt asset, collateral asset and repay amount.
+		fn find_liquidation(
+			liquidator: &AccountIdOf<T>,
+			borrower: &AccountIdOf<T>,
+		) -> Option<(AssetIdOf<T>, AssetIdOf<T>, BalanceOf<T>)> {
+			if liquidator == borrower || !Self::is_liquidatable(borrower).unwrap_or(false) {
+				return None
+			}
+
+			let mut debt: Option<(AssetIdOf<T>, FixedU128)> = None;
+			let mut collateral: Option<(AssetIdOf<T>, FixedU128)> = None;
+
+			for asset_id in Self::market_ids() {
+				let price = match T::PriceOracle::get_price(&asset_id) {
+					Some(price) => price,
+					None => continue,
+				};
+
+				let debt_value =
+					Self::to_value(Self::get_debt_amount(asset_id, borrower.clone()), price);
+				if debt_value > debt.map_or(FixedU128::zero(), |(_, value)| value) {
+					debt = Some((asset_id, debt_value));
+				}
+
+				let deposit_value =
+					Self::to_value(Self::get_collateral_amount(asset_id, borrower), price);
+				if deposit_value > collateral.map_or(FixedU128::zero(), |(_, value)| value) {
+					collateral = Some((asset_id, deposit_value));
+				}
+			}
+
+			let (debt_asset_id, _) = debt?;
+			let (collateral_asset_id, _) = collateral?;
+
+			// Repay up to the close factor share of the debt the liquidator can afford
+			let max_repay_amount: BalanceOf<T> = FixedU128::from_inner(
+				Self::get_debt_amount(debt_asset_id, borrower.clone()).saturated_into::<u128>(),
+			)
+			.saturating_mul(T::CloseFactor::get())
+			.into_inner()
+			.saturated_into();
+			let mut repay_amount =
+				max_repay_amount.min(Self::free_balance(debt_asset_id, liquidator));
+
+			// Scale the repay amount down if the borrower's collateral can't cover the seizure
+			let collateral_amount = Self::get_collateral_amount(collateral_asset_id, borrower);
+			let seize_amount =
+				Self::get_seize_amount(debt_asset_id, collateral_asset_id, repay_amount).ok()?;
+			if seize_amount > collateral_amount {
+				repay_amount = FixedU128::from_inner(repay_amount.saturated_into::<u128>())
+					.saturating_mul(FixedU128::from_rational(
+						collateral_amount.saturated_into::<u128>(),
+						seize_amount.saturated_into::<u128>(),
+					))
+					.into_inner()
+					.saturated_into();
+			}
+
+			(!repay_amount.is_zero()).then_some((debt_asset_id, collateral_asset_id, repay_amount))
+		}
+
+		/// Get number of positions which can be checked for liquidation within the weight
+		fn max_liquidation_checks(remaining_weight: Weight) -> u32 {
+			Self::max_steps(
+				remaining_weight,
+				T::WeightInfo::check_liquidity(0, T::MaxMarkets::get()),
+				T::WeightInfo::check_liquidity(1, T::MaxMarkets::get()),
+				T::MaxLiquidationChecksPerBlock::get(),
+			)
+		}
 
-			for address in Accounts::<T>::iter() {
-				let collateral = FixedU128::from_inner(
-					Self::get_lending_amount(address.0.clone()).saturated_into::<u128>(),
+		/// Migrate as many accounts as fit within the weight. Once the positions of the single
+		/// market are converted their keys are moved to the new hasher, and once every key is
+		/// moved the rates are rescaled if the upgrade deferred it, so that user operations
+		/// resume on the current storage layout.
+		fn migrate_accounts(remaining_weight: Weight) -> Weight {
+			// Positions of the single market are moved before the storage version is set
+			if Self::on_chain_storage_version() == 0 {
+				let finish_weight = T::DbWeight::get().writes(3);
+				let max_steps = Self::max_steps(
+					remaining_weight,
+					T::WeightInfo::migrate_positions(0).saturating_add(finish_weight),
+					T::WeightInfo::migrate_positions(1).saturating_add(finish_weight),
+					T::MaxAccountsMigratedPerBlock::get(),
 				);
-				let borrowed = FixedU128::from_inner(
-					Self::get_debt_amount(address.0.clone()).saturated_into::<u128>(),
+
+				if max_steps == 0 {
+					return Weight::zero()
+				}
+
+				let migrated = migrations::v1::migrate_accounts::<T>(max_steps);
+				let weight = T::WeightInfo::migrate_positions(migrated);
+
+				if AccountsMigration::<T>::exists() {
+					return weight
+				}
+
+				return weight.saturating_add(migrations::v2::start_migration::<T>())
+			}
+
+			let finish_weight = migrations::v3::rescale_deferred_weight::<T>();
+			let max_steps = Self::max_steps(
+				remaining_weight,
+				T::WeightInfo::migrate_accounts(0).saturating_add(finish_weight),
+				T::WeightInfo::migrate_accounts(1).saturating_add(finish_weight),
+				T::MaxAccountsMigratedPerBlock::get(),
+			);
+
+			if max_steps == 0 {
+				return Weight::zero()
+			}
+
+			let migrated = migrations::v2::migrate_accounts::<T>(max_steps);
+			let weight = T::WeightInfo::migrate_accounts(migrated);
+
+			if AccountsMigration::<T>::exists() {
+				return weight
+			}
+
+			weight.saturating_add(migrations::v3::rescale_deferred::<T>())
+		}
+
+		/// Get number of steps of a paginated task which fit within the weight, given the
+		/// weights of zero and one step
+		fn max_steps(
+			remaining_weight: Weight,
+			base_weight: Weight,
+			one_step_weight: Weight,
+			max_steps: u32,
+		) -> u32 {
+			let check_weight = one_step_weight.saturating_sub(base_weight);
+
+			if remaining_weight.any_lt(base_weight) {
+				return 0
+			}
+
+			let available_weight = remaining_weight.saturating_sub(base_weight);
+			let by_ref_time = available_weight
+				.ref_time()
+				.checked_div(check_weight.ref_time())
+				.unwrap_or(u64::MAX);
+			let by_proof_size = available_weight
+				.proof_size()
+				.checked_div(check_weight.proof_size())
+				.unwrap_or(u64::MAX);
+
+			by_ref_time.min(by_proof_size).min(max_steps as u64) as u32
+		}
+
+		/// Check that the market totals are consistent with the funds held by the pallet
+		#[cfg(any(feature = "try-runtime", test))]
+		pub fn do_try_state() -> Result<(), frame_support::sp_runtime::TryRuntimeError> {
+			// Positions of the single market are still moved into the native market
+			if Self::on_chain_storage_version() == 0 {
+				return Ok(())
+			}
+
+			// Accounts keep the old hasher only while they're migrated
+			ensure!(
+				AccountsMigration::<T>::exists() || migrations::v2::old_accounts_count::<T>() == 0,
+				"Accounts left with the old hasher"
+			);
+
+			// Rates are only left per block while the accounts are migrated
+			ensure!(
+				AccountsMigration::<T>::exists() ||
+					!migrations::v3::DeferredBlocksPerYear::<T>::exists(),
+				"Rates left to rescale after the accounts migration"
+			);
+
+			// Receipt tokens are only unfrozen while the pallet burns them
+			ensure!(!ReceiptsUnfrozen::<T>::get(), "Receipt tokens left unfrozen");
+
+			for asset_id in Self::market_ids() {
+				let total_deposits = TotalDeposits::<T>::get(asset_id);
+				let total_borrows = TotalBorrows::<T>::get(asset_id);
+				let total_reserves = TotalReserves::<T>::get(asset_id);
+
+				// The market can't lend out more than it was given
+				ensure!(
+					total_deposits
+						.saturating_add(total_reserves)
+						.saturating_add(InsuranceFund::<T>::get(asset_id)) >=
+						total_borrows,
+					"Market total borrows exceed total deposits and reserves"
 				);
-				let collateralized_balance =
-					collateral.saturating_mul(CollateralFactor::<T>::get());
 
-				if borrowed >= collateralized_balance {
-					Accounts::<T>::remove(&address.0);
+				// The funds which weren't lent out must be held by the pallet
+				ensure!(
+					Self::total_balance(asset_id, &Self::account_id()) >= Self::get_cash(asset_id),
+					"Pallet balance does not cover market cash"
+				);
 
-					Self::deposit_event(Event::AddressLiquidated(address.0));
+				// There can't be any debt without borrowers
+				let has_borrowers = Accounts::<T>::iter_prefix_values(asset_id)
+					.any(|address_info| !address_info.borrow_principal.is_zero());
+				ensure!(
+					has_borrowers || total_borrows.is_zero(),
+					"Market has total borrows without borrowers"
+				);
+			}
 
-					counter += 1;
+			// Accounts are counted once in each market they borrowed from
+			if !AccountsMigration::<T>::exists() {
+				for (who, count) in BorrowedMarkets::<T>::iter() {
+					let borrowed = Self::market_ids()
+						.into_iter()
+						.filter(|asset_id| {
+							!Accounts::<T>::get(asset_id, &who).borrow_principal.is_zero()
+						})
+						.count();
+					ensure!(
+						borrowed == count as usize,
+						"Borrowed markets of an account do not match its debts"
+					);
 				}
+				ensure!(
+					Accounts::<T>::iter().all(|(_, who, address_info)| {
+						address_info.borrow_principal.is_zero() || Self::has_debt(&who)
+					}),
+					"Borrower not counted in the borrowed markets"
+				);
 			}
 
-			T::DbWeight::get()
-				.reads(counter)
-				.saturating_add(T::DbWeight::get().writes(counter))
+			Ok(())
 		}
 	}
 
 	#[pallet::hooks]
 	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
 		fn on_initialize(now: BlockNumber<T>) -> Weight {
-			let consumed_weight = Self::check_liquidity(now);
+			let pending = Self::apply_parameter_changes(now);
+
+			T::WeightInfo::apply_parameter_changes(pending)
+		}
+
+		fn on_idle(_now: BlockNumber<T>, remaining_weight: Weight) -> Weight {
+			// Accounts are migrated before they're checked for liquidation
+			if AccountsMigration::<T>::exists() {
+				return Self::migrate_accounts(remaining_weight)
+			}
+
+			let max_checks = Self::max_liquidation_checks(remaining_weight);
+
+			if max_checks == 0 {
+				return Weight::zero()
+			}
+
+			let checked = Self::check_liquidity(max_checks);
+
+			T::WeightInfo::check_liquidity(checked, T::MaxMarkets::get())
+		}
+
+		fn offchain_worker(now: BlockNumber<T>) {
+			Self::submit_liquidations(now);
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn try_state(
+			_n: BlockNumberFor<T>,
+		) -> Result<(), frame_support::sp_runtime::TryRuntimeError> {
+			Self::do_try_state()
+		}
+	}
+
+	#[pallet::validate_unsigned]
+	impl<T: Config> ValidateUnsigned for Pallet<T> {
+		type Call = Call<T>;
+
+		/// Only accept liquidations with a valid signature, found recently, of an
+		/// undercollateralized borrower which the liquidator can pay for. A single liquidation
+		/// per borrower and debt market is kept in the pool.
+		fn validate_unsigned(_source: TransactionSource, call: &Self::Call) -> TransactionValidity {
+			let (payload, signature) = match call {
+				Call::liquidate_unsigned { payload, signature } => (payload, signature),
+				_ => return InvalidTransaction::Call.into(),
+			};
+
+			// Check the signature of the liquidator
+			if !SignedPayload::<T>::verify::<T::AuthorityId>(payload, signature.clone()) {
+				return InvalidTransaction::BadProof.into()
+			}
+
+			// Check if the liquidation was found recently
+			let now = <frame_system::Pallet<T>>::block_number();
+			if payload.block_number > now {
+				return InvalidTransaction::Future.into()
+			}
+			if now.saturating_sub(payload.block_number).saturated_into::<u64>() >
+				T::UnsignedLongevity::get()
+			{
+				return InvalidTransaction::Stale.into()
+			}
+
+			// Check if the liquidation wasn't executed already
+			let liquidator = payload.public.clone().into_account();
+			let nonce = LiquidatorNonces::<T>::get(&liquidator);
+			if payload.nonce < nonce {
+				return InvalidTransaction::Stale.into()
+			}
+
+			// Check if the liquidation can succeed
+			if Self::is_paused(Operation::Liquidate) ||
+				liquidator == payload.borrower ||
+				payload.repay_amount.is_zero() ||
+				!Self::market_exists(payload.debt_asset_id) ||
+				!Self::market_exists(payload.collateral_asset_id) ||
+				!Self::is_liquidatable(&payload.borrower).unwrap_or(false)
+			{
+				return InvalidTransaction::Call.into()
+			}
+			if Self::free_balance(payload.debt_asset_id, &liquidator) < payload.repay_amount {
+				return InvalidTransaction::Payment.into()
+			}
+
+			let mut transaction = ValidTransaction::with_tag_prefix("DefiLiquidation")
+				.priority(T::UnsignedPriority::get())
+				.and_provides((payload.borrower.clone(), payload.debt_asset_id))
+				.and_provides((liquidator.clone(), payload.nonce))
+				.longevity(T::UnsignedLongevity::get())
+				.propagate(true);
+
+			// Later liquidations of the liquidator wait for the previous ones
+			if payload.nonce > nonce {
+				transaction = transaction.and_requires((liquidator, payload.nonce - 1));
+			}
+
+			transaction.build()
+		}
+
+		fn pre_dispatch(call: &Self::Call) -> Result<(), TransactionValidityError> {
+			// Liquidations of a liquidator are executed in the order of their nonces
+			if let Call::liquidate_unsigned { payload, .. } = call {
+				let liquidator = payload.public.clone().into_account();
+				if payload.nonce > LiquidatorNonces::<T>::get(&liquidator) {
+					return Err(InvalidTransaction::Future.into())
+				}
+			}
 
-			consumed_weight
+			Self::validate_unsigned(TransactionSource::InBlock, call).map(|_| ())
 		}
 	}
 }
@@ -0,0 +1,888 @@
+//! Storage migrations of the defi pallet. Each migration upgrades the storage by a single
+//! version and is a no-op on any other on-chain version, so they can be chained in the runtime.
+
+use crate::*;
+#[cfg(feature = "try-runtime")]
+use frame_support::sp_runtime::TryRuntimeError;
+use frame_support::{
+	pallet_prelude::*,
+	sp_runtime::{
+		traits::{One, Zero},
+		SaturatedConversion, Saturating,
+	},
+	sp_std::prelude::*,
+	traits::{GetStorageVersion, OnRuntimeUpgrade, StorageVersion},
+};
+use frame_system::pallet_prelude::BlockNumberFor;
+
+type BalanceOf<T> = <T as Config>::Balance;
+
+/// Storage layout of the single market pallet
+pub mod v0 {
+	use super::*;
+
+	#[derive(Encode, Decode, Default, PartialEq, Eq, scale_info::TypeInfo)]
+	#[cfg_attr(feature = "std", derive(Debug))]
+	pub struct AddressInfo<Balance, BlockNumber> {
+		/// The deposit balance of the account after last adjustment
+		pub deposit_principal: Balance,
+		/// The time (block height) at which the deposit balance was last adjusted
+		pub deposit_date: BlockNumber,
+		/// The borrowing balance of the account after last adjustment
+		pub borrow_principal: Balance,
+		/// The time (block height) at which the borrowing balance was last adjusted
+		pub borrow_date: BlockNumber,
+	}
+
+	#[frame_support::storage_alias]
+	pub type DepositRate<T: Config> = StorageValue<Pallet<T>, FixedU128>;
+
+	#[frame_support::storage_alias]
+	pub type BorrowingRate<T: Config> = StorageValue<Pallet<T>, FixedU128>;
+
+	#[frame_support::storage_alias]
+	pub type CollateralFactor<T: Config> = StorageValue<Pallet<T>, FixedU128>;
+
+	#[frame_support::storage_alias]
+	pub type Accounts<T: Config> = StorageMap<
+		Pallet<T>,
+		Identity,
+		<T as frame_system::Config>::AccountId,
+		AddressInfo<BalanceOf<T>, BlockNumberFor<T>>,
+	>;
+
+	/// Per-block deposit rate of the single market when none was set
+	pub fn default_deposit_rate() -> FixedU128 {
+		FixedU128::from_inner(92828) / FixedU128::from_inner(10000000000000)
+	}
+
+	/// Per-block borrowing rate of the single market when none was set
+	pub fn default_borrowing_rate() -> FixedU128 {
+		FixedU128::from_inner(128727) / FixedU128::from_inner(10000000000000)
+	}
+
+	/// Get principal with interest compounded per block from the given date up to `now`, as
+	/// the single market pallet computed it
+	pub fn accrued<T: Config>(
+		principal: BalanceOf<T>,
+		date: BlockNumberFor<T>,
+		rate: FixedU128,
+		now: BlockNumberFor<T>,
+	) -> BalanceOf<T> {
+		let elapsed_time: u32 = now.saturating_sub(date).saturated_into();
+
+		let multiplier =
+			FixedU128::one().saturating_add(rate).saturating_pow(elapsed_time as usize);
+
+		FixedU128::from_inner(principal.saturated_into::<u128>())
+			.saturating_mul(multiplier)
+			.into_inner()
+			.saturated_into()
+	}
+}
+
+/// Per-asset markets with deposits held as receipt tokens
+pub mod v1 {
+	use super::*;
+	use frame_support::{
+		sp_io,
+		sp_runtime::traits::{CheckedDiv, Convert},
+		storage::{unhashed, StoragePrefixedMap},
+		traits::tokens::fungibles,
+	};
+
+	/// Debt of an account in a market as it's stored in v1
+	#[derive(Encode, Decode, Default, PartialEq, Eq, scale_info::TypeInfo)]
+	#[cfg_attr(feature = "std", derive(Debug))]
+	pub struct AddressInfo<Balance> {
+		/// The borrowing balance of the account after last adjustment
+		pub borrow_principal: Balance,
+		/// The market borrow index at which the borrowing balance was last adjusted
+		pub borrow_index: FixedU128,
+	}
+
+	#[frame_support::storage_alias]
+	pub type DepositRate<T: Config> =
+		StorageMap<Pallet<T>, Identity, <T as Config>::AssetId, FixedU128>;
+
+	#[frame_support::storage_alias]
+	pub type BorrowingRate<T: Config> =
+		StorageMap<Pallet<T>, Identity, <T as Config>::AssetId, FixedU128>;
+
+	#[frame_support::storage_alias]
+	pub type CollateralFactor<T: Config> =
+		StorageMap<Pallet<T>, Identity, <T as Config>::AssetId, FixedU128>;
+
+	#[frame_support::storage_alias]
+	pub type BorrowIndex<T: Config> =
+		StorageMap<Pallet<T>, Identity, <T as Config>::AssetId, FixedU128>;
+
+	#[frame_support::storage_alias]
+	pub type SupplyIndex<T: Config> =
+		StorageMap<Pallet<T>, Identity, <T as Config>::AssetId, FixedU128>;
+
+	#[frame_support::storage_alias]
+	pub type TotalDeposits<T: Config> =
+		StorageMap<Pallet<T>, Identity, <T as Config>::AssetId, BalanceOf<T>, ValueQuery>;
+
+	#[frame_support::storage_alias]
+	pub type TotalBorrows<T: Config> =
+		StorageMap<Pallet<T>, Identity, <T as Config>::AssetId, BalanceOf<T>, ValueQuery>;
+
+	#[frame_support::storage_alias]
+	pub type LastAccrued<T: Config> =
+		StorageMap<Pallet<T>, Identity, <T as Config>::AssetId, BlockNumberFor<T>, ValueQuery>;
+
+	#[frame_support::storage_alias]
+	pub type ReceiptAssets<T: Config> =
+		StorageMap<Pallet<T>, Identity, <T as Config>::AssetId, <T as Config>::AssetId>;
+
+	#[frame_support::storage_alias]
+	pub type Accounts<T: Config> = StorageDoubleMap<
+		Pallet<T>,
+		Identity,
+		<T as Config>::AssetId,
+		Identity,
+		<T as frame_system::Config>::AccountId,
+		AddressInfo<BalanceOf<T>>,
+	>;
+
+	/// Moves the single market of v0 into the native market. The upgrade moves the rates into
+	/// the native market, which starts accruing from the upgrade block, and starts the
+	/// migration of the positions. They're converted by `migrate_accounts` from `on_idle` over
+	/// as many blocks as it takes, while user operations are paused: deposits into receipt
+	/// tokens and debts into principals at the market borrow index, with the interest accrued
+	/// up to the block the native market started accruing.
+	///
+	/// The storage version is only set to v1 once every position is converted. The following
+	/// migrations are then run from `on_idle` as well, so user operations resume on the current
+	/// layout.
+	pub struct MigrateToV1<T>(PhantomData<T>);
+
+	impl<T: Config> OnRuntimeUpgrade for MigrateToV1<T> {
+		fn on_runtime_upgrade() -> Weight {
+			let on_chain_version = Pallet::<T>::on_chain_storage_version();
+			if on_chain_version != 0 {
+				log::info!(
+					target: "runtime::defi",
+					"Skipping migration to v1, on-chain storage version is {:?}",
+					on_chain_version
+				);
+				return T::DbWeight::get().reads(1)
+			}
+
+			// An upgrade during the migration leaves it running
+			if AccountsMigration::<T>::exists() {
+				log::info!(target: "runtime::defi", "Migration of accounts to v1 in progress");
+				return T::DbWeight::get().reads(2)
+			}
+
+			let native_asset_id = T::NativeAssetId::get();
+
+			// The receipt token of the native market must be available before any position is
+			// moved, otherwise the storage is left untouched
+			if let Err(e) = ensure_receipt_asset::<T>(native_asset_id) {
+				log::error!(
+					target: "runtime::defi",
+					"Unable to create the native receipt asset, migration to v1 aborted: {:?}",
+					e
+				);
+				return T::DbWeight::get().reads(4)
+			}
+
+			// Start accruing the native market from the upgrade block
+			LastAccrued::<T>::insert(native_asset_id, frame_system::Pallet::<T>::block_number());
+
+			// Move the rates into the native market
+			let deposit_rate =
+				v0::DepositRate::<T>::take().unwrap_or_else(v0::default_deposit_rate);
+			let borrowing_rate =
+				v0::BorrowingRate::<T>::take().unwrap_or_else(v0::default_borrowing_rate);
+			let collateral_factor =
+				v0::CollateralFactor::<T>::take().unwrap_or_else(DefaultCollateralFactor::<T>::get);
+			DepositRate::<T>::insert(native_asset_id, deposit_rate);
+			BorrowingRate::<T>::insert(native_asset_id, borrowing_rate);
+			CollateralFactor::<T>::insert(native_asset_id, collateral_factor);
+
+			AccountsMigration::<T>::put(BoundedVec::default());
+
+			log::info!(target: "runtime::defi", "Started migration of accounts to v1");
+
+			T::DbWeight::get().reads_writes(8, 12)
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn pre_upgrade() -> Result<Vec<u8>, TryRuntimeError> {
+			if Pallet::<T>::on_chain_storage_version() != 0 || AccountsMigration::<T>::exists() {
+				return Ok(Vec::new())
+			}
+
+			let deposit_rate = v0::DepositRate::<T>::get().unwrap_or_else(v0::default_deposit_rate);
+			let borrowing_rate =
+				v0::BorrowingRate::<T>::get().unwrap_or_else(v0::default_borrowing_rate);
+			let collateral_factor =
+				v0::CollateralFactor::<T>::get().unwrap_or_else(DefaultCollateralFactor::<T>::get);
+
+			// Positions are converted with the interest accrued up to the upgrade block
+			let now = frame_system::Pallet::<T>::block_number();
+			let positions: Vec<_> = v0::Accounts::<T>::iter()
+				.map(|(who, address_info)| {
+					let deposit = v0::accrued::<T>(
+						address_info.deposit_principal,
+						address_info.deposit_date,
+						deposit_rate,
+						now,
+					);
+					let debt = v0::accrued::<T>(
+						address_info.borrow_principal,
+						address_info.borrow_date,
+						borrowing_rate,
+						now,
+					);
+					(who, deposit, debt)
+				})
+				.collect();
+
+			Ok((deposit_rate, borrowing_rate, collateral_factor, positions).encode())
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn post_upgrade(state: Vec<u8>) -> Result<(), TryRuntimeError> {
+			if state.is_empty() {
+				return Ok(())
+			}
+
+			let (deposit_rate, borrowing_rate, collateral_factor, positions): (
+				FixedU128,
+				FixedU128,
+				FixedU128,
+				Vec<(T::AccountId, BalanceOf<T>, BalanceOf<T>)>,
+			) = Decode::decode(&mut &state[..])
+				.map_err(|_| "Unable to decode the state saved before the upgrade")?;
+
+			let native_asset_id = T::NativeAssetId::get();
+
+			// The rates were moved into the native market
+			ensure!(!v0::DepositRate::<T>::exists(), "Old deposit rate was not removed");
+			ensure!(!v0::BorrowingRate::<T>::exists(), "Old borrowing rate was not removed");
+			ensure!(!v0::CollateralFactor::<T>::exists(), "Old collateral factor was not removed");
+			ensure!(
+				DepositRate::<T>::get(native_asset_id) == Some(deposit_rate),
+				"Deposit rate of the native market does not match"
+			);
+			ensure!(
+				BorrowingRate::<T>::get(native_asset_id) == Some(borrowing_rate),
+				"Borrowing rate of the native market does not match"
+			);
+			ensure!(
+				CollateralFactor::<T>::get(native_asset_id) == Some(collateral_factor),
+				"Collateral factor of the native market does not match"
+			);
+			ensure!(AccountsMigration::<T>::exists(), "Migration of accounts was not started");
+
+			// Run the whole migration and check every position kept its balances with the
+			// accrued interest, then discard it so that it's still run block by block
+			frame_support::storage::with_transaction(|| {
+				let result = (|| -> Result<(), TryRuntimeError> {
+					while AccountsMigration::<T>::exists() {
+						migrate_accounts::<T>(u32::MAX);
+					}
+
+					ensure!(
+						Pallet::<T>::on_chain_storage_version() == 1,
+						"Storage version was not updated to v1"
+					);
+					ensure!(v0::Accounts::<T>::iter().count() == 0, "Accounts left in v0");
+
+					let receipt_asset_id = ReceiptAssets::<T>::get(native_asset_id)
+						.ok_or("Native receipt asset was not created")?;
+					let mut total_deposits: BalanceOf<T> = Zero::zero();
+					let mut total_borrows: BalanceOf<T> = Zero::zero();
+					for (who, deposit, debt) in positions {
+						let receipt_balance =
+							<T::Assets as fungibles::Inspect<_>>::balance(receipt_asset_id, &who);
+						ensure!(
+							receipt_balance == receipt_amount::<T>(native_asset_id, deposit),
+							"Deposit of a migrated account does not match"
+						);
+						ensure!(
+							Accounts::<T>::get(native_asset_id, &who)
+								.map(|address_info| address_info.borrow_principal)
+								.unwrap_or_default() ==
+								debt,
+							"Debt of a migrated account does not match"
+						);
+						total_deposits = total_deposits.saturating_add(deposit);
+						total_borrows = total_borrows.saturating_add(debt);
+					}
+					ensure!(
+						TotalDeposits::<T>::get(native_asset_id) == total_deposits,
+						"Total deposits of the native market do not match the migrated accounts"
+					);
+					ensure!(
+						TotalBorrows::<T>::get(native_asset_id) == total_borrows,
+						"Total borrows of the native market do not match the migrated accounts"
+					);
+
+					Ok(())
+				})();
+
+				frame_support::storage::TransactionOutcome::Rollback(result)
+			})
+		}
+	}
+
+	/// Convert up to `max_steps` positions of v0, resuming after the last visited key. The old
+	/// and the new accounts share their prefix, so converted positions are visited and
+	/// skipped. Returns the number of visited entries, the migration is finished once there are
+	/// none left.
+	pub fn migrate_accounts<T: Config>(max_steps: u32) -> u32 {
+		let cursor = match AccountsMigration::<T>::get() {
+			Some(cursor) => cursor,
+			None => return 0,
+		};
+
+		let native_asset_id = T::NativeAssetId::get();
+		let deposit_rate =
+			DepositRate::<T>::get(native_asset_id).unwrap_or_else(v0::default_deposit_rate);
+		let borrowing_rate =
+			BorrowingRate::<T>::get(native_asset_id).unwrap_or_else(v0::default_borrowing_rate);
+		let accrued_at = LastAccrued::<T>::get(native_asset_id);
+
+		let prefix = Accounts::<T>::final_prefix();
+		let mut previous_key =
+			if cursor.is_empty() { prefix.to_vec() } else { cursor.into_inner() };
+
+		let mut visited: u32 = 0;
+		while visited < max_steps {
+			let key = match sp_io::storage::next_key(&previous_key) {
+				Some(key) if key.starts_with(&prefix) => key,
+				// Every entry was visited
+				_ => {
+					AccountsMigration::<T>::kill();
+					StorageVersion::new(1).put::<Pallet<T>>();
+					log::info!(target: "runtime::defi", "Finished migration of accounts to v1");
+					return visited
+				},
+			};
+
+			visited += 1;
+
+			if let Some(who) = decode_v0_key::<T>(&key[prefix.len()..]) {
+				if let Some(address_info) =
+					unhashed::take::<v0::AddressInfo<BalanceOf<T>, BlockNumberFor<T>>>(&key)
+				{
+					let deposit = v0::accrued::<T>(
+						address_info.deposit_principal,
+						address_info.deposit_date,
+						deposit_rate,
+						accrued_at,
+					);
+					let debt = v0::accrued::<T>(
+						address_info.borrow_principal,
+						address_info.borrow_date,
+						borrowing_rate,
+						accrued_at,
+					);
+					migrate_position::<T>(native_asset_id, &who, deposit, debt);
+				}
+			}
+
+			previous_key = key;
+		}
+
+		// Keys of both layouts fit the bound, otherwise the migration starts over
+		AccountsMigration::<T>::put(BoundedVec::try_from(previous_key).unwrap_or_default());
+
+		visited
+	}
+
+	/// Mint receipt tokens for the deposit and record the debt at the market borrow index
+	fn migrate_position<T: Config>(
+		asset_id: T::AssetId,
+		who: &T::AccountId,
+		deposit: BalanceOf<T>,
+		debt: BalanceOf<T>,
+	) {
+		if !deposit.is_zero() {
+			let minted = ReceiptAssets::<T>::get(asset_id)
+				.ok_or(DispatchError::CannotLookup)
+				.and_then(|receipt_asset_id| {
+					<T::Assets as fungibles::Mutate<_>>::mint_into(
+						receipt_asset_id,
+						who,
+						receipt_amount::<T>(asset_id, deposit),
+					)
+				});
+			match minted {
+				Ok(_) => TotalDeposits::<T>::mutate(asset_id, |total| {
+					*total = total.saturating_add(deposit)
+				}),
+				Err(e) => log::error!(
+					target: "runtime::defi",
+					"Unable to mint receipt tokens of {:?}: {:?}",
+					who,
+					e
+				),
+			}
+		}
+
+		if !debt.is_zero() {
+			let borrow_index = BorrowIndex::<T>::get(asset_id).unwrap_or_else(FixedU128::one);
+			Accounts::<T>::insert(
+				asset_id,
+				who,
+				AddressInfo { borrow_principal: debt, borrow_index },
+			);
+			TotalBorrows::<T>::mutate(asset_id, |total| *total = total.saturating_add(debt));
+		}
+	}
+
+	/// Get the receipt tokens worth the amount at the market supply index, rounding down
+	fn receipt_amount<T: Config>(asset_id: T::AssetId, amount: BalanceOf<T>) -> BalanceOf<T> {
+		let supply_index = SupplyIndex::<T>::get(asset_id).unwrap_or_else(FixedU128::one);
+
+		FixedU128::from_inner(amount.saturated_into::<u128>())
+			.checked_div(&supply_index)
+			.unwrap_or_default()
+			.into_inner()
+			.saturated_into()
+	}
+
+	/// Get the receipt token of the market, creating it owned by the pallet account on first
+	/// use
+	fn ensure_receipt_asset<T: Config>(asset_id: T::AssetId) -> DispatchResult {
+		if ReceiptAssets::<T>::contains_key(asset_id) {
+			return Ok(())
+		}
+
+		let receipt_asset_id = T::ReceiptAssetId::convert(asset_id);
+		ensure!(
+			!<T::Assets as fungibles::Inspect<_>>::asset_exists(receipt_asset_id),
+			"Receipt asset id of the market is taken"
+		);
+
+		<T::Assets as fungibles::Create<_>>::create(
+			receipt_asset_id,
+			Pallet::<T>::account_id(),
+			true,
+			One::one(),
+		)?;
+		ReceiptAssets::<T>::insert(asset_id, receipt_asset_id);
+
+		Ok(())
+	}
+
+	/// Get the account id of a key without its prefix, if it's a position of v0 keyed by the
+	/// account id alone
+	fn decode_v0_key<T: Config>(mut key: &[u8]) -> Option<T::AccountId> {
+		let who = T::AccountId::decode(&mut key).ok()?;
+
+		// Positions of v1 are keyed by the asset id as well, which leaves bytes behind
+		key.is_empty().then_some(who)
+	}
+}
+
+/// `Accounts` keyed by the account id hashed with `Blake2_128Concat`
+pub mod v2 {
+	use super::*;
+	use frame_support::{
+		sp_io,
+		storage::{unhashed, StoragePrefixedMap},
+	};
+
+	/// `Accounts` as they're stored before the migration, keyed by the raw account id
+	#[frame_support::storage_alias]
+	pub type OldAccounts<T: Config> = StorageDoubleMap<
+		Pallet<T>,
+		Identity,
+		<T as Config>::AssetId,
+		Identity,
+		<T as frame_system::Config>::AccountId,
+		AddressInfo<BalanceOf<T>>,
+		ValueQuery,
+	>;
+
+	/// Moves `Accounts` from the `Identity` to the `Blake2_128Concat` hasher of the account id.
+	/// The upgrade only starts the migration, the entries are moved by `migrate_accounts` from
+	/// `on_idle` over as many blocks as it takes, while user operations are paused. While the
+	/// positions of the single market are converted, it's started from `on_idle` once they are.
+	pub struct MigrateToV2<T>(PhantomData<T>);
+
+	impl<T: Config> OnRuntimeUpgrade for MigrateToV2<T> {
+		fn on_runtime_upgrade() -> Weight {
+			let on_chain_version = Pallet::<T>::on_chain_storage_version();
+			if on_chain_version != 1 {
+				log::info!(
+					target: "runtime::defi",
+					"Skipping migration to v2, on-chain storage version is {:?}",
+					on_chain_version
+				);
+				return T::DbWeight::get().reads(1)
+			}
+
+			T::DbWeight::get().reads(1).saturating_add(start_migration::<T>())
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn pre_upgrade() -> Result<Vec<u8>, TryRuntimeError> {
+			if Pallet::<T>::on_chain_storage_version() != 1 {
+				return Ok(Vec::new())
+			}
+
+			let accounts: Vec<_> = OldAccounts::<T>::iter().collect();
+
+			Ok(accounts.encode())
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn post_upgrade(state: Vec<u8>) -> Result<(), TryRuntimeError> {
+			// Started from `on_idle` once the positions of the single market are converted
+			if Pallet::<T>::on_chain_storage_version() == 0 && AccountsMigration::<T>::exists() {
+				return Ok(())
+			}
+
+			ensure!(
+				Pallet::<T>::on_chain_storage_version() >= 2,
+				"Storage version was not updated to v2"
+			);
+
+			if state.is_empty() {
+				return Ok(())
+			}
+
+			let accounts: Vec<(T::AssetId, T::AccountId, AddressInfo<BalanceOf<T>>)> =
+				Decode::decode(&mut &state[..])
+					.map_err(|_| "Unable to decode the state saved before the upgrade")?;
+
+			ensure!(AccountsMigration::<T>::exists(), "Migration of accounts was not started");
+
+			// Run the whole migration and check every account was moved, then discard it so
+			// that it's still run block by block
+			frame_support::storage::with_transaction(|| {
+				let result = (|| -> Result<(), TryRuntimeError> {
+					while AccountsMigration::<T>::exists() {
+						migrate_accounts::<T>(u32::MAX);
+					}
+
+					ensure!(old_accounts_count::<T>() == 0, "Accounts left with the old hasher");
+					ensure!(
+						Accounts::<T>::iter().count() == accounts.len(),
+						"Number of accounts changed"
+					);
+					for (asset_id, who, address_info) in accounts {
+						ensure!(
+							Accounts::<T>::get(asset_id, who) == address_info,
+							"Migrated account does not match"
+						);
+					}
+
+					Pallet::<T>::do_try_state()
+				})();
+
+				frame_support::storage::TransactionOutcome::Rollback(result)
+			})
+		}
+	}
+
+	/// Start moving the entries of `Accounts` to the new hasher and set the storage version to
+	/// v2
+	pub fn start_migration<T: Config>() -> Weight {
+		// The liquidation cursor points at an old key
+		LiquidationCursor::<T>::kill();
+		AccountsMigration::<T>::put(BoundedVec::default());
+		StorageVersion::new(2).put::<Pallet<T>>();
+
+		log::info!(target: "runtime::defi", "Started migration of accounts to v2");
+
+		T::DbWeight::get().writes(3)
+	}
+
+	/// Move up to `max_steps` entries of `Accounts` to the new hasher, resuming after the last
+	/// visited key. Entries already moved are visited and skipped. Returns the number of
+	/// visited entries, the migration is finished once there are none left.
+	pub fn migrate_accounts<T: Config>(max_steps: u32) -> u32 {
+		let cursor = match AccountsMigration::<T>::get() {
+			Some(cursor) => cursor,
+			None => return 0,
+		};
+
+		let prefix = Accounts::<T>::final_prefix();
+		let mut previous_key =
+			if cursor.is_empty() { prefix.to_vec() } else { cursor.into_inner() };
+
+		let mut visited: u32 = 0;
+		while visited < max_steps {
+			let key = match sp_io::storage::next_key(&previous_key) {
+				Some(key) if key.starts_with(&prefix) => key,
+				// Every entry was visited
+				_ => {
+					AccountsMigration::<T>::kill();
+					log::info!(target: "runtime::defi", "Finished migration of accounts to v2");
+					return visited
+				},
+			};
+
+			visited += 1;
+
+			if let Some((asset_id, who)) = decode_old_key::<T>(&key[prefix.len()..]) {
+				if let Some(address_info) = unhashed::take::<AddressInfo<BalanceOf<T>>>(&key) {
+					// Accounts are counted once in each market they borrowed from
+					Pallet::<T>::count_borrowed_market(
+						&who,
+						false,
+						!address_info.borrow_principal.is_zero(),
+					);
+					Accounts::<T>::insert(asset_id, who, address_info);
+				}
+			}
+
+			previous_key = key;
+		}
+
+		// Keys of both hashers fit the bound, otherwise the migration starts over
+		AccountsMigration::<T>::put(BoundedVec::try_from(previous_key).unwrap_or_default());
+
+		visited
+	}
+
+	/// Number of `Accounts` entries still keyed by the raw account id
+	#[cfg(any(feature = "try-runtime", test))]
+	pub fn old_accounts_count<T: Config>() -> usize {
+		let prefix = Accounts::<T>::final_prefix();
+		let mut previous_key = prefix.to_vec();
+		let mut count = 0;
+
+		while let Some(key) = sp_io::storage::next_key(&previous_key) {
+			if !key.starts_with(&prefix) {
+				break
+			}
+			if decode_old_key::<T>(&key[prefix.len()..]).is_some() {
+				count += 1;
+			}
+			previous_key = key;
+		}
+
+		count
+	}
+
+	/// Get the asset id and the account id of a key without its prefix, if the account id is
+	/// not hashed
+	fn decode_old_key<T: Config>(mut key: &[u8]) -> Option<(T::AssetId, T::AccountId)> {
+		let asset_id = T::AssetId::decode(&mut key).ok()?;
+		let who = T::AccountId::decode(&mut key).ok()?;
+
+		// The hash prepended to the account id leaves bytes behind
+		key.is_empty().then_some((asset_id, who))
+	}
+}
+
+/// Interest accrued over the periods of the accrual clock instead of blocks
+pub mod v3 {
+	use super::*;
+	use frame_support::sp_runtime::FixedPointNumber;
+
+	/// Blocks per year of the rescale deferred by the upgrade until the accounts are migrated
+	#[frame_support::storage_alias]
+	pub type DeferredBlocksPerYear<T: Config> = StorageValue<Pallet<T>, u32>;
+
+	/// Converts the markets from accruing per block, assuming `BlocksPerYear` blocks a year, to
+	/// the periods of `Config::AccrualClock`. Rates, their bounds and pending changes are
+	/// rescaled to yield the same over a year, and the time each market was last accrued is
+	/// moved back from the current time by the duration of the blocks since, so positions keep
+	/// the interest accrued before the upgrade.
+	///
+	/// While the positions of the single market are converted, the rescale is deferred and run
+	/// from `on_idle` once every account is migrated.
+	pub struct MigrateToV3<T, BlocksPerYear>(PhantomData<(T, BlocksPerYear)>);
+
+	impl<T: Config, BlocksPerYear: Get<u32>> MigrateToV3<T, BlocksPerYear> {
+		/// Get the per-period rate yielding the same over a year as the given per-block rate
+		pub fn convert_rate(rate: FixedU128) -> FixedU128 {
+			convert_rate::<T>(rate, BlocksPerYear::get())
+		}
+	}
+
+	impl<T: Config, BlocksPerYear: Get<u32>> OnRuntimeUpgrade for MigrateToV3<T, BlocksPerYear> {
+		fn on_runtime_upgrade() -> Weight {
+			let on_chain_version = Pallet::<T>::on_chain_storage_version();
+
+			// The rates are still used to convert the positions of the single market
+			if on_chain_version < 2 && AccountsMigration::<T>::exists() {
+				DeferredBlocksPerYear::<T>::put(BlocksPerYear::get());
+				log::info!(
+					target: "runtime::defi",
+					"Deferred migration to v3 until the accounts are migrated"
+				);
+				return T::DbWeight::get().reads_writes(2, 1)
+			}
+
+			if on_chain_version != 2 {
+				log::info!(
+					target: "runtime::defi",
+					"Skipping migration to v3, on-chain storage version is {:?}",
+					on_chain_version
+				);
+				return T::DbWeight::get().reads(1)
+			}
+
+			T::DbWeight::get().reads(1).saturating_add(rescale::<T>(BlocksPerYear::get()))
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn pre_upgrade() -> Result<Vec<u8>, TryRuntimeError> {
+			if Pallet::<T>::on_chain_storage_version() != 2 {
+				return Ok(Vec::new())
+			}
+
+			let rates: Vec<(T::AssetId, FixedU128, FixedU128)> = DepositRate::<T>::iter()
+				.map(|(asset_id, rate)| (asset_id, rate, BorrowingRate::<T>::get(asset_id)))
+				.collect();
+
+			Ok(rates.encode())
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn post_upgrade(state: Vec<u8>) -> Result<(), TryRuntimeError> {
+			// Rescaled from `on_idle` once the accounts are migrated
+			if Pallet::<T>::on_chain_storage_version() < 2 && DeferredBlocksPerYear::<T>::exists() {
+				return Ok(())
+			}
+
+			ensure!(
+				Pallet::<T>::on_chain_storage_version() >= 3,
+				"Storage version was not updated to v3"
+			);
+
+			if state.is_empty() {
+				return Ok(())
+			}
+
+			let rates: Vec<(T::AssetId, FixedU128, FixedU128)> = Decode::decode(&mut &state[..])
+				.map_err(|_| "Unable to decode the state saved before the upgrade")?;
+
+			for (asset_id, deposit_rate, borrowing_rate) in rates {
+				ensure!(
+					DepositRate::<T>::get(asset_id) == Self::convert_rate(deposit_rate),
+					"Deposit rate was not converted"
+				);
+				ensure!(
+					BorrowingRate::<T>::get(asset_id) == Self::convert_rate(borrowing_rate),
+					"Borrowing rate was not converted"
+				);
+				ensure!(
+					LastAccrued::<T>::get(asset_id) <= T::AccrualClock::now(),
+					"Market accrued in the future"
+				);
+			}
+
+			Pallet::<T>::do_try_state()
+		}
+	}
+
+	/// Run the rescale deferred by the upgrade, if any
+	pub fn rescale_deferred<T: Config>() -> Weight {
+		match DeferredBlocksPerYear::<T>::take() {
+			Some(blocks_per_year) => T::DbWeight::get()
+				.reads_writes(1, 1)
+				.saturating_add(rescale::<T>(blocks_per_year)),
+			None => T::DbWeight::get().reads(1),
+		}
+	}
+
+	/// Weight of `rescale_deferred` with every market listed, each with pending changes of all
+	/// its parameters
+	pub fn rescale_deferred_weight<T: Config>() -> Weight {
+		// Two rates, the time last accrued and three pending changes per market and three bounds
+		let translated = T::MaxMarkets::get().saturating_mul(6).saturating_add(3);
+
+		T::DbWeight::get()
+			.reads_writes(1, 1)
+			.saturating_add(T::DbWeight::get().reads_writes(translated.into(), translated.into()))
+			.saturating_add(T::DbWeight::get().writes(1))
+	}
+
+	/// Rescale the markets to the accrual clock and set the storage version to v3
+	fn rescale<T: Config>(blocks_per_year: u32) -> Weight {
+		let mut translated: u64 = 0;
+
+		// Rescale the rates of every market
+		DepositRate::<T>::translate_values::<FixedU128, _>(|rate| {
+			translated += 1;
+			Some(convert_rate::<T>(rate, blocks_per_year))
+		});
+		BorrowingRate::<T>::translate_values::<FixedU128, _>(|rate| {
+			translated += 1;
+			Some(convert_rate::<T>(rate, blocks_per_year))
+		});
+
+		// Rescale the limits and the scheduled values of the rates
+		Bounds::<T>::translate::<ParameterBounds, _>(|parameter, bounds| {
+			translated += 1;
+			if !is_rate(parameter) {
+				return Some(bounds)
+			}
+			Some(ParameterBounds {
+				min: convert_rate::<T>(bounds.min, blocks_per_year),
+				max: convert_rate::<T>(bounds.max, blocks_per_year),
+				max_change: convert_rate::<T>(bounds.max_change, blocks_per_year),
+			})
+		});
+		PendingParameterChanges::<T>::translate::<PendingChange<BlockNumberFor<T>>, _>(
+			|_asset_id, parameter, change| {
+				translated += 1;
+				if !is_rate(parameter) {
+					return Some(change)
+				}
+				Some(PendingChange {
+					value: convert_rate::<T>(change.value, blocks_per_year),
+					..change
+				})
+			},
+		);
+
+		// Keep the blocks not accrued yet as the same duration of the new clock
+		let current_block: u64 = frame_system::Pallet::<T>::block_number().saturated_into();
+		let now = T::AccrualClock::now();
+		LastAccrued::<T>::translate::<BlockNumberFor<T>, _>(|_asset_id, last_accrued| {
+			translated += 1;
+			let last_accrued: u64 = last_accrued.saturated_into();
+			// Markets which never accrued start at their first interaction
+			if last_accrued.is_zero() {
+				return Some(0)
+			}
+			let elapsed =
+				convert_duration::<T>(current_block.saturating_sub(last_accrued), blocks_per_year);
+			Some(now.saturating_sub(elapsed).max(1))
+		});
+
+		StorageVersion::new(3).put::<Pallet<T>>();
+
+		log::info!(
+			target: "runtime::defi",
+			"Migrated {} entries to accrue interest by the accrual clock",
+			translated
+		);
+
+		T::DbWeight::get().reads_writes(translated, translated + 1)
+	}
+
+	/// Get the per-period rate yielding the same over a year as the given per-block rate
+	fn convert_rate<T: Config>(rate: FixedU128, blocks_per_year: u32) -> FixedU128 {
+		FixedU128::from_rational(
+			rate.into_inner().saturating_mul(blocks_per_year.into()),
+			FixedU128::accuracy().saturating_mul(T::AccrualClock::periods_per_year().into()),
+		)
+	}
+
+	/// Get the number of periods of the accrual clock lasting as long as the given blocks
+	fn convert_duration<T: Config>(blocks: u64, blocks_per_year: u32) -> u64 {
+		(u128::from(blocks).saturating_mul(T::AccrualClock::periods_per_year().into()) /
+			u128::from(blocks_per_year.max(1)))
+		.saturated_into()
+	}
+
+	fn is_rate(parameter: MarketParameter) -> bool {
+		matches!(parameter, MarketParameter::DepositRate | MarketParameter::BorrowingRate)
+	}
+}
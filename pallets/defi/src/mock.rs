@@ -1,21 +1,34 @@
 use crate as pallet_defi;
-use codec::Decode;
 use frame_support::{
-	construct_runtime, parameter_types,
-	traits::{Everything, Hooks},
+	construct_runtime, ord_parameter_types, parameter_types,
+	traits::{
+		AsEnsureOriginWithArg, ConstU128, ConstU32, ConstU64, Contains, Everything, Hooks, UnixTime,
+	},
+	weights::Weight,
 	PalletId,
 };
-use frame_system;
-use hex_literal::hex;
-use sp_core::H256;
+use frame_system::{self, offchain::AppCrypto, EnsureRoot, EnsureSigned, EnsureSignedBy};
+use pallet_defi::{AccrualClock, BlockClock, TimestampClock};
+use parking_lot::RwLock;
+use sp_core::{
+	offchain::{
+		testing::{PoolState, TestOffchainExt, TestTransactionPoolExt},
+		OffchainDbExt, OffchainWorkerExt, TransactionPoolExt,
+	},
+	H256,
+};
 use sp_runtime::{
-	traits::{AccountIdConversion, BlakeTwo256, IdentityLookup},
+	testing::{TestSignature, TestXt, UintAuthorityId},
+	traits::{AccountIdConversion, BlakeTwo256, Convert, IdentityLookup, One},
 	BuildStorage, FixedU128,
 };
+use std::{cell::RefCell, collections::BTreeMap, sync::Arc, time::Duration};
 
 pub type Block = frame_system::mocking::MockBlock<Runtime>;
+pub type Extrinsic = TestXt<RuntimeCall, ()>;
 pub type Balance = u128;
-pub type AccountId = u128;
+pub type AccountId = u64;
+pub type AssetId = u32;
 
 #[macro_export]
 macro_rules! balance {
@@ -29,13 +42,18 @@ macro_rules! balance {
 pub const ALICE: AccountId = 1;
 pub const BOB: AccountId = 2;
 pub const CHARLIE: AccountId = 3;
+pub const DAVE: AccountId = 4;
+pub const AUTHORITY: AccountId = 5;
 
-// Define helper function
-pub fn get_authority_account() -> AccountId {
-	let bytes = hex!("fed77d0df3f5068d8a875e5ae7c3248ba7c602439623cab507206af8e50edd4b");
-	AccountId::decode(&mut &bytes[..]).unwrap()
-}
+// Define test assets
+pub const NATIVE: AssetId = 0;
+pub const USDT: AssetId = 1;
+pub const WBTC: AssetId = 2;
 
+// Receipt tokens of the markets are created above this id
+pub const RECEIPT_ASSET_OFFSET: AssetId = 1_000;
+
+// Define helper function
 pub fn pallet_id() -> AccountId {
 	PalletId(*b"defipllt").into_account_truncating()
 }
@@ -57,6 +75,7 @@ construct_runtime!(
 	pub struct Runtime {
 		System: frame_system,
 		Balances: pallet_balances,
+		Assets: pallet_assets,
 		Defi: pallet_defi,
 	}
 );
@@ -105,25 +124,209 @@ impl pallet_balances::Config for Runtime {
 	type MaxLocks = ();
 	type MaxReserves = ();
 	type ReserveIdentifier = ();
-	type MaxHolds = ();
+	type MaxHolds = ConstU32<1>;
 	type MaxFreezes = ();
-	type RuntimeHoldReason = ();
+	type RuntimeHoldReason = RuntimeHoldReason;
 	type FreezeIdentifier = ();
 }
 
+pub struct MockReceiptFreezer;
+
+impl pallet_assets::FrozenBalance<AssetId, AccountId, Balance> for MockReceiptFreezer {
+	fn frozen_balance(asset: AssetId, who: &AccountId) -> Option<Balance> {
+		Defi::frozen_receipts(asset, who)
+	}
+
+	fn died(_asset: AssetId, _who: &AccountId) {}
+}
+
+impl pallet_assets::Config for Runtime {
+	type RuntimeEvent = RuntimeEvent;
+	type Balance = Balance;
+	type AssetId = AssetId;
+	type AssetIdParameter = codec::Compact<AssetId>;
+	type Currency = Balances;
+	type CreateOrigin = AsEnsureOriginWithArg<EnsureSigned<AccountId>>;
+	type ForceOrigin = EnsureRoot<AccountId>;
+	type AssetDeposit = ConstU128<1>;
+	type AssetAccountDeposit = ConstU128<1>;
+	type MetadataDepositBase = ConstU128<1>;
+	type MetadataDepositPerByte = ConstU128<1>;
+	type ApprovalDeposit = ConstU128<1>;
+	type StringLimit = ConstU32<50>;
+	type Freezer = MockReceiptFreezer;
+	type Extra = ();
+	type CallbackHandle = ();
+	type WeightInfo = ();
+	type RemoveItemsLimit = ConstU32<1000>;
+	#[cfg(feature = "runtime-benchmarks")]
+	type BenchmarkHelper = ();
+}
+
+thread_local! {
+	static PRICES: RefCell<BTreeMap<AssetId, FixedU128>> = RefCell::new(BTreeMap::new());
+}
+
+pub struct MockPriceOracle;
+
+impl MockPriceOracle {
+	pub fn set_price(asset_id: AssetId, price: Option<FixedU128>) {
+		PRICES.with(|prices| match price {
+			Some(price) => prices.borrow_mut().insert(asset_id, price),
+			None => prices.borrow_mut().remove(&asset_id),
+		});
+	}
+}
+
+impl pallet_defi::PriceOracle<AssetId> for MockPriceOracle {
+	fn get_price(asset_id: &AssetId) -> Option<FixedU128> {
+		PRICES.with(|prices| prices.borrow().get(asset_id).copied())
+	}
+}
+
+#[cfg(feature = "runtime-benchmarks")]
+pub struct MockBenchmarkHelper;
+
+#[cfg(feature = "runtime-benchmarks")]
+impl pallet_defi::BenchmarkHelper<AssetId> for MockBenchmarkHelper {
+	fn asset_id(id: u32) -> AssetId {
+		id.into()
+	}
+
+	fn set_price(asset_id: AssetId, price: FixedU128) {
+		MockPriceOracle::set_price(asset_id, Some(price));
+	}
+}
+
+pub struct MockReceiptAssetId;
+
+impl Convert<AssetId, AssetId> for MockReceiptAssetId {
+	fn convert(asset_id: AssetId) -> AssetId {
+		asset_id + RECEIPT_ASSET_OFFSET
+	}
+}
+
+parameter_types! {
+	pub JumpBaseRate: FixedU128 = FixedU128::from_rational(1, 100);
+	pub JumpMultiplier: FixedU128 = FixedU128::from_rational(10, 100);
+	pub JumpKink: FixedU128 = FixedU128::from_rational(80, 100);
+	pub JumpJumpMultiplier: FixedU128 = FixedU128::from_rational(200, 100);
+}
+
+pub type MockJumpRateModel =
+	pallet_defi::JumpRateModel<JumpBaseRate, JumpMultiplier, JumpKink, JumpJumpMultiplier>;
+
+ord_parameter_types! {
+	pub const ReserveManager: AccountId = DAVE;
+}
+
+impl frame_system::offchain::SigningTypes for Runtime {
+	type Public = UintAuthorityId;
+	type Signature = TestSignature;
+}
+
+impl<LocalCall> frame_system::offchain::SendTransactionTypes<LocalCall> for Runtime
+where
+	RuntimeCall: From<LocalCall>,
+{
+	type OverarchingCall = RuntimeCall;
+	type Extrinsic = Extrinsic;
+}
+
+/// Signs liquidations with the keys set by `UintAuthorityId::set_all_keys`
+pub struct MockAuthId;
+
+impl AppCrypto<UintAuthorityId, TestSignature> for MockAuthId {
+	type RuntimeAppPublic = UintAuthorityId;
+	type GenericSignature = TestSignature;
+	type GenericPublic = UintAuthorityId;
+}
+
 parameter_types! {
 	pub const NumberOfBlocksYearly: u32 = 5256000;
+	pub const NativeAssetId: AssetId = NATIVE;
+	pub CloseFactor: FixedU128 = FixedU128::from_rational(50, 100);
+	pub LiquidationIncentive: FixedU128 = FixedU128::from_rational(10, 100);
+	pub static ParameterChangeDelay: u64 = 0;
+	pub static AccrueByTimestamp: bool = false;
+	pub static MockTimestamp: u64 = 0;
+}
+
+/// Unix time in seconds set by the tests
+pub struct MockTime;
+
+impl UnixTime for MockTime {
+	fn now() -> Duration {
+		Duration::from_secs(MockTimestamp::get())
+	}
+}
+
+/// Accrues interest per block unless the tests switch to the timestamp
+pub struct MockAccrualClock;
+
+impl AccrualClock for MockAccrualClock {
+	fn now() -> u64 {
+		if AccrueByTimestamp::get() {
+			TimestampClock::<MockTime>::now()
+		} else {
+			BlockClock::<Runtime, NumberOfBlocksYearly>::now()
+		}
+	}
+
+	fn periods_per_year() -> u32 {
+		if AccrueByTimestamp::get() {
+			TimestampClock::<MockTime>::periods_per_year()
+		} else {
+			BlockClock::<Runtime, NumberOfBlocksYearly>::periods_per_year()
+		}
+	}
+}
+
+pub struct MockFlashLoanCallFilter;
+
+impl Contains<RuntimeCall> for MockFlashLoanCallFilter {
+	fn contains(call: &RuntimeCall) -> bool {
+		!matches!(call, RuntimeCall::Defi(_))
+	}
 }
 
 impl pallet_defi::Config for Runtime {
 	type RuntimeEvent = RuntimeEvent;
+	type RuntimeCall = RuntimeCall;
+	type CallFilter = MockFlashLoanCallFilter;
+	type Balance = Balance;
 	type Currency = Balances;
-	type NumberOfBlocksYearly = NumberOfBlocksYearly;
+	type RuntimeHoldReason = RuntimeHoldReason;
+	type AssetId = AssetId;
+	type Assets = Assets;
+	type ReceiptAssetId = MockReceiptAssetId;
+	type NativeAssetId = NativeAssetId;
+	type PriceOracle = MockPriceOracle;
+	type InterestRateModel = pallet_defi::FixedRateModel<Runtime>;
+	type AdminOrigin = EnsureRoot<AccountId>;
+	type ReserveOrigin = EnsureSignedBy<ReserveManager, AccountId>;
+	type PauseOrigin = pallet_defi::EnsurePauseGuardian<Runtime>;
+	type CloseFactor = CloseFactor;
+	type LiquidationIncentive = LiquidationIncentive;
+	type MaxMarkets = ConstU32<3>;
+	type MaxLiquidationChecksPerBlock = ConstU32<3>;
+	type MaxAccountsMigratedPerBlock = ConstU32<3>;
+	type AuthorityId = MockAuthId;
+	type MaxUnsignedLiquidations = ConstU32<2>;
+	type UnsignedPriority = ConstU64<100>;
+	type UnsignedLongevity = ConstU64<5>;
+	type ParameterChangeDelay = ParameterChangeDelay;
+	type AccrualClock = MockAccrualClock;
 	type WeightInfo = ();
+	#[cfg(feature = "runtime-benchmarks")]
+	type BenchmarkHelper = MockBenchmarkHelper;
 }
 
 pub struct ExtBuilder {
 	endowed_accounts: Vec<(AccountId, Balance)>,
+	endowed_assets: Vec<(AssetId, AccountId, Balance)>,
+	prices: Vec<(AssetId, FixedU128)>,
+	defi_genesis: pallet_defi::GenesisConfig<Runtime>,
 }
 
 impl Default for ExtBuilder {
@@ -133,12 +336,36 @@ impl Default for ExtBuilder {
 				(ALICE, balance!(100)),
 				(BOB, balance!(100)),
 				(CHARLIE, balance!(100)),
+				(DAVE, balance!(100)),
+			],
+			endowed_assets: vec![
+				(USDT, ALICE, balance!(100)),
+				(USDT, BOB, balance!(100)),
+				(USDT, CHARLIE, balance!(100)),
+				(WBTC, ALICE, balance!(100)),
+				(WBTC, BOB, balance!(100)),
+				(WBTC, CHARLIE, balance!(100)),
+			],
+			prices: vec![
+				(NATIVE, FixedU128::one()),
+				(USDT, FixedU128::one()),
+				(WBTC, FixedU128::one()),
 			],
+			defi_genesis: pallet_defi::GenesisConfig::<Runtime> {
+				authority: Some(AUTHORITY),
+				pause_guardian: Some(CHARLIE),
+				..Default::default()
+			},
 		}
 	}
 }
 
 impl ExtBuilder {
+	pub fn with_defi_genesis(mut self, defi_genesis: pallet_defi::GenesisConfig<Runtime>) -> Self {
+		self.defi_genesis = defi_genesis;
+		self
+	}
+
 	pub fn build(self) -> sp_io::TestExternalities {
 		let mut storage = RuntimeGenesisConfig::default().build_storage().unwrap();
 
@@ -148,8 +375,34 @@ impl ExtBuilder {
 		.assimilate_storage(&mut storage)
 		.unwrap();
 
+		pallet_assets::GenesisConfig::<Runtime> {
+			assets: vec![(USDT, ALICE, true, 1), (WBTC, ALICE, true, 1)],
+			metadata: vec![],
+			accounts: self.endowed_assets,
+		}
+		.assimilate_storage(&mut storage)
+		.unwrap();
+
+		self.defi_genesis.assimilate_storage(&mut storage).unwrap();
+
+		PRICES.with(|prices| *prices.borrow_mut() = self.prices.into_iter().collect());
+
 		storage.into()
 	}
+
+	/// Build externalities in which the offchain worker can submit transactions to the
+	/// returned pool
+	pub fn build_offchain(self) -> (sp_io::TestExternalities, Arc<RwLock<PoolState>>) {
+		let mut ext = self.build();
+
+		let (offchain, _) = TestOffchainExt::new();
+		let (pool, pool_state) = TestTransactionPoolExt::new();
+		ext.register_extension(OffchainDbExt::new(offchain.clone()));
+		ext.register_extension(OffchainWorkerExt::new(offchain));
+		ext.register_extension(TransactionPoolExt::new(pool));
+
+		(ext, pool_state)
+	}
 }
 
 pub fn run_to_block(n: u64) {
@@ -158,5 +411,6 @@ pub fn run_to_block(n: u64) {
 		System::set_block_number(System::block_number() + 1);
 		System::on_initialize(System::block_number());
 		Defi::on_initialize(System::block_number());
+		Defi::on_idle(System::block_number(), Weight::MAX);
 	}
 }
@@ -1,8 +1,24 @@
 mod tests {
-	use crate::{balance, mock::*, pallet, Error};
+	use crate::{
+		balance,
+		migrations::{v0, v1::MigrateToV1, v2, v2::MigrateToV2, v3, v3::MigrateToV3},
+		mock::*,
+		pallet, Error, InterestRateModel, LiquidationPayload, MarketParameter, Operation,
+		ParameterBounds, WeightInfo,
+	};
+	use codec::{Decode, Encode};
 	use frame_support::{
 		assert_err, assert_ok,
-		sp_runtime::{traits::One, FixedU128, SaturatedConversion, Saturating},
+		sp_runtime::{
+			testing::{TestSignature, UintAuthorityId},
+			traits::{CheckedDiv, One, ValidateUnsigned},
+			transaction_validity::{InvalidTransaction, TransactionSource},
+			DispatchError, FixedU128, Saturating,
+		},
+		traits::{
+			Currency, GetStorageVersion, Hooks, OnRuntimeUpgrade, StorageInfoTrait, StorageVersion,
+		},
+		weights::Weight,
 	};
 	use pallet_balances;
 
@@ -12,50 +28,60 @@ mod tests {
 	}
 
 	// Utility functions
+	/// List a market with default parameters
+	pub fn create_market(asset_id: AssetId) {
+		assert_ok!(Defi::create_market(
+			RuntimeOrigin::signed(AUTHORITY),
+			asset_id,
+			get_default_deposit_rate(),
+			get_default_borrowing_rate(),
+			get_default_collateral_factor()
+		));
+	}
+
 	/// Get user's debt
 	pub fn get_debt_amount(user: AccountId) -> Balance {
 		// Get address info and check if borrow principal is zero
-		let address_info = pallet::Accounts::<Runtime>::get(user);
+		let address_info = pallet::Accounts::<Runtime>::get(NATIVE, user);
 
 		if address_info.borrow_principal == balance!(0) {
 			return balance!(0)
 		}
 
-		// Calculate elapsed blocks
+		// Calculate borrow index accrued up to the current block
 		let current_block = frame_system::Pallet::<Runtime>::block_number();
-		let balance = get_principal_with_accrued_interest(
+		let borrow_index = get_index_with_accrued_interest(
 			current_block,
-			address_info.borrow_date,
-			address_info.borrow_principal,
+			pallet::LastAccrued::<Runtime>::get(NATIVE),
+			pallet::BorrowIndex::<Runtime>::get(NATIVE),
 			get_pallet_borrowing_rate(),
 		);
 
-		balance
+		FixedU128::from_inner(address_info.borrow_principal)
+			.saturating_mul(borrow_index)
+			.checked_div(&address_info.borrow_index)
+			.unwrap()
+			.into_inner()
 	}
 
-	/// Get principal with accrued interest
-	fn get_principal_with_accrued_interest(
+	/// Get market index with accrued interest
+	fn get_index_with_accrued_interest(
 		current_block: u64,
 		date: u64,
-		principal: Balance,
+		index: FixedU128,
 		rate: FixedU128,
-	) -> Balance {
+	) -> FixedU128 {
 		// Calculate elapsed blocks
 		let elapsed_time_block_number = current_block - date;
 		let elapsed_time: u32 = TryInto::try_into(elapsed_time_block_number)
 			.ok()
 			.expect("blockchain will not exceed 2^32 blocks; qed");
 
-		// Calculate principal with accrued interest
-		let multiplier = (FixedU128::one().saturating_add(rate))
-			.saturating_pow(elapsed_time as usize)
-			.saturating_sub(FixedU128::one());
-		let principal_fixed = FixedU128::from_inner(principal.saturated_into::<u128>());
+		// Calculate index with accrued interest
+		let multiplier =
+			(FixedU128::one().saturating_add(rate)).saturating_pow(elapsed_time as usize);
 
-		return principal_fixed
-			.saturating_add(principal_fixed.saturating_mul(multiplier))
-			.into_inner()
-			.saturated_into()
+		index.saturating_mul(multiplier)
 	}
 
 	#[test]
@@ -66,7 +92,7 @@ mod tests {
 			run_to_block(1);
 
 			assert_err!(
-				Defi::deposit(RuntimeOrigin::signed(ALICE), balance!(0)),
+				Defi::deposit(RuntimeOrigin::signed(ALICE), NATIVE, balance!(0)),
 				Error::<Runtime>::InvalidDepositAmount
 			);
 		});
@@ -80,9 +106,32 @@ mod tests {
 			run_to_block(1);
 
 			assert_err!(
-				Defi::deposit(RuntimeOrigin::signed(ALICE), balance!(101)),
+				Defi::deposit(RuntimeOrigin::signed(ALICE), NATIVE, balance!(101)),
+				Error::<Runtime>::InsufficientBalance
+			);
+		});
+	}
+
+	#[test]
+	fn deposit_keeps_existential_deposit() {
+		let mut ext = ExtBuilder::default().build();
+		ext.execute_with(|| {
+			// Start test from block 1
+			run_to_block(1);
+
+			// The whole balance can't be deposited without reaping the account
+			assert_err!(
+				Defi::deposit(RuntimeOrigin::signed(ALICE), NATIVE, balance!(100)),
 				Error::<Runtime>::InsufficientBalance
 			);
+
+			let amount = balance!(100) - ExistentialDeposit::get();
+			assert_ok!(Defi::deposit(RuntimeOrigin::signed(ALICE), NATIVE, amount));
+
+			assert_eq!(
+				pallet_balances::Pallet::<Runtime>::free_balance(ALICE),
+				ExistentialDeposit::get()
+			);
 		});
 	}
 
@@ -98,13 +147,12 @@ mod tests {
 			assert_eq!(pallet_balances::Pallet::<Runtime>::free_balance(&pallet_id()), balance!(0));
 
 			// Check ALICE lending/borrowing position before deposit
-			let alice_info = pallet::Accounts::<Runtime>::get(ALICE);
-			assert_eq!(alice_info.deposit_principal, balance!(0));
-			assert_eq!(alice_info.deposit_date, 0);
+			let alice_info = pallet::Accounts::<Runtime>::get(NATIVE, ALICE);
+			assert_eq!(Defi::receipt_balance(NATIVE, &ALICE), balance!(0));
 			assert_eq!(alice_info.borrow_principal, balance!(0));
-			assert_eq!(alice_info.borrow_date, 0);
+			assert_eq!(alice_info.borrow_index, FixedU128::from_inner(0));
 
-			assert_ok!(Defi::deposit(RuntimeOrigin::signed(ALICE), balance!(10)));
+			assert_ok!(Defi::deposit(RuntimeOrigin::signed(ALICE), NATIVE, balance!(10)));
 
 			// Check balances after deposit
 			assert_eq!(pallet_balances::Pallet::<Runtime>::free_balance(ALICE), balance!(90));
@@ -114,11 +162,10 @@ mod tests {
 			);
 
 			// Check ALICE lending/borrowing position after deposit
-			let alice_info = pallet::Accounts::<Runtime>::get(ALICE);
-			assert_eq!(alice_info.deposit_principal, balance!(10));
-			assert_eq!(alice_info.deposit_date, 1);
+			let alice_info = pallet::Accounts::<Runtime>::get(NATIVE, ALICE);
+			assert_eq!(Defi::receipt_balance(NATIVE, &ALICE), balance!(10));
 			assert_eq!(alice_info.borrow_principal, balance!(0));
-			assert_eq!(alice_info.borrow_date, 0);
+			assert_eq!(alice_info.borrow_index, FixedU128::from_inner(0));
 		});
 	}
 
@@ -134,20 +181,18 @@ mod tests {
 			assert_eq!(pallet_balances::Pallet::<Runtime>::free_balance(&pallet_id()), balance!(0));
 
 			// Check ALICE lending/borrowing position before first deposit
-			let alice_info = pallet::Accounts::<Runtime>::get(ALICE);
-			assert_eq!(alice_info.deposit_principal, balance!(0));
-			assert_eq!(alice_info.deposit_date, 0);
+			let alice_info = pallet::Accounts::<Runtime>::get(NATIVE, ALICE);
+			assert_eq!(Defi::receipt_balance(NATIVE, &ALICE), balance!(0));
 			assert_eq!(alice_info.borrow_principal, balance!(0));
-			assert_eq!(alice_info.borrow_date, 0);
+			assert_eq!(alice_info.borrow_index, FixedU128::from_inner(0));
 
-			assert_ok!(Defi::deposit(RuntimeOrigin::signed(ALICE), balance!(10)));
+			assert_ok!(Defi::deposit(RuntimeOrigin::signed(ALICE), NATIVE, balance!(10)));
 
 			// Check ALICE lending/borrowing position after first deposit
-			let alice_info = pallet::Accounts::<Runtime>::get(ALICE);
-			assert_eq!(alice_info.deposit_principal, balance!(10));
-			assert_eq!(alice_info.deposit_date, 1);
+			let alice_info = pallet::Accounts::<Runtime>::get(NATIVE, ALICE);
+			assert_eq!(Defi::receipt_balance(NATIVE, &ALICE), balance!(10));
 			assert_eq!(alice_info.borrow_principal, balance!(0));
-			assert_eq!(alice_info.borrow_date, 0);
+			assert_eq!(alice_info.borrow_index, FixedU128::from_inner(0));
 
 			// Check balances after first deposit
 			assert_eq!(pallet_balances::Pallet::<Runtime>::free_balance(ALICE), balance!(90));
@@ -167,13 +212,13 @@ mod tests {
 			);
 
 			// Check ALICE lending/borrowing position before second deposit
-			let alice_info = pallet::Accounts::<Runtime>::get(ALICE);
-			assert_eq!(alice_info.deposit_principal, balance!(10));
-			assert_eq!(alice_info.deposit_date, 1);
+			let alice_info = pallet::Accounts::<Runtime>::get(NATIVE, ALICE);
+			assert_eq!(Defi::receipt_balance(NATIVE, &ALICE), balance!(10));
 			assert_eq!(alice_info.borrow_principal, balance!(0));
-			assert_eq!(alice_info.borrow_date, 0);
+			assert_eq!(alice_info.borrow_index, FixedU128::from_inner(0));
 
-			assert_ok!(Defi::deposit(RuntimeOrigin::signed(ALICE), balance!(10)));
+			let lending_amount = Defi::get_lending_amount(NATIVE, ALICE);
+			assert_ok!(Defi::deposit(RuntimeOrigin::signed(ALICE), NATIVE, balance!(10)));
 
 			// Check balances after second deposit
 			assert_eq!(pallet_balances::Pallet::<Runtime>::free_balance(ALICE), balance!(80));
@@ -182,12 +227,15 @@ mod tests {
 				balance!(20)
 			);
 
-			// Check ALICE lending/borrowing position before second deposit
-			let alice_info = pallet::Accounts::<Runtime>::get(ALICE);
-			assert_eq!(alice_info.deposit_principal, balance!(20));
-			assert_eq!(alice_info.deposit_date, 11);
+			// Check ALICE lending/borrowing position after second deposit, up to rounding of
+			// the receipt tokens
+			assert!(
+				Defi::get_lending_amount(NATIVE, ALICE).abs_diff(lending_amount + balance!(10)) <=
+					2
+			);
+			let alice_info = pallet::Accounts::<Runtime>::get(NATIVE, ALICE);
 			assert_eq!(alice_info.borrow_principal, balance!(0));
-			assert_eq!(alice_info.borrow_date, 0);
+			assert_eq!(alice_info.borrow_index, FixedU128::from_inner(0));
 		});
 	}
 
@@ -199,7 +247,7 @@ mod tests {
 			run_to_block(1);
 
 			assert_err!(
-				Defi::borrow(RuntimeOrigin::signed(ALICE), balance!(10)),
+				Defi::borrow(RuntimeOrigin::signed(ALICE), NATIVE, balance!(10)),
 				Error::<Runtime>::PalletHasNotEnoughFunds
 			);
 		});
@@ -212,10 +260,10 @@ mod tests {
 			// Start test from block 1
 			run_to_block(1);
 
-			assert_ok!(Defi::deposit(RuntimeOrigin::signed(CHARLIE), balance!(99)));
+			assert_ok!(Defi::deposit(RuntimeOrigin::signed(CHARLIE), NATIVE, balance!(99)));
 
 			assert_err!(
-				Defi::borrow(RuntimeOrigin::signed(ALICE), balance!(10)),
+				Defi::borrow(RuntimeOrigin::signed(ALICE), NATIVE, balance!(10)),
 				Error::<Runtime>::UnallowedBorrowAmount
 			);
 		});
@@ -229,8 +277,8 @@ mod tests {
 			run_to_block(1);
 
 			// Execute prerequired deposits
-			assert_ok!(Defi::deposit(RuntimeOrigin::signed(CHARLIE), balance!(99)));
-			assert_ok!(Defi::deposit(RuntimeOrigin::signed(ALICE), balance!(10)));
+			assert_ok!(Defi::deposit(RuntimeOrigin::signed(CHARLIE), NATIVE, balance!(99)));
+			assert_ok!(Defi::deposit(RuntimeOrigin::signed(ALICE), NATIVE, balance!(10)));
 
 			// Check balances before borrow
 			assert_eq!(pallet_balances::Pallet::<Runtime>::free_balance(ALICE), balance!(90));
@@ -240,13 +288,12 @@ mod tests {
 			);
 
 			// Check ALICE lending/borrowing position before borrow
-			let alice_info = pallet::Accounts::<Runtime>::get(ALICE);
-			assert_eq!(alice_info.deposit_principal, balance!(10));
-			assert_eq!(alice_info.deposit_date, 1);
+			let alice_info = pallet::Accounts::<Runtime>::get(NATIVE, ALICE);
+			assert_eq!(Defi::receipt_balance(NATIVE, &ALICE), balance!(10));
 			assert_eq!(alice_info.borrow_principal, balance!(0));
-			assert_eq!(alice_info.borrow_date, 0);
+			assert_eq!(alice_info.borrow_index, FixedU128::from_inner(0));
 
-			assert_ok!(Defi::borrow(RuntimeOrigin::signed(ALICE), balance!(5)));
+			assert_ok!(Defi::borrow(RuntimeOrigin::signed(ALICE), NATIVE, balance!(5)));
 
 			// Check balances after borrow
 			assert_eq!(pallet_balances::Pallet::<Runtime>::free_balance(ALICE), balance!(95));
@@ -256,11 +303,10 @@ mod tests {
 			);
 
 			// Check ALICE lending/borrowing position after borrow
-			let alice_info = pallet::Accounts::<Runtime>::get(ALICE);
-			assert_eq!(alice_info.deposit_principal, balance!(10));
-			assert_eq!(alice_info.deposit_date, 1);
+			let alice_info = pallet::Accounts::<Runtime>::get(NATIVE, ALICE);
+			assert_eq!(Defi::receipt_balance(NATIVE, &ALICE), balance!(10));
 			assert_eq!(alice_info.borrow_principal, balance!(5));
-			assert_eq!(alice_info.borrow_date, 1);
+			assert_eq!(alice_info.borrow_index, FixedU128::one());
 		});
 	}
 
@@ -272,8 +318,8 @@ mod tests {
 			run_to_block(1);
 
 			// Execute prerequired deposits
-			assert_ok!(Defi::deposit(RuntimeOrigin::signed(CHARLIE), balance!(99)));
-			assert_ok!(Defi::deposit(RuntimeOrigin::signed(ALICE), balance!(10)));
+			assert_ok!(Defi::deposit(RuntimeOrigin::signed(CHARLIE), NATIVE, balance!(99)));
+			assert_ok!(Defi::deposit(RuntimeOrigin::signed(ALICE), NATIVE, balance!(10)));
 
 			// Check balances before first borrow
 			assert_eq!(pallet_balances::Pallet::<Runtime>::free_balance(ALICE), balance!(90));
@@ -283,13 +329,12 @@ mod tests {
 			);
 
 			// Check ALICE lending/borrowing position before first borrow
-			let alice_info = pallet::Accounts::<Runtime>::get(ALICE);
-			assert_eq!(alice_info.deposit_principal, balance!(10));
-			assert_eq!(alice_info.deposit_date, 1);
+			let alice_info = pallet::Accounts::<Runtime>::get(NATIVE, ALICE);
+			assert_eq!(Defi::receipt_balance(NATIVE, &ALICE), balance!(10));
 			assert_eq!(alice_info.borrow_principal, balance!(0));
-			assert_eq!(alice_info.borrow_date, 0);
+			assert_eq!(alice_info.borrow_index, FixedU128::from_inner(0));
 
-			assert_ok!(Defi::borrow(RuntimeOrigin::signed(ALICE), balance!(5)));
+			assert_ok!(Defi::borrow(RuntimeOrigin::signed(ALICE), NATIVE, balance!(5)));
 
 			// Check balances after borrow
 			assert_eq!(pallet_balances::Pallet::<Runtime>::free_balance(ALICE), balance!(95));
@@ -299,11 +344,10 @@ mod tests {
 			);
 
 			// Check ALICE lending/borrowing position after first borrow
-			let alice_info = pallet::Accounts::<Runtime>::get(ALICE);
-			assert_eq!(alice_info.deposit_principal, balance!(10));
-			assert_eq!(alice_info.deposit_date, 1);
+			let alice_info = pallet::Accounts::<Runtime>::get(NATIVE, ALICE);
+			assert_eq!(Defi::receipt_balance(NATIVE, &ALICE), balance!(10));
 			assert_eq!(alice_info.borrow_principal, balance!(5));
-			assert_eq!(alice_info.borrow_date, 1);
+			assert_eq!(alice_info.borrow_index, FixedU128::one());
 
 			// Run blockchain to block 11
 			run_to_block(11);
@@ -316,13 +360,12 @@ mod tests {
 			);
 
 			// Check ALICE lending/borrowing position before first borrow
-			let alice_info = pallet::Accounts::<Runtime>::get(ALICE);
-			assert_eq!(alice_info.deposit_principal, balance!(10));
-			assert_eq!(alice_info.deposit_date, 1);
+			let alice_info = pallet::Accounts::<Runtime>::get(NATIVE, ALICE);
+			assert_eq!(Defi::receipt_balance(NATIVE, &ALICE), balance!(10));
 			assert_eq!(alice_info.borrow_principal, balance!(5));
-			assert_eq!(alice_info.borrow_date, 1);
+			assert_eq!(alice_info.borrow_index, FixedU128::one());
 
-			assert_ok!(Defi::borrow(RuntimeOrigin::signed(ALICE), balance!(1)));
+			assert_ok!(Defi::borrow(RuntimeOrigin::signed(ALICE), NATIVE, balance!(1)));
 
 			// Check balances after borrow
 			assert_eq!(pallet_balances::Pallet::<Runtime>::free_balance(ALICE), balance!(96));
@@ -334,11 +377,10 @@ mod tests {
 			// Check ALICE lending/borrowing position after first borrow
 			let calculated_interest = get_debt_amount(ALICE);
 
-			let alice_info = pallet::Accounts::<Runtime>::get(ALICE);
-			assert_eq!(alice_info.deposit_principal, balance!(10));
-			assert_eq!(alice_info.deposit_date, 1);
+			let alice_info = pallet::Accounts::<Runtime>::get(NATIVE, ALICE);
+			assert_eq!(Defi::receipt_balance(NATIVE, &ALICE), balance!(10));
 			assert_eq!(alice_info.borrow_principal, calculated_interest);
-			assert_eq!(alice_info.borrow_date, 11);
+			assert_eq!(alice_info.borrow_index, pallet::BorrowIndex::<Runtime>::get(NATIVE));
 		});
 	}
 
@@ -350,7 +392,7 @@ mod tests {
 			run_to_block(1);
 
 			assert_err!(
-				Defi::withdraw(RuntimeOrigin::signed(ALICE), balance!(10)),
+				Defi::withdraw(RuntimeOrigin::signed(ALICE), NATIVE, balance!(10)),
 				Error::<Runtime>::NoFundsDeposited
 			);
 		});
@@ -364,10 +406,10 @@ mod tests {
 			run_to_block(1);
 
 			// Execute prerequired deposits
-			assert_ok!(Defi::deposit(RuntimeOrigin::signed(ALICE), balance!(10)));
+			assert_ok!(Defi::deposit(RuntimeOrigin::signed(ALICE), NATIVE, balance!(10)));
 
 			assert_err!(
-				Defi::withdraw(RuntimeOrigin::signed(ALICE), balance!(11)),
+				Defi::withdraw(RuntimeOrigin::signed(ALICE), NATIVE, balance!(11)),
 				Error::<Runtime>::UserHasNotEnoughFunds
 			);
 		});
@@ -381,7 +423,7 @@ mod tests {
 			run_to_block(1);
 
 			// Execute prerequired deposit and transaction
-			assert_ok!(Defi::deposit(RuntimeOrigin::signed(ALICE), balance!(10)));
+			assert_ok!(Defi::deposit(RuntimeOrigin::signed(ALICE), NATIVE, balance!(10)));
 			assert_ok!(pallet_balances::Pallet::<Runtime>::transfer_keep_alive(
 				RuntimeOrigin::signed(pallet_id()),
 				BOB,
@@ -389,7 +431,7 @@ mod tests {
 			));
 
 			assert_err!(
-				Defi::withdraw(RuntimeOrigin::signed(ALICE), balance!(6)),
+				Defi::withdraw(RuntimeOrigin::signed(ALICE), NATIVE, balance!(6)),
 				Error::<Runtime>::PalletHasNotEnoughFunds
 			);
 		});
@@ -403,7 +445,7 @@ mod tests {
 			run_to_block(1);
 
 			// Execute prerequired deposit
-			assert_ok!(Defi::deposit(RuntimeOrigin::signed(ALICE), balance!(10)));
+			assert_ok!(Defi::deposit(RuntimeOrigin::signed(ALICE), NATIVE, balance!(10)));
 
 			// Check balances before withdrawl
 			assert_eq!(pallet_balances::Pallet::<Runtime>::free_balance(ALICE), balance!(90));
@@ -413,27 +455,229 @@ mod tests {
 			);
 
 			// Check ALICE lending/borrowing position before withdrawl
-			let alice_info = pallet::Accounts::<Runtime>::get(ALICE);
-			assert_eq!(alice_info.deposit_principal, balance!(10));
-			assert_eq!(alice_info.deposit_date, 1);
+			let alice_info = pallet::Accounts::<Runtime>::get(NATIVE, ALICE);
+			assert_eq!(Defi::receipt_balance(NATIVE, &ALICE), balance!(10));
 			assert_eq!(alice_info.borrow_principal, balance!(0));
-			assert_eq!(alice_info.borrow_date, 0);
+			assert_eq!(alice_info.borrow_index, FixedU128::from_inner(0));
 
 			// Run blockchain to block 11
 			run_to_block(11);
 
-			assert_ok!(Defi::withdraw(RuntimeOrigin::signed(ALICE), balance!(9)));
+			let lending_amount = Defi::get_lending_amount(NATIVE, ALICE);
+			assert_ok!(Defi::withdraw(RuntimeOrigin::signed(ALICE), NATIVE, balance!(9)));
 
 			// Check balances after withdrawl
 			assert_eq!(pallet_balances::Pallet::<Runtime>::free_balance(ALICE), balance!(99));
 			assert_eq!(pallet_balances::Pallet::<Runtime>::free_balance(&pallet_id()), balance!(1));
 
-			// Check ALICE lending/borrowing position after withdrawl
-			let alice_info = pallet::Accounts::<Runtime>::get(ALICE);
-			assert_eq!(alice_info.deposit_principal, balance!(1));
-			assert_eq!(alice_info.deposit_date, 11);
+			// Check ALICE lending/borrowing position after withdrawl, up to rounding of the
+			// receipt tokens
+			assert!(
+				Defi::get_lending_amount(NATIVE, ALICE).abs_diff(lending_amount - balance!(9)) <= 2
+			);
+			let alice_info = pallet::Accounts::<Runtime>::get(NATIVE, ALICE);
 			assert_eq!(alice_info.borrow_principal, balance!(0));
-			assert_eq!(alice_info.borrow_date, 0);
+			assert_eq!(alice_info.borrow_index, FixedU128::from_inner(0));
+		});
+	}
+
+	#[test]
+	fn withdraw_with_open_debt_fails() {
+		let mut ext = ExtBuilder::default().build();
+		ext.execute_with(|| {
+			// Start test from block 1
+			run_to_block(1);
+
+			create_market(USDT);
+
+			// Execute prerequired deposits and borrow against the native deposit
+			assert_ok!(Defi::deposit(RuntimeOrigin::signed(CHARLIE), USDT, balance!(50)));
+			assert_ok!(Defi::deposit(RuntimeOrigin::signed(ALICE), NATIVE, balance!(10)));
+			assert_ok!(Defi::borrow(RuntimeOrigin::signed(ALICE), USDT, balance!(7)));
+
+			// Withdrawing the whole deposit would leave the debt uncovered
+			assert_err!(
+				Defi::withdraw(RuntimeOrigin::signed(ALICE), NATIVE, balance!(10)),
+				Error::<Runtime>::UnallowedWithdrawAmount
+			);
+			assert_err!(
+				Defi::withdraw(RuntimeOrigin::signed(ALICE), NATIVE, balance!(1)),
+				Error::<Runtime>::UnallowedWithdrawAmount
+			);
+			assert_eq!(Defi::receipt_balance(NATIVE, &ALICE), balance!(10));
+			assert_eq!(pallet_balances::Pallet::<Runtime>::free_balance(ALICE), balance!(90));
+
+			// The part of the deposit which is not needed to cover the debt can be withdrawn
+			assert_ok!(Defi::withdraw(RuntimeOrigin::signed(ALICE), NATIVE, balance!(1) / 2));
+			assert_eq!(
+				pallet_balances::Pallet::<Runtime>::free_balance(ALICE),
+				balance!(90) + balance!(1) / 2
+			);
+		});
+	}
+
+	#[test]
+	fn withdraw_without_debt_and_price_ok() {
+		let mut ext = ExtBuilder::default().build();
+		ext.execute_with(|| {
+			// Start test from block 1
+			run_to_block(1);
+
+			assert_ok!(Defi::deposit(RuntimeOrigin::signed(ALICE), NATIVE, balance!(10)));
+			assert_ok!(Defi::deposit_collateral(RuntimeOrigin::signed(ALICE), NATIVE, balance!(5)));
+			assert_ok!(Defi::deposit(RuntimeOrigin::signed(BOB), NATIVE, balance!(10)));
+			assert_ok!(Defi::borrow(RuntimeOrigin::signed(BOB), NATIVE, balance!(1)));
+
+			// Accounts without debt withdraw while their positions can't be priced
+			MockPriceOracle::set_price(NATIVE, None);
+			assert_ok!(Defi::withdraw(RuntimeOrigin::signed(ALICE), NATIVE, balance!(10)));
+			assert_ok!(Defi::withdraw_collateral(
+				RuntimeOrigin::signed(ALICE),
+				NATIVE,
+				balance!(5)
+			));
+			assert_eq!(pallet_balances::Pallet::<Runtime>::free_balance(ALICE), balance!(100));
+
+			// The debt of a borrower still has to be priced
+			assert_err!(
+				Defi::withdraw(RuntimeOrigin::signed(BOB), NATIVE, balance!(1)),
+				Error::<Runtime>::PriceUnavailable
+			);
+		});
+	}
+
+	#[test]
+	fn receipt_exchange_rate_grows_ok() {
+		let mut ext = ExtBuilder::default().build();
+		ext.execute_with(|| {
+			// Start test from block 1
+			run_to_block(1);
+
+			assert_ok!(Defi::deposit(RuntimeOrigin::signed(ALICE), NATIVE, balance!(10)));
+			assert_eq!(Assets::balance(NATIVE + RECEIPT_ASSET_OFFSET, ALICE), balance!(10));
+
+			// Run blockchain to block 11
+			run_to_block(11);
+
+			// Receipt balance is unchanged while its value grows with the supply index
+			let supply_index = Defi::accrued_market_state(NATIVE).supply_index;
+			assert!(supply_index > FixedU128::one());
+			assert_eq!(Defi::receipt_balance(NATIVE, &ALICE), balance!(10));
+			assert_eq!(
+				Defi::get_lending_amount(NATIVE, ALICE),
+				FixedU128::from_inner(balance!(10)).saturating_mul(supply_index).into_inner()
+			);
+		});
+	}
+
+	#[test]
+	fn receipt_transfer_moves_claim_ok() {
+		let mut ext = ExtBuilder::default().build();
+		ext.execute_with(|| {
+			// Start test from block 1
+			run_to_block(1);
+
+			assert_ok!(Defi::deposit(RuntimeOrigin::signed(ALICE), NATIVE, balance!(10)));
+
+			// ALICE has no debt and can transfer part of the receipt tokens to BOB
+			assert_ok!(Assets::transfer(
+				RuntimeOrigin::signed(ALICE),
+				(NATIVE + RECEIPT_ASSET_OFFSET).into(),
+				BOB,
+				balance!(4)
+			));
+
+			assert_eq!(Defi::get_lending_amount(NATIVE, ALICE), balance!(6));
+			assert_eq!(Defi::get_lending_amount(NATIVE, BOB), balance!(4));
+
+			// BOB withdraws the transferred claim, ALICE can't withdraw it anymore
+			assert_ok!(Defi::withdraw(RuntimeOrigin::signed(BOB), NATIVE, balance!(4)));
+			assert_eq!(pallet_balances::Pallet::<Runtime>::free_balance(BOB), balance!(104));
+			assert_eq!(Defi::receipt_balance(NATIVE, &BOB), balance!(0));
+
+			assert_err!(
+				Defi::withdraw(RuntimeOrigin::signed(ALICE), NATIVE, balance!(7)),
+				Error::<Runtime>::UserHasNotEnoughFunds
+			);
+			assert_ok!(Defi::do_try_state());
+		});
+	}
+
+	#[test]
+	fn receipt_transfer_with_debt_fails() {
+		let mut ext = ExtBuilder::default().build();
+		ext.execute_with(|| {
+			// Start test from block 1
+			run_to_block(1);
+
+			assert_ok!(Defi::deposit(RuntimeOrigin::signed(ALICE), NATIVE, balance!(10)));
+			assert_ok!(Defi::borrow(RuntimeOrigin::signed(ALICE), NATIVE, balance!(5)));
+			assert_eq!(Defi::borrowed_markets(ALICE), 1);
+
+			// Receipt tokens backing the debt are frozen, only the surplus can be transferred
+			assert_err!(
+				Assets::transfer(
+					RuntimeOrigin::signed(ALICE),
+					(NATIVE + RECEIPT_ASSET_OFFSET).into(),
+					BOB,
+					balance!(4)
+				),
+				pallet_assets::Error::<Runtime>::BalanceLow
+			);
+			assert_ok!(Assets::transfer(
+				RuntimeOrigin::signed(ALICE),
+				(NATIVE + RECEIPT_ASSET_OFFSET).into(),
+				BOB,
+				balance!(3)
+			));
+			assert_eq!(Defi::get_lending_amount(NATIVE, ALICE), balance!(7));
+			assert!(!Defi::is_liquidatable(&ALICE).unwrap());
+
+			// Receipt tokens are released once the debt is repaid
+			assert_ok!(Defi::repay(RuntimeOrigin::signed(ALICE), NATIVE, balance!(5)));
+			assert_eq!(Defi::borrowed_markets(ALICE), 0);
+			assert_ok!(Assets::transfer(
+				RuntimeOrigin::signed(ALICE),
+				(NATIVE + RECEIPT_ASSET_OFFSET).into(),
+				BOB,
+				balance!(7)
+			));
+			assert_eq!(Defi::get_lending_amount(NATIVE, BOB), balance!(10));
+			assert_ok!(Defi::do_try_state());
+		});
+	}
+
+	#[test]
+	fn receipt_transfer_without_debt_and_price_ok() {
+		let mut ext = ExtBuilder::default().build();
+		ext.execute_with(|| {
+			// Start test from block 1
+			run_to_block(1);
+
+			assert_ok!(Defi::deposit(RuntimeOrigin::signed(ALICE), NATIVE, balance!(10)));
+			assert_ok!(Defi::deposit(RuntimeOrigin::signed(BOB), NATIVE, balance!(10)));
+			assert_ok!(Defi::borrow(RuntimeOrigin::signed(BOB), NATIVE, balance!(5)));
+
+			// Accounts without debt aren't frozen while their positions can't be priced
+			MockPriceOracle::set_price(NATIVE, None);
+			assert_ok!(Assets::transfer(
+				RuntimeOrigin::signed(ALICE),
+				(NATIVE + RECEIPT_ASSET_OFFSET).into(),
+				CHARLIE,
+				balance!(10)
+			));
+			assert_eq!(Defi::get_lending_amount(NATIVE, CHARLIE), balance!(10));
+
+			// Receipt tokens backing a debt are all frozen
+			assert_err!(
+				Assets::transfer(
+					RuntimeOrigin::signed(BOB),
+					(NATIVE + RECEIPT_ASSET_OFFSET).into(),
+					CHARLIE,
+					balance!(1)
+				),
+				pallet_assets::Error::<Runtime>::BalanceLow
+			);
 		});
 	}
 
@@ -446,7 +690,7 @@ mod tests {
 
 			// Execute prerequired deposit
 			assert_err!(
-				Defi::repay(RuntimeOrigin::signed(ALICE), balance!(110)),
+				Defi::repay(RuntimeOrigin::signed(ALICE), NATIVE, balance!(110)),
 				Error::<Runtime>::InsufficientBalance
 			);
 		});
@@ -461,7 +705,7 @@ mod tests {
 
 			// Execute prerequired deposit
 			assert_err!(
-				Defi::repay(RuntimeOrigin::signed(ALICE), balance!(10)),
+				Defi::repay(RuntimeOrigin::signed(ALICE), NATIVE, balance!(10)),
 				Error::<Runtime>::NothingToRepay
 			);
 		});
@@ -475,8 +719,8 @@ mod tests {
 			run_to_block(1);
 
 			// Execute prerequired deposits and withdrawls
-			assert_ok!(Defi::deposit(RuntimeOrigin::signed(ALICE), balance!(10)));
-			assert_ok!(Defi::borrow(RuntimeOrigin::signed(ALICE), balance!(5)));
+			assert_ok!(Defi::deposit(RuntimeOrigin::signed(ALICE), NATIVE, balance!(10)));
+			assert_ok!(Defi::borrow(RuntimeOrigin::signed(ALICE), NATIVE, balance!(5)));
 
 			// Run blockchain to block 11
 			run_to_block(11);
@@ -486,13 +730,12 @@ mod tests {
 			assert_eq!(pallet_balances::Pallet::<Runtime>::free_balance(&pallet_id()), balance!(5));
 
 			// Check ALICE lending/borrowing position before repay
-			let alice_info = pallet::Accounts::<Runtime>::get(ALICE);
-			assert_eq!(alice_info.deposit_principal, balance!(10));
-			assert_eq!(alice_info.deposit_date, 1);
+			let alice_info = pallet::Accounts::<Runtime>::get(NATIVE, ALICE);
+			assert_eq!(Defi::receipt_balance(NATIVE, &ALICE), balance!(10));
 			assert_eq!(alice_info.borrow_principal, balance!(5));
-			assert_eq!(alice_info.borrow_date, 1);
+			assert_eq!(alice_info.borrow_index, FixedU128::one());
 
-			assert_ok!(Defi::repay(RuntimeOrigin::signed(ALICE), balance!(5)));
+			assert_ok!(Defi::repay(RuntimeOrigin::signed(ALICE), NATIVE, balance!(5)));
 
 			// Check balances after repay
 			assert_eq!(pallet_balances::Pallet::<Runtime>::free_balance(ALICE), balance!(90));
@@ -504,11 +747,10 @@ mod tests {
 			// Check ALICE lending/borrowing position after repay
 			let calculated_interest = get_debt_amount(ALICE) - 8285;
 
-			let alice_info = pallet::Accounts::<Runtime>::get(ALICE);
-			assert_eq!(alice_info.deposit_principal, balance!(10));
-			assert_eq!(alice_info.deposit_date, 1);
+			let alice_info = pallet::Accounts::<Runtime>::get(NATIVE, ALICE);
+			assert_eq!(Defi::receipt_balance(NATIVE, &ALICE), balance!(10));
 			assert_eq!(alice_info.borrow_principal, calculated_interest + 8285);
-			assert_eq!(alice_info.borrow_date, 11);
+			assert_eq!(alice_info.borrow_index, pallet::BorrowIndex::<Runtime>::get(NATIVE));
 		});
 	}
 
@@ -520,8 +762,8 @@ mod tests {
 			run_to_block(1);
 
 			// Execute prerequired deposits and withdrawls
-			assert_ok!(Defi::deposit(RuntimeOrigin::signed(ALICE), balance!(10)));
-			assert_ok!(Defi::borrow(RuntimeOrigin::signed(ALICE), balance!(5)));
+			assert_ok!(Defi::deposit(RuntimeOrigin::signed(ALICE), NATIVE, balance!(10)));
+			assert_ok!(Defi::borrow(RuntimeOrigin::signed(ALICE), NATIVE, balance!(5)));
 
 			// Run blockchain to block 11
 			run_to_block(11);
@@ -531,14 +773,13 @@ mod tests {
 			assert_eq!(pallet_balances::Pallet::<Runtime>::free_balance(&pallet_id()), balance!(5));
 
 			// Check ALICE lending/borrowing position before repay
-			let alice_info = pallet::Accounts::<Runtime>::get(ALICE);
-			assert_eq!(alice_info.deposit_principal, balance!(10));
-			assert_eq!(alice_info.deposit_date, 1);
+			let alice_info = pallet::Accounts::<Runtime>::get(NATIVE, ALICE);
+			assert_eq!(Defi::receipt_balance(NATIVE, &ALICE), balance!(10));
 			assert_eq!(alice_info.borrow_principal, balance!(5));
-			assert_eq!(alice_info.borrow_date, 1);
+			assert_eq!(alice_info.borrow_index, FixedU128::one());
 
 			let calculated_interest = get_debt_amount(ALICE);
-			assert_ok!(Defi::repay(RuntimeOrigin::signed(ALICE), calculated_interest));
+			assert_ok!(Defi::repay(RuntimeOrigin::signed(ALICE), NATIVE, calculated_interest));
 
 			// Check balances after repay
 			assert_eq!(
@@ -551,11 +792,10 @@ mod tests {
 			);
 
 			// Check ALICE lending/borrowing position after repay
-			let alice_info = pallet::Accounts::<Runtime>::get(ALICE);
-			assert_eq!(alice_info.deposit_principal, balance!(10));
-			assert_eq!(alice_info.deposit_date, 1);
+			let alice_info = pallet::Accounts::<Runtime>::get(NATIVE, ALICE);
+			assert_eq!(Defi::receipt_balance(NATIVE, &ALICE), balance!(10));
 			assert_eq!(alice_info.borrow_principal, balance!(0));
-			assert_eq!(alice_info.borrow_date, 0);
+			assert_eq!(alice_info.borrow_index, pallet::BorrowIndex::<Runtime>::get(NATIVE));
 		});
 	}
 
@@ -567,7 +807,11 @@ mod tests {
 			run_to_block(1);
 
 			assert_err!(
-				Defi::update_deposit_rate(RuntimeOrigin::signed(ALICE), FixedU128::from_inner(1)),
+				Defi::update_deposit_rate(
+					RuntimeOrigin::signed(ALICE),
+					NATIVE,
+					FixedU128::from_inner(1)
+				),
 				Error::<Runtime>::UnauthorizedUserAction
 			);
 		});
@@ -581,15 +825,155 @@ mod tests {
 			run_to_block(1);
 
 			// Check deposit rate before update
-			assert_eq!(pallet::DepositRate::<Runtime>::get(), get_default_deposit_rate(),);
+			assert_eq!(pallet::DepositRate::<Runtime>::get(NATIVE), get_default_deposit_rate(),);
 
 			assert_ok!(Defi::update_deposit_rate(
-				RuntimeOrigin::signed(get_authority_account()),
+				RuntimeOrigin::signed(AUTHORITY),
+				NATIVE,
 				FixedU128::from_inner(1)
 			),);
 
 			// Check deposit rate after update
-			assert_eq!(pallet::DepositRate::<Runtime>::get(), FixedU128::from_inner(1),);
+			assert_eq!(pallet::DepositRate::<Runtime>::get(NATIVE), FixedU128::from_inner(1),);
+		});
+	}
+
+	#[test]
+	fn update_deposit_rate_admin_origin_ok() {
+		let mut ext = ExtBuilder::default().build();
+		ext.execute_with(|| {
+			// Start test from block 1
+			run_to_block(1);
+
+			assert_ok!(Defi::update_deposit_rate(
+				RuntimeOrigin::root(),
+				NATIVE,
+				FixedU128::from_inner(1)
+			));
+
+			assert_eq!(pallet::DepositRate::<Runtime>::get(NATIVE), FixedU128::from_inner(1));
+		});
+	}
+
+	#[test]
+	fn set_authority_bad_origin() {
+		let mut ext = ExtBuilder::default().build();
+		ext.execute_with(|| {
+			// Start test from block 1
+			run_to_block(1);
+
+			// Authority account can't replace itself
+			assert_err!(
+				Defi::set_authority(RuntimeOrigin::signed(AUTHORITY), Some(ALICE)),
+				DispatchError::BadOrigin
+			);
+		});
+	}
+
+	#[test]
+	fn genesis_config_ok() {
+		let genesis = pallet::GenesisConfig::<Runtime> {
+			authority: Some(CHARLIE),
+			pause_guardian: Some(DAVE),
+			markets: vec![(
+				USDT,
+				get_default_deposit_rate(),
+				get_default_borrowing_rate(),
+				FixedU128::from_rational(50, 100),
+				FixedU128::from_rational(10, 100),
+			)],
+			pallet_balance: balance!(1),
+			positions: vec![
+				(BOB, USDT, balance!(50), 0),
+				(ALICE, NATIVE, balance!(10), 0),
+				(ALICE, USDT, 0, balance!(5)),
+			],
+		};
+
+		let mut ext = ExtBuilder::default().with_defi_genesis(genesis).build();
+		ext.execute_with(|| {
+			// Start test from block 1
+			run_to_block(1);
+
+			// Check authority and market parameters
+			assert_eq!(pallet::AuthorityAccount::<Runtime>::get(), Some(CHARLIE));
+			assert_eq!(pallet::PauseGuardian::<Runtime>::get(), Some(DAVE));
+			assert!(Defi::market_exists(USDT));
+			assert_eq!(
+				pallet::CollateralFactor::<Runtime>::get(USDT),
+				FixedU128::from_rational(50, 100)
+			);
+			assert_eq!(
+				pallet::ReserveFactor::<Runtime>::get(USDT),
+				FixedU128::from_rational(10, 100)
+			);
+
+			// Check balances of the pallet and accounts
+			assert_eq!(Balances::free_balance(pallet_id()), balance!(11));
+			assert_eq!(Balances::free_balance(ALICE), balance!(90));
+			assert_eq!(Assets::balance(USDT, pallet_id()), balance!(45));
+			assert_eq!(Assets::balance(USDT, ALICE), balance!(105));
+			assert_eq!(Assets::balance(USDT, BOB), balance!(50));
+
+			// Check positions and market totals
+			assert_eq!(Defi::get_lending_amount(USDT, BOB), balance!(50));
+			assert_eq!(Defi::get_lending_amount(NATIVE, ALICE), balance!(10));
+			assert_eq!(Defi::get_debt_amount(USDT, ALICE), balance!(5));
+			assert_eq!(pallet::TotalDeposits::<Runtime>::get(USDT), balance!(50));
+			assert_eq!(pallet::TotalBorrows::<Runtime>::get(USDT), balance!(5));
+			assert_ok!(Defi::do_try_state());
+		});
+	}
+
+	#[test]
+	#[should_panic(expected = "Position in an unlisted market")]
+	fn genesis_config_unlisted_market() {
+		let genesis = pallet::GenesisConfig::<Runtime> {
+			positions: vec![(BOB, USDT, balance!(50), 0)],
+			..Default::default()
+		};
+
+		ExtBuilder::default().with_defi_genesis(genesis).build();
+	}
+
+	#[test]
+	fn set_authority_ok() {
+		let mut ext = ExtBuilder::default().build();
+		ext.execute_with(|| {
+			// Start test from block 1
+			run_to_block(1);
+
+			assert_ok!(Defi::set_authority(RuntimeOrigin::root(), Some(ALICE)));
+			System::assert_last_event(pallet::Event::AuthoritySet(Some(ALICE)).into());
+
+			// New authority account can update parameters, previous one can't
+			assert_ok!(Defi::update_deposit_rate(
+				RuntimeOrigin::signed(ALICE),
+				NATIVE,
+				FixedU128::from_inner(1)
+			));
+			assert_err!(
+				Defi::update_deposit_rate(
+					RuntimeOrigin::signed(AUTHORITY),
+					NATIVE,
+					FixedU128::from_inner(1)
+				),
+				Error::<Runtime>::UnauthorizedUserAction
+			);
+
+			// Without authority account only the admin origin can update parameters
+			assert_ok!(Defi::set_authority(RuntimeOrigin::root(), None));
+			System::assert_last_event(pallet::Event::AuthoritySet(None).into());
+			assert_eq!(pallet::AuthorityAccount::<Runtime>::get(), None);
+
+			assert_err!(
+				Defi::update_deposit_rate(
+					RuntimeOrigin::signed(ALICE),
+					NATIVE,
+					FixedU128::from_inner(1)
+				),
+				Error::<Runtime>::UnauthorizedUserAction
+			);
 		});
 	}
 
@@ -601,7 +985,11 @@ mod tests {
 			run_to_block(1);
 
 			assert_err!(
-				Defi::update_borrowing_rate(RuntimeOrigin::signed(ALICE), FixedU128::from_inner(1)),
+				Defi::update_borrowing_rate(
+					RuntimeOrigin::signed(ALICE),
+					NATIVE,
+					FixedU128::from_inner(1)
+				),
 				Error::<Runtime>::UnauthorizedUserAction
 			);
 		});
@@ -615,15 +1003,16 @@ mod tests {
 			run_to_block(1);
 
 			// Check borrowing rate before update
-			assert_eq!(pallet::BorrowingRate::<Runtime>::get(), get_default_borrowing_rate(),);
+			assert_eq!(pallet::BorrowingRate::<Runtime>::get(NATIVE), get_default_borrowing_rate(),);
 
 			assert_ok!(Defi::update_borrowing_rate(
-				RuntimeOrigin::signed(get_authority_account()),
+				RuntimeOrigin::signed(AUTHORITY),
+				NATIVE,
 				FixedU128::from_inner(1)
 			),);
 
 			// Check borrowing rate after update
-			assert_eq!(pallet::BorrowingRate::<Runtime>::get(), FixedU128::from_inner(1),);
+			assert_eq!(pallet::BorrowingRate::<Runtime>::get(NATIVE), FixedU128::from_inner(1),);
 		});
 	}
 
@@ -637,6 +1026,7 @@ mod tests {
 			assert_err!(
 				Defi::update_collateral_factor(
 					RuntimeOrigin::signed(ALICE),
+					NATIVE,
 					FixedU128::from_inner(1)
 				),
 				Error::<Runtime>::UnauthorizedUserAction
@@ -652,44 +1042,2496 @@ mod tests {
 			run_to_block(1);
 
 			// Check collateral factor before update
-			assert_eq!(pallet::CollateralFactor::<Runtime>::get(), get_default_collateral_factor(),);
+			assert_eq!(
+				pallet::CollateralFactor::<Runtime>::get(NATIVE),
+				get_default_collateral_factor(),
+			);
 
 			assert_ok!(Defi::update_collateral_factor(
-				RuntimeOrigin::signed(get_authority_account()),
+				RuntimeOrigin::signed(AUTHORITY),
+				NATIVE,
 				FixedU128::from_inner(1)
 			),);
 
 			// Check collateral factor after update
-			assert_eq!(pallet::CollateralFactor::<Runtime>::get(), FixedU128::from_inner(1),);
+			assert_eq!(pallet::CollateralFactor::<Runtime>::get(NATIVE), FixedU128::from_inner(1),);
 		});
 	}
 
 	#[test]
-	fn check_liquidity_ok() {
+	fn update_collateral_factor_exceeds_one() {
 		let mut ext = ExtBuilder::default().build();
 		ext.execute_with(|| {
 			// Start test from block 1
 			run_to_block(1);
 
-			// Execute prerequired deposits and borrows
-			assert_ok!(Defi::deposit(RuntimeOrigin::signed(ALICE), balance!(10)));
-			assert_ok!(Defi::borrow(RuntimeOrigin::signed(ALICE), balance!(5)));
+			assert_err!(
+				Defi::update_collateral_factor(
+					RuntimeOrigin::signed(AUTHORITY),
+					NATIVE,
+					FixedU128::from_rational(101, 100)
+				),
+				Error::<Runtime>::InvalidCollateralFactor
+			);
+		});
+	}
 
-			// Change borrowing rate for faster liquidation
-			assert_ok!(Defi::update_borrowing_rate(
-				RuntimeOrigin::signed(get_authority_account()),
-				FixedU128::from_inner(balance!(1))
-			),);
+	#[test]
+	fn set_parameter_bounds_bad_origin() {
+		let mut ext = ExtBuilder::default().build();
+		ext.execute_with(|| {
+			// Start test from block 1
+			run_to_block(1);
 
-			// Run blockchain to block 100
-			run_to_block(100);
+			// Authority account can't change the bounds
+			assert_err!(
+				Defi::set_parameter_bounds(
+					RuntimeOrigin::signed(AUTHORITY),
+					MarketParameter::CollateralFactor,
+					None
+				),
+				DispatchError::BadOrigin
+			);
+		});
+	}
 
-			// Check if ALICE position was liquidateds
-			let alice_info = pallet::Accounts::<Runtime>::get(ALICE);
-			assert_eq!(alice_info.deposit_principal, balance!(0));
-			assert_eq!(alice_info.deposit_date, 0);
-			assert_eq!(alice_info.borrow_principal, balance!(0));
-			assert_eq!(alice_info.borrow_date, 0);
+	#[test]
+	fn set_parameter_bounds_invalid() {
+		let mut ext = ExtBuilder::default().build();
+		ext.execute_with(|| {
+			// Start test from block 1
+			run_to_block(1);
+
+			// Minimum above maximum
+			assert_err!(
+				Defi::set_parameter_bounds(
+					RuntimeOrigin::root(),
+					MarketParameter::DepositRate,
+					Some(ParameterBounds {
+						min: FixedU128::from_inner(2),
+						max: FixedU128::from_inner(1),
+						max_change: FixedU128::from_inner(1),
+					})
+				),
+				Error::<Runtime>::InvalidParameterBounds
+			);
+
+			// Collateral factor above one
+			assert_err!(
+				Defi::set_parameter_bounds(
+					RuntimeOrigin::root(),
+					MarketParameter::CollateralFactor,
+					Some(ParameterBounds {
+						min: FixedU128::from_inner(0),
+						max: FixedU128::from_rational(3, 2),
+						max_change: FixedU128::one(),
+					})
+				),
+				Error::<Runtime>::InvalidParameterBounds
+			);
 		});
 	}
+
+	#[test]
+	fn update_collateral_factor_within_bounds_ok() {
+		let mut ext = ExtBuilder::default().build();
+		ext.execute_with(|| {
+			// Start test from block 1
+			run_to_block(1);
+
+			let bounds = ParameterBounds {
+				min: FixedU128::from_rational(50, 100),
+				max: FixedU128::from_rational(80, 100),
+				max_change: FixedU128::from_rational(10, 100),
+			};
+			assert_ok!(Defi::set_parameter_bounds(
+				RuntimeOrigin::root(),
+				MarketParameter::CollateralFactor,
+				Some(bounds.clone())
+			));
+			System::assert_last_event(
+				pallet::Event::ParameterBoundsSet(MarketParameter::CollateralFactor, Some(bounds))
+					.into(),
+			);
+
+			// Above the maximum
+			assert_err!(
+				Defi::update_collateral_factor(
+					RuntimeOrigin::signed(AUTHORITY),
+					NATIVE,
+					FixedU128::from_rational(85, 100)
+				),
+				Error::<Runtime>::ParameterOutOfBounds
+			);
+
+			// Within bounds but too far from the current 75%
+			assert_err!(
+				Defi::update_collateral_factor(
+					RuntimeOrigin::signed(AUTHORITY),
+					NATIVE,
+					FixedU128::from_rational(60, 100)
+				),
+				Error::<Runtime>::ParameterChangeTooLarge
+			);
+
+			// New markets are listed within bounds
+			assert_err!(
+				Defi::create_market(
+					RuntimeOrigin::signed(AUTHORITY),
+					USDT,
+					get_default_deposit_rate(),
+					get_default_borrowing_rate(),
+					FixedU128::from_rational(90, 100)
+				),
+				Error::<Runtime>::ParameterOutOfBounds
+			);
+
+			assert_ok!(Defi::update_collateral_factor(
+				RuntimeOrigin::signed(AUTHORITY),
+				NATIVE,
+				FixedU128::from_rational(70, 100)
+			));
+			System::assert_last_event(
+				pallet::Event::ParameterChangeApplied(
+					NATIVE,
+					MarketParameter::CollateralFactor,
+					FixedU128::from_rational(70, 100),
+				)
+				.into(),
+			);
+			assert_eq!(
+				pallet::CollateralFactor::<Runtime>::get(NATIVE),
+				FixedU128::from_rational(70, 100)
+			);
+		});
+	}
+
+	#[test]
+	fn parameter_change_delay_ok() {
+		let mut ext = ExtBuilder::default().build();
+		ext.execute_with(|| {
+			// Start test from block 1
+			run_to_block(1);
+			ParameterChangeDelay::set(&10);
+
+			assert_ok!(Defi::update_borrowing_rate(
+				RuntimeOrigin::signed(AUTHORITY),
+				NATIVE,
+				FixedU128::from_inner(1)
+			));
+			System::assert_last_event(
+				pallet::Event::ParameterChangeScheduled(
+					NATIVE,
+					MarketParameter::BorrowingRate,
+					FixedU128::from_inner(1),
+					11,
+				)
+				.into(),
+			);
+
+			// Rate doesn't change before the delay passes
+			run_to_block(10);
+			assert_eq!(pallet::BorrowingRate::<Runtime>::get(NATIVE), get_default_borrowing_rate());
+
+			run_to_block(11);
+			System::assert_last_event(
+				pallet::Event::ParameterChangeApplied(
+					NATIVE,
+					MarketParameter::BorrowingRate,
+					FixedU128::from_inner(1),
+				)
+				.into(),
+			);
+			assert_eq!(pallet::BorrowingRate::<Runtime>::get(NATIVE), FixedU128::from_inner(1));
+			assert_eq!(
+				pallet::PendingParameterChanges::<Runtime>::get(
+					NATIVE,
+					MarketParameter::BorrowingRate
+				),
+				None
+			);
+
+			// Interest was accrued at the previous rate up to the change
+			assert_eq!(pallet::LastAccrued::<Runtime>::get(NATIVE), 11);
+		});
+	}
+
+	#[test]
+	fn cancel_parameter_change_ok() {
+		let mut ext = ExtBuilder::default().build();
+		ext.execute_with(|| {
+			// Start test from block 1
+			run_to_block(1);
+			ParameterChangeDelay::set(&10);
+
+			assert_err!(
+				Defi::cancel_parameter_change(
+					RuntimeOrigin::signed(AUTHORITY),
+					NATIVE,
+					MarketParameter::DepositRate
+				),
+				Error::<Runtime>::NoPendingParameterChange
+			);
+
+			assert_ok!(Defi::update_deposit_rate(
+				RuntimeOrigin::signed(AUTHORITY),
+				NATIVE,
+				FixedU128::from_inner(1)
+			));
+
+			// Only the admin origin or the authority account can cancel
+			assert_err!(
+				Defi::cancel_parameter_change(
+					RuntimeOrigin::signed(ALICE),
+					NATIVE,
+					MarketParameter::DepositRate
+				),
+				Error::<Runtime>::UnauthorizedUserAction
+			);
+
+			assert_ok!(Defi::cancel_parameter_change(
+				RuntimeOrigin::root(),
+				NATIVE,
+				MarketParameter::DepositRate
+			));
+			System::assert_last_event(
+				pallet::Event::ParameterChangeCancelled(NATIVE, MarketParameter::DepositRate)
+					.into(),
+			);
+
+			// Cancelled change is never applied
+			run_to_block(20);
+			assert_eq!(pallet::DepositRate::<Runtime>::get(NATIVE), get_default_deposit_rate());
+		});
+	}
+
+	#[test]
+	fn check_liquidity_ok() {
+		let mut ext = ExtBuilder::default().build();
+		ext.execute_with(|| {
+			// Start test from block 1
+			run_to_block(1);
+
+			// Execute prerequired deposits and borrows
+			assert_ok!(Defi::deposit(RuntimeOrigin::signed(ALICE), NATIVE, balance!(10)));
+			assert_ok!(Defi::borrow(RuntimeOrigin::signed(ALICE), NATIVE, balance!(5)));
+
+			// Change borrowing rate for faster liquidation
+			assert_ok!(Defi::update_borrowing_rate(
+				RuntimeOrigin::signed(AUTHORITY),
+				NATIVE,
+				FixedU128::from_rational(1, 100)
+			),);
+
+			// Run blockchain to block 50, the debt exceeds the borrowing power but not the
+			// collateral
+			run_to_block(50);
+
+			// Check if ALICE position was reported and kept for liquidators
+			System::assert_has_event(pallet::Event::AddressUndercollateralized(ALICE).into());
+
+			let alice_info = pallet::Accounts::<Runtime>::get(NATIVE, ALICE);
+			assert_eq!(Defi::receipt_balance(NATIVE, &ALICE), balance!(10));
+			assert_eq!(alice_info.borrow_principal, balance!(5));
+		});
+	}
+
+	#[test]
+	fn create_market_unauthorized_user_action() {
+		let mut ext = ExtBuilder::default().build();
+		ext.execute_with(|| {
+			// Start test from block 1
+			run_to_block(1);
+
+			assert_err!(
+				Defi::create_market(
+					RuntimeOrigin::signed(ALICE),
+					USDT,
+					get_default_deposit_rate(),
+					get_default_borrowing_rate(),
+					get_default_collateral_factor()
+				),
+				Error::<Runtime>::UnauthorizedUserAction
+			);
+		});
+	}
+
+	#[test]
+	fn create_market_already_exists() {
+		let mut ext = ExtBuilder::default().build();
+		ext.execute_with(|| {
+			// Start test from block 1
+			run_to_block(1);
+
+			// Native market is always listed
+			assert_err!(
+				Defi::create_market(
+					RuntimeOrigin::signed(AUTHORITY),
+					NATIVE,
+					get_default_deposit_rate(),
+					get_default_borrowing_rate(),
+					get_default_collateral_factor()
+				),
+				Error::<Runtime>::MarketAlreadyExists
+			);
+		});
+	}
+
+	#[test]
+	fn create_market_ok() {
+		let mut ext = ExtBuilder::default().build();
+		ext.execute_with(|| {
+			// Start test from block 1
+			run_to_block(1);
+
+			assert!(!pallet::Markets::<Runtime>::get(USDT));
+
+			assert_ok!(Defi::create_market(
+				RuntimeOrigin::signed(AUTHORITY),
+				USDT,
+				FixedU128::from_inner(1),
+				FixedU128::from_inner(2),
+				FixedU128::from_inner(3)
+			));
+
+			// Check market parameters after creation
+			assert!(pallet::Markets::<Runtime>::get(USDT));
+			assert_eq!(pallet::DepositRate::<Runtime>::get(USDT), FixedU128::from_inner(1));
+			assert_eq!(pallet::BorrowingRate::<Runtime>::get(USDT), FixedU128::from_inner(2));
+			assert_eq!(pallet::CollateralFactor::<Runtime>::get(USDT), FixedU128::from_inner(3));
+
+			// Check receipt token of the market
+			let receipt_asset_id = USDT + RECEIPT_ASSET_OFFSET;
+			assert_eq!(pallet::ReceiptAssets::<Runtime>::get(USDT), Some(receipt_asset_id));
+			assert!(pallet_assets::Asset::<Runtime>::contains_key(receipt_asset_id));
+			System::assert_has_event(
+				pallet::Event::ReceiptAssetCreated(USDT, receipt_asset_id).into(),
+			);
+
+			// Native market parameters are untouched
+			assert_eq!(pallet::DepositRate::<Runtime>::get(NATIVE), get_default_deposit_rate());
+		});
+	}
+
+	#[test]
+	fn create_market_receipt_asset_unavailable() {
+		let mut ext = ExtBuilder::default().build();
+		ext.execute_with(|| {
+			// Start test from block 1
+			run_to_block(1);
+
+			// BOB takes the receipt asset id of the market
+			assert_ok!(Assets::create(
+				RuntimeOrigin::signed(BOB),
+				(USDT + RECEIPT_ASSET_OFFSET).into(),
+				BOB,
+				1
+			));
+
+			assert_err!(
+				Defi::create_market(
+					RuntimeOrigin::signed(AUTHORITY),
+					USDT,
+					get_default_deposit_rate(),
+					get_default_borrowing_rate(),
+					get_default_collateral_factor()
+				),
+				Error::<Runtime>::ReceiptAssetUnavailable
+			);
+			assert!(!pallet::Markets::<Runtime>::get(USDT));
+		});
+	}
+
+	#[test]
+	fn create_market_too_many_markets() {
+		let mut ext = ExtBuilder::default().build();
+		ext.execute_with(|| {
+			// Start test from block 1
+			run_to_block(1);
+
+			// Native market and two more fill up the limit of three markets
+			create_market(USDT);
+			create_market(WBTC);
+
+			assert_err!(
+				Defi::create_market(
+					RuntimeOrigin::signed(AUTHORITY),
+					WBTC + 1,
+					get_default_deposit_rate(),
+					get_default_borrowing_rate(),
+					get_default_collateral_factor()
+				),
+				Error::<Runtime>::TooManyMarkets
+			);
+			assert!(!pallet::Markets::<Runtime>::get(WBTC + 1));
+		});
+	}
+
+	#[test]
+	fn deposit_market_not_found() {
+		let mut ext = ExtBuilder::default().build();
+		ext.execute_with(|| {
+			// Start test from block 1
+			run_to_block(1);
+
+			assert_err!(
+				Defi::deposit(RuntimeOrigin::signed(ALICE), USDT, balance!(10)),
+				Error::<Runtime>::MarketNotFound
+			);
+		});
+	}
+
+	#[test]
+	fn multi_asset_positions_are_separate() {
+		let mut ext = ExtBuilder::default().build();
+		ext.execute_with(|| {
+			// Start test from block 1
+			run_to_block(1);
+
+			create_market(USDT);
+
+			// Execute prerequired deposits
+			assert_ok!(Defi::deposit(RuntimeOrigin::signed(CHARLIE), USDT, balance!(50)));
+			assert_ok!(Defi::deposit(RuntimeOrigin::signed(ALICE), USDT, balance!(10)));
+			assert_ok!(Defi::deposit(RuntimeOrigin::signed(ALICE), NATIVE, balance!(20)));
+
+			// Check balances after deposits
+			assert_eq!(pallet_assets::Pallet::<Runtime>::balance(USDT, ALICE), balance!(90));
+			assert_eq!(pallet_assets::Pallet::<Runtime>::balance(USDT, pallet_id()), balance!(60));
+			assert_eq!(pallet_balances::Pallet::<Runtime>::free_balance(ALICE), balance!(80));
+			assert_eq!(
+				pallet_balances::Pallet::<Runtime>::free_balance(&pallet_id()),
+				balance!(20)
+			);
+
+			assert_ok!(Defi::borrow(RuntimeOrigin::signed(ALICE), USDT, balance!(5)));
+
+			// Check ALICE positions in both markets
+			let alice_usdt_info = pallet::Accounts::<Runtime>::get(USDT, ALICE);
+			assert_eq!(Defi::receipt_balance(USDT, &ALICE), balance!(10));
+			assert_eq!(alice_usdt_info.borrow_principal, balance!(5));
+
+			let alice_native_info = pallet::Accounts::<Runtime>::get(NATIVE, ALICE);
+			assert_eq!(Defi::receipt_balance(NATIVE, &ALICE), balance!(20));
+			assert_eq!(alice_native_info.borrow_principal, balance!(0));
+
+			// Check balances after borrow
+			assert_eq!(pallet_assets::Pallet::<Runtime>::balance(USDT, ALICE), balance!(95));
+			assert_eq!(pallet_assets::Pallet::<Runtime>::balance(USDT, pallet_id()), balance!(55));
+		});
+	}
+
+	#[test]
+	fn borrow_against_cross_asset_collateral_ok() {
+		let mut ext = ExtBuilder::default().build();
+		ext.execute_with(|| {
+			// Start test from block 1
+			run_to_block(1);
+
+			create_market(USDT);
+			MockPriceOracle::set_price(NATIVE, Some(FixedU128::from_u32(2)));
+
+			// Execute prerequired deposits
+			assert_ok!(Defi::deposit(RuntimeOrigin::signed(CHARLIE), USDT, balance!(50)));
+			assert_ok!(Defi::deposit(RuntimeOrigin::signed(ALICE), NATIVE, balance!(10)));
+
+			// Native collateral worth 20 USDT with 0.75 collateral factor
+			assert_eq!(Defi::get_allowed_borrowing_amount(USDT, ALICE), balance!(15));
+
+			assert_err!(
+				Defi::borrow(RuntimeOrigin::signed(ALICE), USDT, balance!(16)),
+				Error::<Runtime>::UnallowedBorrowAmount
+			);
+			assert_ok!(Defi::borrow(RuntimeOrigin::signed(ALICE), USDT, balance!(12)));
+
+			// Check balances after borrow
+			assert_eq!(pallet_assets::Pallet::<Runtime>::balance(USDT, ALICE), balance!(112));
+
+			// Debt in USDT reduces borrowing power in every market
+			assert_eq!(Defi::get_allowed_borrowing_amount(USDT, ALICE), balance!(3));
+			assert_eq!(Defi::get_allowed_borrowing_amount(NATIVE, ALICE), balance!(3) / 2);
+		});
+	}
+
+	#[test]
+	fn borrow_price_unavailable() {
+		let mut ext = ExtBuilder::default().build();
+		ext.execute_with(|| {
+			// Start test from block 1
+			run_to_block(1);
+
+			create_market(USDT);
+
+			// Execute prerequired deposits
+			assert_ok!(Defi::deposit(RuntimeOrigin::signed(CHARLIE), USDT, balance!(50)));
+			assert_ok!(Defi::deposit(RuntimeOrigin::signed(ALICE), NATIVE, balance!(10)));
+
+			MockPriceOracle::set_price(NATIVE, None);
+
+			assert_err!(
+				Defi::borrow(RuntimeOrigin::signed(ALICE), USDT, balance!(1)),
+				Error::<Runtime>::PriceUnavailable
+			);
+		});
+	}
+
+	#[test]
+	fn check_liquidity_cross_asset_ok() {
+		let mut ext = ExtBuilder::default().build();
+		ext.execute_with(|| {
+			// Start test from block 1
+			run_to_block(1);
+
+			create_market(USDT);
+
+			// Execute prerequired deposits and borrows
+			assert_ok!(Defi::deposit(RuntimeOrigin::signed(CHARLIE), USDT, balance!(50)));
+			assert_ok!(Defi::deposit(RuntimeOrigin::signed(ALICE), NATIVE, balance!(10)));
+			assert_ok!(Defi::borrow(RuntimeOrigin::signed(ALICE), USDT, balance!(7)));
+
+			// Position stays healthy while the price holds
+			run_to_block(2);
+			assert_eq!(pallet::Accounts::<Runtime>::get(USDT, ALICE).borrow_principal, balance!(7));
+
+			// Collateral price drops below the liquidation threshold
+			MockPriceOracle::set_price(NATIVE, Some(FixedU128::from_rational(8, 10)));
+			run_to_block(3);
+
+			// Check if ALICE was reported and her positions were kept in every market
+			System::assert_has_event(pallet::Event::AddressUndercollateralized(ALICE).into());
+			assert!(pallet::Accounts::<Runtime>::contains_key(USDT, ALICE));
+			assert!(pallet::Accounts::<Runtime>::contains_key(NATIVE, ALICE));
+		});
+	}
+
+	#[test]
+	fn fund_insurance_bad_origin() {
+		let mut ext = ExtBuilder::default().build();
+		ext.execute_with(|| {
+			// Start test from block 1
+			run_to_block(1);
+
+			assert_err!(
+				Defi::fund_insurance(RuntimeOrigin::signed(ALICE), NATIVE, balance!(1)),
+				DispatchError::BadOrigin
+			);
+		});
+	}
+
+	#[test]
+	fn fund_insurance_insufficient_reserves() {
+		let mut ext = ExtBuilder::default().build();
+		ext.execute_with(|| {
+			// Start test from block 1
+			run_to_block(1);
+
+			assert_ok!(Defi::add_reserves(RuntimeOrigin::signed(DAVE), NATIVE, balance!(1)));
+
+			assert_err!(
+				Defi::fund_insurance(RuntimeOrigin::signed(DAVE), NATIVE, balance!(2)),
+				Error::<Runtime>::InsufficientReserves
+			);
+		});
+	}
+
+	#[test]
+	fn fund_insurance_ok() {
+		let mut ext = ExtBuilder::default().build();
+		ext.execute_with(|| {
+			// Start test from block 1
+			run_to_block(1);
+
+			assert_ok!(Defi::add_reserves(RuntimeOrigin::signed(DAVE), NATIVE, balance!(3)));
+			assert_ok!(Defi::fund_insurance(RuntimeOrigin::signed(DAVE), NATIVE, balance!(2)));
+			System::assert_last_event(pallet::Event::InsuranceFunded(NATIVE, balance!(2)).into());
+
+			// Funds stay available in the market
+			assert_eq!(pallet::TotalReserves::<Runtime>::get(NATIVE), balance!(1));
+			assert_eq!(pallet::InsuranceFund::<Runtime>::get(NATIVE), balance!(2));
+			assert_eq!(Defi::get_cash(NATIVE), balance!(3));
+			assert_ok!(Defi::do_try_state());
+		});
+	}
+
+	#[test]
+	fn check_liquidity_bad_debt_ok() {
+		let mut ext = ExtBuilder::default().build();
+		ext.execute_with(|| {
+			// Start test from block 1
+			run_to_block(1);
+
+			create_market(USDT);
+
+			// Execute prerequired deposits and borrows
+			assert_ok!(Defi::deposit(RuntimeOrigin::signed(CHARLIE), NATIVE, balance!(50)));
+			assert_ok!(Defi::deposit(RuntimeOrigin::signed(ALICE), USDT, balance!(10)));
+			assert_ok!(Defi::borrow(RuntimeOrigin::signed(ALICE), NATIVE, balance!(7)));
+
+			// Fill the insurance fund of the native market
+			assert_ok!(Defi::add_reserves(RuntimeOrigin::signed(DAVE), NATIVE, balance!(1)));
+			assert_ok!(Defi::fund_insurance(RuntimeOrigin::signed(DAVE), NATIVE, balance!(1)));
+
+			// Collateral price drops so far that the debt exceeds the whole collateral
+			MockPriceOracle::set_price(USDT, Some(FixedU128::from_rational(1, 2)));
+			assert_eq!(Defi::is_insolvent(&ALICE), Ok(true));
+
+			Defi::check_liquidity(10);
+
+			// Remaining collateral is moved to reserves
+			System::assert_has_event(
+				pallet::Event::CollateralAbsorbed(ALICE, USDT, balance!(10)).into(),
+			);
+			assert_eq!(Defi::receipt_balance(USDT, &ALICE), balance!(0));
+			assert_eq!(pallet::TotalDeposits::<Runtime>::get(USDT), balance!(0));
+			assert_eq!(pallet::TotalReserves::<Runtime>::get(USDT), balance!(10));
+
+			// Debt is written off, covered by the insurance fund first and then by depositors
+			System::assert_has_event(
+				pallet::Event::BadDebtRecorded(ALICE, NATIVE, balance!(7)).into(),
+			);
+			System::assert_has_event(pallet::Event::BadDebtCovered(NATIVE, balance!(1)).into());
+			System::assert_has_event(pallet::Event::BadDebtSocialised(NATIVE, balance!(6)).into());
+			assert!(!pallet::Accounts::<Runtime>::contains_key(NATIVE, ALICE));
+			assert_eq!(pallet::BadDebt::<Runtime>::get(NATIVE), balance!(7));
+			assert_eq!(pallet::InsuranceFund::<Runtime>::get(NATIVE), balance!(0));
+			assert_eq!(pallet::TotalBorrows::<Runtime>::get(NATIVE), balance!(0));
+			assert_eq!(pallet::TotalDeposits::<Runtime>::get(NATIVE), balance!(44));
+			assert_eq!(Defi::get_lending_amount(NATIVE, CHARLIE), balance!(44));
+			assert_ok!(Defi::do_try_state());
+		});
+	}
+
+	#[test]
+	fn check_liquidity_bad_debt_exceeding_deposits_ok() {
+		let mut ext = ExtBuilder::default().build();
+		ext.execute_with(|| {
+			// Start test from block 1
+			run_to_block(1);
+
+			create_market(USDT);
+
+			// Execute prerequired deposits and borrows
+			assert_ok!(Defi::add_reserves(RuntimeOrigin::signed(DAVE), NATIVE, balance!(10)));
+			assert_ok!(Defi::deposit(RuntimeOrigin::signed(CHARLIE), NATIVE, balance!(20)));
+			assert_ok!(Defi::deposit(RuntimeOrigin::signed(ALICE), USDT, balance!(20)));
+			assert_ok!(Defi::borrow(RuntimeOrigin::signed(ALICE), NATIVE, balance!(12)));
+
+			// Depositors withdraw the funds left, which are partly reserves
+			assert_ok!(Defi::withdraw(RuntimeOrigin::signed(CHARLIE), NATIVE, balance!(15)));
+
+			// Collateral price drops so far that the debt exceeds the whole collateral
+			MockPriceOracle::set_price(USDT, Some(FixedU128::from_rational(1, 2)));
+			assert_eq!(Defi::is_insolvent(&ALICE), Ok(true));
+
+			Defi::check_liquidity(10);
+
+			// Deposits only cover part of the written off debt, the rest is kept uncovered
+			System::assert_has_event(
+				pallet::Event::BadDebtRecorded(ALICE, NATIVE, balance!(12)).into(),
+			);
+			System::assert_has_event(pallet::Event::BadDebtSocialised(NATIVE, balance!(5)).into());
+			System::assert_has_event(pallet::Event::BadDebtUncovered(NATIVE, balance!(7)).into());
+			assert_eq!(pallet::BadDebt::<Runtime>::get(NATIVE), balance!(12));
+			assert_eq!(pallet::UncoveredBadDebt::<Runtime>::get(NATIVE), balance!(7));
+			assert_eq!(pallet::TotalDeposits::<Runtime>::get(NATIVE), balance!(0));
+
+			// Market cash only counts the reserves the pallet still holds
+			assert_eq!(Defi::get_cash(NATIVE), balance!(3));
+			assert_ok!(Defi::do_try_state());
+		});
+	}
+
+	#[test]
+	fn check_liquidity_bad_debt_paused() {
+		let mut ext = ExtBuilder::default().build();
+		ext.execute_with(|| {
+			// Start test from block 1
+			run_to_block(1);
+
+			create_market(USDT);
+
+			// Execute prerequired deposits and borrows
+			assert_ok!(Defi::deposit(RuntimeOrigin::signed(CHARLIE), NATIVE, balance!(50)));
+			assert_ok!(Defi::deposit(RuntimeOrigin::signed(ALICE), USDT, balance!(10)));
+			assert_ok!(Defi::borrow(RuntimeOrigin::signed(ALICE), NATIVE, balance!(7)));
+
+			// Collateral price drops so far that the debt exceeds the whole collateral
+			MockPriceOracle::set_price(USDT, Some(FixedU128::from_rational(1, 2)));
+			assert_eq!(Defi::is_insolvent(&ALICE), Ok(true));
+
+			// Debt isn't written off while liquidations are paused, the account is only reported
+			assert_ok!(Defi::pause_operation(RuntimeOrigin::signed(CHARLIE), Operation::Liquidate));
+			Defi::check_liquidity(10);
+
+			System::assert_last_event(pallet::Event::AddressUndercollateralized(ALICE).into());
+			assert_eq!(Defi::receipt_balance(USDT, &ALICE), balance!(10));
+			assert_eq!(Defi::get_debt_amount(NATIVE, ALICE), balance!(7));
+			assert_eq!(pallet::BadDebt::<Runtime>::get(NATIVE), balance!(0));
+
+			// Debt is written off once liquidations are unpaused
+			assert_ok!(Defi::unpause_operation(
+				RuntimeOrigin::signed(CHARLIE),
+				Operation::Liquidate
+			));
+			Defi::check_liquidity(10);
+
+			System::assert_has_event(
+				pallet::Event::BadDebtRecorded(ALICE, NATIVE, balance!(7)).into(),
+			);
+			assert_eq!(pallet::BadDebt::<Runtime>::get(NATIVE), balance!(7));
+			assert_ok!(Defi::do_try_state());
+		});
+	}
+
+	#[test]
+	fn total_borrows_and_utilization_ok() {
+		let mut ext = ExtBuilder::default().build();
+		ext.execute_with(|| {
+			// Start test from block 1
+			run_to_block(1);
+
+			// Nothing is borrowed yet
+			assert_ok!(Defi::deposit(RuntimeOrigin::signed(CHARLIE), NATIVE, balance!(99)));
+			assert_ok!(Defi::deposit(RuntimeOrigin::signed(ALICE), NATIVE, balance!(10)));
+			assert_eq!(Defi::get_utilization(NATIVE), FixedU128::from_inner(0));
+
+			// Borrowed funds are tracked in the market total
+			assert_ok!(Defi::borrow(RuntimeOrigin::signed(ALICE), NATIVE, balance!(5)));
+			assert_eq!(pallet::TotalBorrows::<Runtime>::get(NATIVE), balance!(5));
+			assert_eq!(
+				Defi::get_utilization(NATIVE),
+				FixedU128::from_rational(balance!(5), balance!(109))
+			);
+
+			// Fixed rate model ignores utilization
+			assert_eq!(Defi::get_borrowing_rate(NATIVE), get_default_borrowing_rate());
+			assert_eq!(Defi::get_deposit_rate(NATIVE), get_default_deposit_rate());
+
+			// Repaid loan is removed from the market total
+			assert_ok!(Defi::repay(RuntimeOrigin::signed(ALICE), NATIVE, balance!(5)));
+			assert_eq!(pallet::TotalBorrows::<Runtime>::get(NATIVE), balance!(0));
+			assert_eq!(Defi::get_utilization(NATIVE), FixedU128::from_inner(0));
+		});
+	}
+
+	#[test]
+	fn rate_change_is_not_retroactive_ok() {
+		let mut ext = ExtBuilder::default().build();
+		ext.execute_with(|| {
+			// Start test from block 1
+			run_to_block(1);
+
+			// Execute prerequired deposits and borrows
+			assert_ok!(Defi::deposit(RuntimeOrigin::signed(ALICE), NATIVE, balance!(10)));
+			assert_ok!(Defi::borrow(RuntimeOrigin::signed(ALICE), NATIVE, balance!(5)));
+
+			// Run blockchain to block 11 and change borrowing rate
+			run_to_block(11);
+			let new_rate = FixedU128::from_rational(1, 1000);
+			assert_ok!(Defi::update_borrowing_rate(
+				RuntimeOrigin::signed(AUTHORITY),
+				NATIVE,
+				new_rate
+			));
+
+			// Interest up to block 11 is accrued at the previous rate
+			let index_before_update = get_index_with_accrued_interest(
+				11,
+				1,
+				FixedU128::one(),
+				get_pallet_borrowing_rate(),
+			);
+			assert_eq!(pallet::BorrowIndex::<Runtime>::get(NATIVE), index_before_update);
+			assert_eq!(pallet::LastAccrued::<Runtime>::get(NATIVE), 11);
+
+			// Run blockchain to block 21
+			run_to_block(21);
+
+			// Interest after block 11 is accrued at the new rate
+			let index = get_index_with_accrued_interest(21, 11, index_before_update, new_rate);
+			assert_eq!(
+				Defi::get_debt_amount(NATIVE, ALICE),
+				FixedU128::from_inner(balance!(5)).saturating_mul(index).into_inner()
+			);
+		});
+	}
+
+	#[test]
+	fn market_totals_are_tracked_ok() {
+		let mut ext = ExtBuilder::default().build();
+		ext.execute_with(|| {
+			// Start test from block 1
+			run_to_block(1);
+
+			// Execute prerequired deposits and borrows
+			assert_ok!(Defi::deposit(RuntimeOrigin::signed(CHARLIE), NATIVE, balance!(99)));
+			assert_ok!(Defi::deposit(RuntimeOrigin::signed(ALICE), NATIVE, balance!(10)));
+			assert_ok!(Defi::borrow(RuntimeOrigin::signed(ALICE), NATIVE, balance!(5)));
+
+			assert_eq!(pallet::TotalDeposits::<Runtime>::get(NATIVE), balance!(109));
+			assert_eq!(pallet::TotalBorrows::<Runtime>::get(NATIVE), balance!(5));
+			assert_eq!(Defi::get_cash(NATIVE), balance!(104));
+
+			// Execute repay and withdrawl
+			assert_ok!(Defi::repay(RuntimeOrigin::signed(ALICE), NATIVE, balance!(2)));
+			assert_ok!(Defi::withdraw(RuntimeOrigin::signed(CHARLIE), NATIVE, balance!(3)));
+
+			assert_eq!(pallet::TotalDeposits::<Runtime>::get(NATIVE), balance!(106));
+			assert_eq!(pallet::TotalBorrows::<Runtime>::get(NATIVE), balance!(3));
+			assert_eq!(Defi::get_cash(NATIVE), balance!(103));
+			assert_ok!(Defi::do_try_state());
+
+			// Funds sent directly to the pallet don't change the market state
+			let utilization = Defi::get_utilization(NATIVE);
+			assert_ok!(pallet_balances::Pallet::<Runtime>::transfer_allow_death(
+				RuntimeOrigin::signed(BOB),
+				pallet_id(),
+				balance!(50)
+			));
+			assert_eq!(Defi::get_cash(NATIVE), balance!(103));
+			assert_eq!(Defi::get_utilization(NATIVE), utilization);
+			assert_ok!(Defi::do_try_state());
+		});
+	}
+
+	#[test]
+	fn reserves_accrue_reserve_factor_share_ok() {
+		let mut ext = ExtBuilder::default().build();
+		ext.execute_with(|| {
+			// Start test from block 1
+			run_to_block(1);
+
+			// Depositors earn nothing so reserves are the only share of interest kept
+			assert_ok!(Defi::update_deposit_rate(
+				RuntimeOrigin::signed(AUTHORITY),
+				NATIVE,
+				FixedU128::from_inner(0)
+			));
+			assert_ok!(Defi::update_reserve_factor(
+				RuntimeOrigin::signed(AUTHORITY),
+				NATIVE,
+				FixedU128::from_rational(10, 100)
+			));
+
+			// Execute prerequired deposits and borrows
+			assert_ok!(Defi::deposit(RuntimeOrigin::signed(ALICE), NATIVE, balance!(10)));
+			assert_ok!(Defi::borrow(RuntimeOrigin::signed(ALICE), NATIVE, balance!(5)));
+
+			// Run blockchain to block 11
+			run_to_block(11);
+			Defi::accrue_interest(NATIVE);
+
+			let interest = get_debt_amount(ALICE) - balance!(5);
+			assert!(interest > 0);
+			assert_eq!(pallet::TotalDeposits::<Runtime>::get(NATIVE), balance!(10));
+			assert_eq!(pallet::TotalBorrows::<Runtime>::get(NATIVE), balance!(5) + interest);
+			assert_eq!(
+				pallet::TotalReserves::<Runtime>::get(NATIVE),
+				FixedU128::from_inner(interest)
+					.saturating_mul(FixedU128::from_rational(10, 100))
+					.into_inner()
+			);
+			assert_ok!(Defi::do_try_state());
+		});
+	}
+
+	#[test]
+	fn update_reserve_factor_unauthorized_user_action() {
+		let mut ext = ExtBuilder::default().build();
+		ext.execute_with(|| {
+			// Start test from block 1
+			run_to_block(1);
+
+			assert_err!(
+				Defi::update_reserve_factor(
+					RuntimeOrigin::signed(ALICE),
+					NATIVE,
+					FixedU128::from_rational(10, 100)
+				),
+				Error::<Runtime>::UnauthorizedUserAction
+			);
+		});
+	}
+
+	#[test]
+	fn update_reserve_factor_invalid_reserve_factor() {
+		let mut ext = ExtBuilder::default().build();
+		ext.execute_with(|| {
+			// Start test from block 1
+			run_to_block(1);
+
+			assert_err!(
+				Defi::update_reserve_factor(
+					RuntimeOrigin::signed(AUTHORITY),
+					NATIVE,
+					FixedU128::from_rational(101, 100)
+				),
+				Error::<Runtime>::InvalidReserveFactor
+			);
+		});
+	}
+
+	#[test]
+	fn update_reserve_factor_ok() {
+		let mut ext = ExtBuilder::default().build();
+		ext.execute_with(|| {
+			// Start test from block 1
+			run_to_block(1);
+
+			let new_factor = FixedU128::from_rational(10, 100);
+			assert_ok!(Defi::update_reserve_factor(
+				RuntimeOrigin::signed(AUTHORITY),
+				NATIVE,
+				new_factor
+			));
+
+			assert_eq!(pallet::ReserveFactor::<Runtime>::get(NATIVE), new_factor);
+			System::assert_last_event(
+				pallet::Event::ReserveFactorUpdated(NATIVE, new_factor).into(),
+			);
+		});
+	}
+
+	#[test]
+	fn add_reserves_bad_origin() {
+		let mut ext = ExtBuilder::default().build();
+		ext.execute_with(|| {
+			// Start test from block 1
+			run_to_block(1);
+
+			assert_err!(
+				Defi::add_reserves(RuntimeOrigin::signed(ALICE), NATIVE, balance!(10)),
+				DispatchError::BadOrigin
+			);
+		});
+	}
+
+	#[test]
+	fn add_and_reduce_reserves_ok() {
+		let mut ext = ExtBuilder::default().build();
+		ext.execute_with(|| {
+			// Start test from block 1
+			run_to_block(1);
+
+			assert_ok!(Defi::add_reserves(RuntimeOrigin::signed(DAVE), NATIVE, balance!(10)));
+
+			// Check balances and reserves after adding reserves
+			assert_eq!(pallet_balances::Pallet::<Runtime>::free_balance(DAVE), balance!(90));
+			assert_eq!(
+				pallet_balances::Pallet::<Runtime>::free_balance(&pallet_id()),
+				balance!(10)
+			);
+			assert_eq!(pallet::TotalReserves::<Runtime>::get(NATIVE), balance!(10));
+			assert_eq!(Defi::get_cash(NATIVE), balance!(10));
+
+			assert_err!(
+				Defi::reduce_reserves(RuntimeOrigin::signed(DAVE), NATIVE, BOB, balance!(11)),
+				Error::<Runtime>::InsufficientReserves
+			);
+
+			assert_ok!(Defi::reduce_reserves(
+				RuntimeOrigin::signed(DAVE),
+				NATIVE,
+				BOB,
+				balance!(4)
+			));
+
+			// Check balances and reserves after reducing reserves
+			assert_eq!(pallet_balances::Pallet::<Runtime>::free_balance(BOB), balance!(104));
+			assert_eq!(pallet_balances::Pallet::<Runtime>::free_balance(&pallet_id()), balance!(6));
+			assert_eq!(pallet::TotalReserves::<Runtime>::get(NATIVE), balance!(6));
+			System::assert_last_event(
+				pallet::Event::ReservesReduced(BOB, NATIVE, balance!(4)).into(),
+			);
+			assert_ok!(Defi::do_try_state());
+		});
+	}
+
+	#[test]
+	fn set_caps_unauthorized_user_action() {
+		let mut ext = ExtBuilder::default().build();
+		ext.execute_with(|| {
+			// Start test from block 1
+			run_to_block(1);
+
+			assert_err!(
+				Defi::set_caps(RuntimeOrigin::signed(ALICE), NATIVE, Some(balance!(1)), None),
+				Error::<Runtime>::UnauthorizedUserAction
+			);
+			assert_err!(
+				Defi::set_global_caps(RuntimeOrigin::signed(ALICE), Some(balance!(1)), None),
+				Error::<Runtime>::UnauthorizedUserAction
+			);
+			assert_err!(
+				Defi::set_liquidity_buffer(
+					RuntimeOrigin::signed(ALICE),
+					NATIVE,
+					FixedU128::from_rational(10, 100)
+				),
+				Error::<Runtime>::UnauthorizedUserAction
+			);
+		});
+	}
+
+	#[test]
+	fn set_caps_market_not_found() {
+		let mut ext = ExtBuilder::default().build();
+		ext.execute_with(|| {
+			// Start test from block 1
+			run_to_block(1);
+
+			assert_err!(
+				Defi::set_caps(RuntimeOrigin::signed(AUTHORITY), USDT, Some(balance!(1)), None),
+				Error::<Runtime>::MarketNotFound
+			);
+		});
+	}
+
+	#[test]
+	fn supply_cap_exceeded() {
+		let mut ext = ExtBuilder::default().build();
+		ext.execute_with(|| {
+			// Start test from block 1
+			run_to_block(1);
+
+			assert_ok!(Defi::set_caps(
+				RuntimeOrigin::signed(AUTHORITY),
+				NATIVE,
+				Some(balance!(15)),
+				None
+			));
+			System::assert_last_event(
+				pallet::Event::CapsSet(NATIVE, Some(balance!(15)), None).into(),
+			);
+
+			assert_ok!(Defi::deposit(RuntimeOrigin::signed(ALICE), NATIVE, balance!(10)));
+			assert_err!(
+				Defi::deposit(RuntimeOrigin::signed(BOB), NATIVE, balance!(6)),
+				Error::<Runtime>::SupplyCapExceeded
+			);
+			assert_ok!(Defi::deposit(RuntimeOrigin::signed(BOB), NATIVE, balance!(5)));
+		});
+	}
+
+	#[test]
+	fn borrow_cap_exceeded() {
+		let mut ext = ExtBuilder::default().build();
+		ext.execute_with(|| {
+			// Start test from block 1
+			run_to_block(1);
+
+			assert_ok!(Defi::set_caps(
+				RuntimeOrigin::signed(AUTHORITY),
+				NATIVE,
+				None,
+				Some(balance!(3))
+			));
+
+			assert_ok!(Defi::deposit(RuntimeOrigin::signed(ALICE), NATIVE, balance!(10)));
+			assert_err!(
+				Defi::borrow(RuntimeOrigin::signed(ALICE), NATIVE, balance!(4)),
+				Error::<Runtime>::BorrowCapExceeded
+			);
+			assert_ok!(Defi::borrow(RuntimeOrigin::signed(ALICE), NATIVE, balance!(3)));
+		});
+	}
+
+	#[test]
+	fn global_caps_exceeded() {
+		let mut ext = ExtBuilder::default().build();
+		ext.execute_with(|| {
+			// Start test from block 1
+			run_to_block(1);
+
+			create_market(USDT);
+
+			assert_ok!(Defi::set_global_caps(
+				RuntimeOrigin::signed(AUTHORITY),
+				Some(balance!(15)),
+				Some(balance!(4))
+			));
+			System::assert_last_event(
+				pallet::Event::GlobalCapsSet(Some(balance!(15)), Some(balance!(4))).into(),
+			);
+
+			// Deposits of both markets count towards the cap
+			assert_ok!(Defi::deposit(RuntimeOrigin::signed(ALICE), NATIVE, balance!(10)));
+			assert_err!(
+				Defi::deposit(RuntimeOrigin::signed(BOB), USDT, balance!(6)),
+				Error::<Runtime>::GlobalSupplyCapExceeded
+			);
+			assert_ok!(Defi::deposit(RuntimeOrigin::signed(BOB), USDT, balance!(5)));
+
+			assert_err!(
+				Defi::borrow(RuntimeOrigin::signed(ALICE), USDT, balance!(5)),
+				Error::<Runtime>::GlobalBorrowCapExceeded
+			);
+			assert_ok!(Defi::borrow(RuntimeOrigin::signed(ALICE), USDT, balance!(4)));
+		});
+	}
+
+	#[test]
+	fn liquidity_buffer_breached() {
+		let mut ext = ExtBuilder::default().build();
+		ext.execute_with(|| {
+			// Start test from block 1
+			run_to_block(1);
+
+			assert_err!(
+				Defi::set_liquidity_buffer(
+					RuntimeOrigin::signed(AUTHORITY),
+					NATIVE,
+					FixedU128::from_rational(101, 100)
+				),
+				Error::<Runtime>::InvalidLiquidityBuffer
+			);
+
+			assert_ok!(Defi::set_liquidity_buffer(
+				RuntimeOrigin::signed(AUTHORITY),
+				NATIVE,
+				FixedU128::from_rational(50, 100)
+			));
+			System::assert_last_event(
+				pallet::Event::LiquidityBufferSet(NATIVE, FixedU128::from_rational(50, 100)).into(),
+			);
+
+			// Half of the 10 deposited stays in the pool
+			assert_ok!(Defi::deposit(RuntimeOrigin::signed(ALICE), NATIVE, balance!(10)));
+			assert_err!(
+				Defi::borrow(RuntimeOrigin::signed(ALICE), NATIVE, balance!(6)),
+				Error::<Runtime>::LiquidityBufferBreached
+			);
+			assert_ok!(Defi::borrow(RuntimeOrigin::signed(ALICE), NATIVE, balance!(5)));
+
+			// Withdrawals can use the buffer
+			assert_ok!(Defi::withdraw(RuntimeOrigin::signed(ALICE), NATIVE, balance!(1)));
+		});
+	}
+
+	#[test]
+	fn liquidity_buffer_excludes_reserves() {
+		let mut ext = ExtBuilder::default().build();
+		ext.execute_with(|| {
+			// Start test from block 1
+			run_to_block(1);
+
+			assert_ok!(Defi::set_liquidity_buffer(
+				RuntimeOrigin::signed(AUTHORITY),
+				NATIVE,
+				FixedU128::from_rational(50, 100)
+			));
+
+			// Reserves add to the cash of the market but aren't lent out
+			assert_ok!(Defi::add_reserves(RuntimeOrigin::signed(DAVE), NATIVE, balance!(10)));
+			assert_ok!(Defi::deposit(RuntimeOrigin::signed(ALICE), NATIVE, balance!(10)));
+			assert_eq!(Defi::get_cash(NATIVE), balance!(20));
+			assert_err!(
+				Defi::borrow(RuntimeOrigin::signed(ALICE), NATIVE, balance!(6)),
+				Error::<Runtime>::LiquidityBufferBreached
+			);
+			assert_ok!(Defi::borrow(RuntimeOrigin::signed(ALICE), NATIVE, balance!(5)));
+			assert_ok!(Defi::do_try_state());
+		});
+	}
+
+	/// Let ALICE borrow 7 USDT against 10 NATIVE and drop the NATIVE price by half
+	fn setup_undercollateralized_alice() {
+		create_market(USDT);
+
+		assert_ok!(Defi::deposit(RuntimeOrigin::signed(CHARLIE), USDT, balance!(50)));
+		assert_ok!(Defi::deposit(RuntimeOrigin::signed(ALICE), NATIVE, balance!(10)));
+		assert_ok!(Defi::borrow(RuntimeOrigin::signed(ALICE), USDT, balance!(7)));
+
+		MockPriceOracle::set_price(NATIVE, Some(FixedU128::from_rational(1, 2)));
+	}
+
+	#[test]
+	fn liquidate_address_not_liquidatable() {
+		let mut ext = ExtBuilder::default().build();
+		ext.execute_with(|| {
+			// Start test from block 1
+			run_to_block(1);
+
+			setup_undercollateralized_alice();
+			MockPriceOracle::set_price(NATIVE, Some(FixedU128::one()));
+
+			assert_err!(
+				Defi::liquidate(RuntimeOrigin::signed(BOB), ALICE, USDT, NATIVE, balance!(1)),
+				Error::<Runtime>::AddressNotLiquidatable
+			);
+		});
+	}
+
+	#[test]
+	fn liquidate_debt_equal_to_borrowing_power() {
+		let mut ext = ExtBuilder::default().build();
+		ext.execute_with(|| {
+			// Start test from block 1
+			run_to_block(1);
+
+			create_market(USDT);
+
+			// Borrow up to the whole borrowing power of 10 NATIVE at a collateral factor of 75%
+			assert_ok!(Defi::deposit(RuntimeOrigin::signed(CHARLIE), USDT, balance!(50)));
+			assert_ok!(Defi::deposit(RuntimeOrigin::signed(ALICE), NATIVE, balance!(10)));
+			assert_ok!(Defi::borrow(RuntimeOrigin::signed(ALICE), USDT, balance!(75) / 10));
+
+			// A position the borrow was allowed for can't be liquidated
+			assert_eq!(Defi::get_health_factor(&ALICE), Ok(Some(FixedU128::one().into_inner())));
+			assert_eq!(Defi::is_liquidatable(&ALICE), Ok(false));
+			assert_err!(
+				Defi::liquidate(RuntimeOrigin::signed(BOB), ALICE, USDT, NATIVE, balance!(1)),
+				Error::<Runtime>::AddressNotLiquidatable
+			);
+		});
+	}
+
+	#[test]
+	fn liquidate_self_liquidation() {
+		let mut ext = ExtBuilder::default().build();
+		ext.execute_with(|| {
+			// Start test from block 1
+			run_to_block(1);
+
+			setup_undercollateralized_alice();
+
+			assert_err!(
+				Defi::liquidate(RuntimeOrigin::signed(ALICE), ALICE, USDT, NATIVE, balance!(1)),
+				Error::<Runtime>::SelfLiquidation
+			);
+		});
+	}
+
+	#[test]
+	fn liquidate_excessive_repay_amount() {
+		let mut ext = ExtBuilder::default().build();
+		ext.execute_with(|| {
+			// Start test from block 1
+			run_to_block(1);
+
+			setup_undercollateralized_alice();
+
+			// Close factor allows repaying at most half of the 7 USDT debt
+			assert_err!(
+				Defi::liquidate(RuntimeOrigin::signed(BOB), ALICE, USDT, NATIVE, balance!(4)),
+				Error::<Runtime>::ExcessiveRepayAmount
+			);
+		});
+	}
+
+	#[test]
+	fn liquidate_insufficient_collateral() {
+		let mut ext = ExtBuilder::default().build();
+		ext.execute_with(|| {
+			// Start test from block 1
+			run_to_block(1);
+
+			setup_undercollateralized_alice();
+
+			// Collateral price drops so far that 3 USDT are worth more than the whole deposit
+			MockPriceOracle::set_price(NATIVE, Some(FixedU128::from_rational(1, 10)));
+
+			assert_err!(
+				Defi::liquidate(RuntimeOrigin::signed(BOB), ALICE, USDT, NATIVE, balance!(3)),
+				Error::<Runtime>::InsufficientCollateral
+			);
+		});
+	}
+
+	#[test]
+	fn liquidate_ok() {
+		let mut ext = ExtBuilder::default().build();
+		ext.execute_with(|| {
+			// Start test from block 1
+			run_to_block(1);
+
+			setup_undercollateralized_alice();
+
+			assert_ok!(Defi::liquidate(
+				RuntimeOrigin::signed(BOB),
+				ALICE,
+				USDT,
+				NATIVE,
+				balance!(3)
+			));
+
+			// 3 USDT worth 0.3 plus 10% incentive seize 0.33 / 0.5 = 6.6 NATIVE
+			let seized = balance!(66) / 10;
+			System::assert_last_event(
+				pallet::Event::AddressLiquidated(BOB, ALICE, USDT, balance!(3), NATIVE, seized)
+					.into(),
+			);
+
+			// Check balances after liquidation
+			assert_eq!(Assets::balance(USDT, BOB), balance!(97));
+
+			// Check positions after liquidation
+			assert_eq!(pallet::Accounts::<Runtime>::get(USDT, ALICE).borrow_principal, balance!(4));
+			assert_eq!(Defi::receipt_balance(NATIVE, &ALICE), balance!(10) - seized);
+			assert_eq!(Defi::receipt_balance(NATIVE, &BOB), seized);
+
+			// Check market totals after liquidation
+			assert_eq!(pallet::TotalBorrows::<Runtime>::get(USDT), balance!(4));
+			assert_eq!(pallet::TotalDeposits::<Runtime>::get(NATIVE), balance!(10));
+			assert_ok!(Defi::do_try_state());
+		});
+	}
+
+	#[test]
+	fn liquidate_held_collateral_ok() {
+		let mut ext = ExtBuilder::default().build();
+		ext.execute_with(|| {
+			// Start test from block 1
+			run_to_block(1);
+
+			// ALICE deposits 4 NATIVE and holds 6 NATIVE as collateral only
+			create_market(USDT);
+			assert_ok!(Defi::deposit(RuntimeOrigin::signed(CHARLIE), USDT, balance!(50)));
+			assert_ok!(Defi::deposit(RuntimeOrigin::signed(ALICE), NATIVE, balance!(4)));
+			assert_ok!(Defi::deposit_collateral(RuntimeOrigin::signed(ALICE), NATIVE, balance!(6)));
+			assert_ok!(Defi::borrow(RuntimeOrigin::signed(ALICE), USDT, balance!(7)));
+
+			MockPriceOracle::set_price(NATIVE, Some(FixedU128::from_rational(1, 2)));
+
+			assert_ok!(Defi::liquidate(
+				RuntimeOrigin::signed(BOB),
+				ALICE,
+				USDT,
+				NATIVE,
+				balance!(3)
+			));
+
+			// 6.6 NATIVE are seized, the whole deposit and 2.6 of the held collateral
+			let seized = balance!(66) / 10;
+			System::assert_last_event(
+				pallet::Event::AddressLiquidated(BOB, ALICE, USDT, balance!(3), NATIVE, seized)
+					.into(),
+			);
+
+			// Seized deposit stays deposited and seized held collateral is paid out
+			assert_eq!(Defi::receipt_balance(NATIVE, &ALICE), 0);
+			assert_eq!(Defi::receipt_balance(NATIVE, &BOB), balance!(4));
+			assert_eq!(Defi::held_collateral(NATIVE, &ALICE), balance!(6) - (seized - balance!(4)));
+			assert_eq!(Balances::free_balance(BOB), balance!(100) + seized - balance!(4));
+			assert_eq!(Balances::free_balance(ALICE), balance!(90));
+
+			// Check market totals after liquidation
+			assert_eq!(pallet::TotalBorrows::<Runtime>::get(USDT), balance!(4));
+			assert_eq!(pallet::TotalDeposits::<Runtime>::get(NATIVE), balance!(4));
+			assert_ok!(Defi::do_try_state());
+		});
+	}
+
+	#[test]
+	fn deposit_collateral_ok() {
+		let mut ext = ExtBuilder::default().build();
+		ext.execute_with(|| {
+			// Start test from block 1
+			run_to_block(1);
+
+			create_market(USDT);
+			assert_ok!(Defi::deposit(RuntimeOrigin::signed(CHARLIE), USDT, balance!(50)));
+
+			assert_ok!(Defi::deposit_collateral(
+				RuntimeOrigin::signed(ALICE),
+				NATIVE,
+				balance!(10)
+			));
+			System::assert_last_event(
+				pallet::Event::CollateralDeposited(ALICE, NATIVE, balance!(10)).into(),
+			);
+
+			// Funds stay in the account and aren't lent out
+			assert_eq!(Balances::free_balance(ALICE), balance!(90));
+			assert_eq!(Balances::total_balance(&ALICE), balance!(100));
+			assert_eq!(Defi::held_collateral(NATIVE, &ALICE), balance!(10));
+			assert_eq!(Defi::receipt_balance(NATIVE, &ALICE), 0);
+			assert_eq!(pallet::TotalDeposits::<Runtime>::get(NATIVE), 0);
+
+			// Held collateral counts towards the borrowing power of 10 * 0.75
+			assert_err!(
+				Defi::borrow(RuntimeOrigin::signed(ALICE), USDT, balance!(8)),
+				Error::<Runtime>::UnallowedBorrowAmount
+			);
+			assert_ok!(Defi::borrow(RuntimeOrigin::signed(ALICE), USDT, balance!(7)));
+			assert_ok!(Defi::do_try_state());
+		});
+	}
+
+	#[test]
+	fn deposit_collateral_unsupported_market() {
+		let mut ext = ExtBuilder::default().build();
+		ext.execute_with(|| {
+			// Start test from block 1
+			run_to_block(1);
+
+			create_market(USDT);
+
+			assert_err!(
+				Defi::deposit_collateral(RuntimeOrigin::signed(ALICE), USDT, balance!(10)),
+				Error::<Runtime>::CollateralOnlyUnsupported
+			);
+			assert_err!(
+				Defi::deposit_collateral(RuntimeOrigin::signed(ALICE), NATIVE, balance!(101)),
+				Error::<Runtime>::InsufficientBalance
+			);
+		});
+	}
+
+	#[test]
+	fn withdraw_collateral_ok() {
+		let mut ext = ExtBuilder::default().build();
+		ext.execute_with(|| {
+			// Start test from block 1
+			run_to_block(1);
+
+			create_market(USDT);
+			assert_ok!(Defi::deposit(RuntimeOrigin::signed(CHARLIE), USDT, balance!(50)));
+			assert_ok!(Defi::deposit_collateral(
+				RuntimeOrigin::signed(ALICE),
+				NATIVE,
+				balance!(10)
+			));
+			assert_ok!(Defi::borrow(RuntimeOrigin::signed(ALICE), USDT, balance!(7)));
+
+			assert_err!(
+				Defi::withdraw_collateral(RuntimeOrigin::signed(ALICE), NATIVE, balance!(11)),
+				Error::<Runtime>::UserHasNotEnoughFunds
+			);
+
+			// 9 NATIVE left would give a borrowing power of 6.75 against 7 USDT debt
+			assert_err!(
+				Defi::withdraw_collateral(RuntimeOrigin::signed(ALICE), NATIVE, balance!(1)),
+				Error::<Runtime>::UnallowedWithdrawAmount
+			);
+
+			assert_ok!(Defi::repay(RuntimeOrigin::signed(ALICE), USDT, balance!(7)));
+			assert_ok!(Defi::withdraw_collateral(
+				RuntimeOrigin::signed(ALICE),
+				NATIVE,
+				balance!(10)
+			));
+			System::assert_last_event(
+				pallet::Event::CollateralWithdrawn(ALICE, NATIVE, balance!(10)).into(),
+			);
+
+			assert_eq!(Balances::free_balance(ALICE), balance!(100));
+			assert_eq!(Defi::held_collateral(NATIVE, &ALICE), 0);
+			assert_err!(
+				Defi::withdraw_collateral(RuntimeOrigin::signed(ALICE), NATIVE, balance!(1)),
+				Error::<Runtime>::NoFundsDeposited
+			);
+		});
+	}
+
+	#[test]
+	fn get_health_factor_ok() {
+		let mut ext = ExtBuilder::default().build();
+		ext.execute_with(|| {
+			// Start test from block 1
+			run_to_block(1);
+
+			setup_undercollateralized_alice();
+
+			// No health factor without debt
+			assert_eq!(Defi::get_health_factor(&CHARLIE), Ok(None));
+
+			// Borrowing power 10 * 0.5 * 0.75 against 7 USDT debt
+			assert_eq!(
+				Defi::get_health_factor(&ALICE),
+				Ok(Some(FixedU128::from_rational(375, 700).into_inner()))
+			);
+
+			// No health factor without price
+			MockPriceOracle::set_price(NATIVE, None);
+			assert_err!(Defi::get_health_factor(&ALICE), Error::<Runtime>::PriceUnavailable);
+		});
+	}
+
+	#[test]
+	fn check_liquidity_is_paginated_ok() {
+		let mut ext = ExtBuilder::default().build();
+		ext.execute_with(|| {
+			// Start test from block 1
+			run_to_block(1);
+
+			// Open four positions while at most three are checked per block
+			for user in [ALICE, BOB, CHARLIE, DAVE] {
+				assert_ok!(Defi::deposit(RuntimeOrigin::signed(user), NATIVE, balance!(10)));
+			}
+
+			// First block checks three positions and remembers where it stopped
+			assert_eq!(Defi::on_idle(1, Weight::MAX), <() as WeightInfo>::check_liquidity(3, 3));
+			assert!(pallet::LiquidationCursor::<Runtime>::get().is_some());
+
+			// Next block resumes with the remaining position and starts over afterwards
+			assert_eq!(Defi::on_idle(2, Weight::MAX), <() as WeightInfo>::check_liquidity(1, 3));
+			assert!(pallet::LiquidationCursor::<Runtime>::get().is_none());
+
+			// Nothing is checked without leftover weight
+			assert_eq!(Defi::on_idle(3, Weight::zero()), Weight::zero());
+		});
+	}
+
+	/// Sign a liquidation of Alice's USDT debt against her NATIVE deposit found at block 1, the
+	/// first one of the liquidator
+	fn signed_liquidation(
+		liquidator: AccountId,
+		repay_amount: Balance,
+	) -> (pallet::LiquidationPayloadOf<Runtime>, TestSignature) {
+		let payload = LiquidationPayload {
+			public: UintAuthorityId(liquidator),
+			borrower: ALICE,
+			debt_asset_id: USDT,
+			collateral_asset_id: NATIVE,
+			repay_amount,
+			block_number: 1,
+			nonce: 0,
+		};
+		let signature = TestSignature(liquidator, payload.encode());
+
+		(payload, signature)
+	}
+
+	#[test]
+	fn offchain_worker_without_key_submits_nothing() {
+		let (mut ext, pool_state) = ExtBuilder::default().build_offchain();
+		ext.execute_with(|| {
+			// Start test from block 1
+			run_to_block(1);
+
+			setup_undercollateralized_alice();
+
+			Defi::offchain_worker(1);
+
+			assert!(pool_state.read().transactions.is_empty());
+		});
+	}
+
+	#[test]
+	fn offchain_worker_submits_liquidation_ok() {
+		let (mut ext, pool_state) = ExtBuilder::default().build_offchain();
+		ext.execute_with(|| {
+			// Start test from block 1
+			run_to_block(1);
+
+			UintAuthorityId::set_all_keys(vec![BOB]);
+			setup_undercollateralized_alice();
+
+			Defi::offchain_worker(1);
+
+			// Check the submitted transaction
+			let tx = pool_state.write().transactions.pop().unwrap();
+			assert!(pool_state.read().transactions.is_empty());
+			let tx = Extrinsic::decode(&mut &*tx).unwrap();
+			assert_eq!(tx.signature, None);
+
+			// Half of 7 USDT debt is repaid against the NATIVE deposit
+			let (payload, signature) = match tx.call {
+				RuntimeCall::Defi(pallet::Call::liquidate_unsigned { payload, signature }) =>
+					(payload, signature),
+				_ => panic!("unexpected call"),
+			};
+			assert_eq!(
+				(payload.clone(), signature.clone()),
+				signed_liquidation(BOB, balance!(35) / 10)
+			);
+
+			// Check that the transaction is accepted and executed
+			let call = pallet::Call::liquidate_unsigned {
+				payload: payload.clone(),
+				signature: signature.clone(),
+			};
+			assert_ok!(Defi::validate_unsigned(TransactionSource::Local, &call));
+			assert_ok!(Defi::liquidate_unsigned(RuntimeOrigin::none(), payload, signature));
+
+			// 3.5 USDT worth 0.35 plus 10% incentive seize 0.385 / 0.5 = 7.7 NATIVE
+			System::assert_last_event(
+				pallet::Event::AddressLiquidated(
+					BOB,
+					ALICE,
+					USDT,
+					balance!(35) / 10,
+					NATIVE,
+					balance!(77) / 10,
+				)
+				.into(),
+			);
+			assert_ok!(Defi::do_try_state());
+		});
+	}
+
+	#[test]
+	fn offchain_worker_scales_repay_to_collateral_ok() {
+		let (mut ext, pool_state) = ExtBuilder::default().build_offchain();
+		ext.execute_with(|| {
+			// Start test from block 1
+			run_to_block(1);
+
+			UintAuthorityId::set_all_keys(vec![BOB]);
+			setup_undercollateralized_alice();
+
+			// Alice's 10 NATIVE now cover only 0.2 worth of the repaid value
+			MockPriceOracle::set_price(NATIVE, Some(FixedU128::from_rational(2, 100)));
+
+			Defi::offchain_worker(1);
+
+			let tx = pool_state.write().transactions.pop().unwrap();
+			let tx = Extrinsic::decode(&mut &*tx).unwrap();
+			let payload = match tx.call {
+				RuntimeCall::Defi(pallet::Call::liquidate_unsigned { payload, .. }) => payload,
+				_ => panic!("unexpected call"),
+			};
+
+			// 3.5 USDT would seize 19.25 NATIVE, so repay is scaled by 10 / 19.25
+			let repay_amount = FixedU128::from_inner(balance!(35) / 10)
+				.saturating_mul(FixedU128::from_rational(balance!(10), balance!(1925) / 10))
+				.into_inner();
+			assert_eq!(payload.repay_amount, repay_amount);
+
+			let (payload, signature) = signed_liquidation(BOB, repay_amount);
+			assert_ok!(Defi::liquidate_unsigned(RuntimeOrigin::none(), payload, signature));
+			assert_ok!(Defi::do_try_state());
+		});
+	}
+
+	#[test]
+	fn validate_unsigned_rejects_invalid_liquidations() {
+		let mut ext = ExtBuilder::default().build();
+		ext.execute_with(|| {
+			// Start test from block 1
+			run_to_block(1);
+
+			setup_undercollateralized_alice();
+
+			let validate = |payload, signature| {
+				Defi::validate_unsigned(
+					TransactionSource::External,
+					&pallet::Call::liquidate_unsigned { payload, signature },
+				)
+			};
+
+			// Signed by a different key
+			let (payload, _) = signed_liquidation(BOB, balance!(1));
+			let (_, signature) = signed_liquidation(CHARLIE, balance!(1));
+			assert_err!(validate(payload, signature), InvalidTransaction::BadProof);
+
+			// Liquidator can't pay the repaid amount
+			let (payload, signature) = signed_liquidation(DAVE, balance!(1));
+			assert_err!(validate(payload, signature), InvalidTransaction::Payment);
+
+			// Borrower liquidating himself
+			let (payload, signature) = signed_liquidation(ALICE, balance!(1));
+			assert_err!(validate(payload, signature), InvalidTransaction::Call);
+
+			// Borrower is not undercollateralized anymore
+			MockPriceOracle::set_price(NATIVE, Some(FixedU128::one()));
+			let (payload, signature) = signed_liquidation(BOB, balance!(1));
+			assert_err!(validate(payload, signature), InvalidTransaction::Call);
+			MockPriceOracle::set_price(NATIVE, Some(FixedU128::from_rational(1, 2)));
+
+			// Valid liquidation
+			let (payload, signature) = signed_liquidation(BOB, balance!(1));
+			assert_ok!(validate(payload, signature));
+
+			// Liquidation found longer ago than its longevity
+			run_to_block(7);
+			let (payload, signature) = signed_liquidation(BOB, balance!(1));
+			assert_err!(validate(payload, signature), InvalidTransaction::Stale);
+		});
+	}
+
+	#[test]
+	fn liquidate_unsigned_replay_rejected() {
+		let mut ext = ExtBuilder::default().build();
+		ext.execute_with(|| {
+			// Start test from block 1
+			run_to_block(1);
+
+			setup_undercollateralized_alice();
+
+			let (payload, signature) = signed_liquidation(BOB, balance!(1));
+			let call = pallet::Call::liquidate_unsigned {
+				payload: payload.clone(),
+				signature: signature.clone(),
+			};
+			assert_ok!(Defi::pre_dispatch(&call));
+			assert_ok!(Defi::liquidate_unsigned(RuntimeOrigin::none(), payload.clone(), signature));
+			assert_eq!(pallet::LiquidatorNonces::<Runtime>::get(BOB), 1);
+
+			// The executed payload can't be submitted again
+			assert_err!(
+				Defi::validate_unsigned(TransactionSource::External, &call),
+				InvalidTransaction::Stale
+			);
+			assert_err!(Defi::pre_dispatch(&call), InvalidTransaction::Stale);
+
+			// Later liquidations wait for the previous ones of the liquidator
+			let sign = |nonce| {
+				let payload = LiquidationPayload { nonce, ..payload.clone() };
+				let signature = TestSignature(BOB, payload.encode());
+				pallet::Call::liquidate_unsigned { payload, signature }
+			};
+			assert_eq!(
+				Defi::validate_unsigned(TransactionSource::External, &sign(1)).unwrap().requires,
+				Vec::<Vec<u8>>::new()
+			);
+			assert_ok!(Defi::pre_dispatch(&sign(1)));
+			assert_eq!(
+				Defi::validate_unsigned(TransactionSource::External, &sign(2)).unwrap().requires,
+				vec![("DefiLiquidation", (BOB, 1u32)).encode()]
+			);
+			assert_err!(Defi::pre_dispatch(&sign(2)), InvalidTransaction::Future);
+		});
+	}
+
+	#[test]
+	fn liquidate_unsigned_bad_origin() {
+		let mut ext = ExtBuilder::default().build();
+		ext.execute_with(|| {
+			// Start test from block 1
+			run_to_block(1);
+
+			setup_undercollateralized_alice();
+
+			let (payload, signature) = signed_liquidation(BOB, balance!(1));
+			assert_err!(
+				Defi::liquidate_unsigned(RuntimeOrigin::signed(BOB), payload, signature),
+				DispatchError::BadOrigin
+			);
+		});
+	}
+
+	#[test]
+	fn pause_operation_bad_origin() {
+		let mut ext = ExtBuilder::default().build();
+		ext.execute_with(|| {
+			// Start test from block 1
+			run_to_block(1);
+
+			assert_err!(
+				Defi::pause_operation(RuntimeOrigin::signed(ALICE), Operation::Borrow),
+				DispatchError::BadOrigin
+			);
+			assert_err!(
+				Defi::unpause_operation(RuntimeOrigin::signed(AUTHORITY), Operation::Borrow),
+				DispatchError::BadOrigin
+			);
+		});
+	}
+
+	#[test]
+	fn pause_operation_ok() {
+		let mut ext = ExtBuilder::default().build();
+		ext.execute_with(|| {
+			// Start test from block 1
+			run_to_block(1);
+
+			assert_ok!(Defi::deposit(RuntimeOrigin::signed(ALICE), NATIVE, balance!(10)));
+			assert_ok!(Defi::borrow(RuntimeOrigin::signed(ALICE), NATIVE, balance!(2)));
+
+			assert_ok!(Defi::pause_operation(RuntimeOrigin::signed(CHARLIE), Operation::Borrow));
+			System::assert_last_event(pallet::Event::OperationPaused(Operation::Borrow).into());
+			assert!(Defi::is_paused(Operation::Borrow));
+
+			// Borrowing is frozen while other operations keep working
+			assert_err!(
+				Defi::borrow(RuntimeOrigin::signed(ALICE), NATIVE, balance!(1)),
+				Error::<Runtime>::OperationPaused
+			);
+			assert_ok!(Defi::repay(RuntimeOrigin::signed(ALICE), NATIVE, balance!(1)));
+			assert_ok!(Defi::deposit(RuntimeOrigin::signed(ALICE), NATIVE, balance!(1)));
+			assert_ok!(Defi::withdraw(RuntimeOrigin::signed(ALICE), NATIVE, balance!(1)));
+
+			assert_ok!(Defi::unpause_operation(RuntimeOrigin::signed(CHARLIE), Operation::Borrow));
+			System::assert_last_event(pallet::Event::OperationUnpaused(Operation::Borrow).into());
+			assert!(!Defi::is_paused(Operation::Borrow));
+
+			assert_ok!(Defi::borrow(RuntimeOrigin::signed(ALICE), NATIVE, balance!(1)));
+		});
+	}
+
+	#[test]
+	fn set_pause_guardian_bad_origin() {
+		let mut ext = ExtBuilder::default().build();
+		ext.execute_with(|| {
+			// Start test from block 1
+			run_to_block(1);
+
+			// Neither the pause guardian nor the authority account can replace the guardian
+			assert_err!(
+				Defi::set_pause_guardian(RuntimeOrigin::signed(CHARLIE), Some(ALICE)),
+				DispatchError::BadOrigin
+			);
+			assert_err!(
+				Defi::set_pause_guardian(RuntimeOrigin::signed(AUTHORITY), Some(ALICE)),
+				DispatchError::BadOrigin
+			);
+		});
+	}
+
+	#[test]
+	fn set_pause_guardian_ok() {
+		let mut ext = ExtBuilder::default().build();
+		ext.execute_with(|| {
+			// Start test from block 1
+			run_to_block(1);
+
+			assert_ok!(Defi::set_pause_guardian(RuntimeOrigin::root(), Some(ALICE)));
+			System::assert_last_event(pallet::Event::PauseGuardianSet(Some(ALICE)).into());
+			assert_eq!(Defi::pause_guardian(), Some(ALICE));
+
+			// New pause guardian can pause operations, previous one can't
+			assert_ok!(Defi::pause_operation(RuntimeOrigin::signed(ALICE), Operation::Borrow));
+			assert_err!(
+				Defi::unpause_operation(RuntimeOrigin::signed(CHARLIE), Operation::Borrow),
+				DispatchError::BadOrigin
+			);
+
+			// Removing the pause guardian leaves no signed account able to pause
+			assert_ok!(Defi::set_pause_guardian(RuntimeOrigin::root(), None));
+			System::assert_last_event(pallet::Event::PauseGuardianSet(None).into());
+			assert_err!(
+				Defi::unpause_operation(RuntimeOrigin::signed(ALICE), Operation::Borrow),
+				DispatchError::BadOrigin
+			);
+		});
+	}
+
+	#[test]
+	fn pause_liquidations_ok() {
+		let (mut ext, pool_state) = ExtBuilder::default().build_offchain();
+		ext.execute_with(|| {
+			// Start test from block 1
+			run_to_block(1);
+
+			setup_undercollateralized_alice();
+			UintAuthorityId::set_all_keys(vec![BOB]);
+
+			assert_ok!(Defi::pause_operation(RuntimeOrigin::signed(CHARLIE), Operation::Liquidate));
+
+			// Signed liquidations fail
+			assert_err!(
+				Defi::liquidate(RuntimeOrigin::signed(BOB), ALICE, USDT, NATIVE, balance!(1)),
+				Error::<Runtime>::OperationPaused
+			);
+
+			// Unsigned liquidations are neither submitted nor accepted
+			Defi::offchain_worker(1);
+			assert!(pool_state.read().transactions.is_empty());
+
+			let (payload, signature) = signed_liquidation(BOB, balance!(1));
+			assert_err!(
+				Defi::validate_unsigned(
+					TransactionSource::External,
+					&pallet::Call::liquidate_unsigned { payload, signature },
+				),
+				InvalidTransaction::Call
+			);
+		});
+	}
+
+	#[test]
+	fn set_flash_loan_fee_unauthorized_user_action() {
+		let mut ext = ExtBuilder::default().build();
+		ext.execute_with(|| {
+			// Start test from block 1
+			run_to_block(1);
+
+			assert_err!(
+				Defi::set_flash_loan_fee(
+					RuntimeOrigin::signed(ALICE),
+					NATIVE,
+					FixedU128::from_rational(1, 100)
+				),
+				Error::<Runtime>::UnauthorizedUserAction
+			);
+		});
+	}
+
+	#[test]
+	fn set_flash_loan_fee_invalid() {
+		let mut ext = ExtBuilder::default().build();
+		ext.execute_with(|| {
+			// Start test from block 1
+			run_to_block(1);
+
+			assert_err!(
+				Defi::set_flash_loan_fee(
+					RuntimeOrigin::signed(AUTHORITY),
+					NATIVE,
+					FixedU128::from_u32(2)
+				),
+				Error::<Runtime>::InvalidFlashLoanFee
+			);
+		});
+	}
+
+	#[test]
+	fn flash_loan_pallet_has_not_enough_funds() {
+		let mut ext = ExtBuilder::default().build();
+		ext.execute_with(|| {
+			// Start test from block 1
+			run_to_block(1);
+
+			assert_ok!(Defi::deposit(RuntimeOrigin::signed(CHARLIE), NATIVE, balance!(50)));
+
+			assert_err!(
+				Defi::flash_loan(
+					RuntimeOrigin::signed(ALICE),
+					NATIVE,
+					balance!(51),
+					Box::new(RuntimeCall::System(frame_system::Call::remark { remark: vec![] }))
+				),
+				Error::<Runtime>::PalletHasNotEnoughFunds
+			);
+		});
+	}
+
+	#[test]
+	fn flash_loan_not_repaid() {
+		let mut ext = ExtBuilder::default().build();
+		ext.execute_with(|| {
+			// Start test from block 1
+			run_to_block(1);
+
+			assert_ok!(Defi::deposit(RuntimeOrigin::signed(CHARLIE), NATIVE, balance!(50)));
+
+			// ALICE sends the loan away and can't pay it back
+			let call = RuntimeCall::Balances(pallet_balances::Call::transfer_keep_alive {
+				dest: DAVE,
+				value: balance!(140),
+			});
+			assert!(Defi::flash_loan(
+				RuntimeOrigin::signed(ALICE),
+				NATIVE,
+				balance!(50),
+				Box::new(call)
+			)
+			.is_err());
+
+			// The whole loan is reverted
+			assert_eq!(pallet_balances::Pallet::<Runtime>::free_balance(ALICE), balance!(100));
+			assert_eq!(pallet_balances::Pallet::<Runtime>::free_balance(DAVE), balance!(100));
+			assert_eq!(
+				pallet_balances::Pallet::<Runtime>::free_balance(&pallet_id()),
+				balance!(50)
+			);
+		});
+	}
+
+	#[test]
+	fn flash_loan_ok() {
+		let mut ext = ExtBuilder::default().build();
+		ext.execute_with(|| {
+			// Start test from block 1
+			run_to_block(1);
+
+			assert_ok!(Defi::deposit(RuntimeOrigin::signed(CHARLIE), NATIVE, balance!(50)));
+			assert_ok!(Defi::set_flash_loan_fee(
+				RuntimeOrigin::signed(AUTHORITY),
+				NATIVE,
+				FixedU128::from_rational(1, 100)
+			));
+			assert_ok!(Defi::update_reserve_factor(
+				RuntimeOrigin::signed(AUTHORITY),
+				NATIVE,
+				FixedU128::from_rational(50, 100)
+			));
+
+			// ALICE passes the loan on and gets it back within the call
+			let call = RuntimeCall::Balances(pallet_balances::Call::transfer_keep_alive {
+				dest: DAVE,
+				value: balance!(10),
+			});
+			assert_ok!(Defi::flash_loan(
+				RuntimeOrigin::signed(ALICE),
+				NATIVE,
+				balance!(10),
+				Box::new(call)
+			));
+
+			// 1% fee of the 10 loaned
+			let fee = balance!(1) / 10;
+			System::assert_last_event(
+				pallet::Event::FlashLoanRepaid(ALICE, NATIVE, balance!(10), fee).into(),
+			);
+
+			// Check balances after the loan
+			assert_eq!(pallet_balances::Pallet::<Runtime>::free_balance(ALICE), balance!(90) - fee);
+			assert_eq!(pallet_balances::Pallet::<Runtime>::free_balance(DAVE), balance!(110));
+			assert_eq!(
+				pallet_balances::Pallet::<Runtime>::free_balance(&pallet_id()),
+				balance!(50) + fee
+			);
+
+			// Half of the fee goes to reserves, the other half to CHARLIE through the supply index
+			assert_eq!(pallet::TotalReserves::<Runtime>::get(NATIVE), fee / 2);
+			assert_eq!(pallet::TotalDeposits::<Runtime>::get(NATIVE), balance!(50) + fee / 2);
+			assert_eq!(Defi::get_lending_amount(NATIVE, CHARLIE), balance!(50) + fee / 2);
+			assert_ok!(Defi::do_try_state());
+		});
+	}
+
+	#[test]
+	fn flash_loan_call_filtered() {
+		let mut ext = ExtBuilder::default().build();
+		ext.execute_with(|| {
+			// Start test from block 1
+			run_to_block(1);
+
+			assert_ok!(Defi::deposit(RuntimeOrigin::signed(CHARLIE), NATIVE, balance!(50)));
+
+			// Lent funds can't be deposited back into the pool
+			let deposit =
+				RuntimeCall::Defi(pallet::Call::deposit { asset_id: NATIVE, amount: balance!(10) });
+			assert_err!(
+				Defi::flash_loan(
+					RuntimeOrigin::signed(ALICE),
+					NATIVE,
+					balance!(10),
+					Box::new(deposit)
+				),
+				Error::<Runtime>::FlashLoanCallFiltered
+			);
+
+			// Flash loans can't be nested
+			let nested = RuntimeCall::Defi(pallet::Call::flash_loan {
+				asset_id: NATIVE,
+				amount: balance!(10),
+				call: Box::new(RuntimeCall::System(frame_system::Call::remark { remark: vec![] })),
+			});
+			assert_err!(
+				Defi::flash_loan(
+					RuntimeOrigin::signed(ALICE),
+					NATIVE,
+					balance!(10),
+					Box::new(nested)
+				),
+				Error::<Runtime>::FlashLoanCallFiltered
+			);
+			assert_eq!(pallet_balances::Pallet::<Runtime>::free_balance(ALICE), balance!(100));
+		});
+	}
+
+	#[test]
+	fn flash_loan_paused() {
+		let mut ext = ExtBuilder::default().build();
+		ext.execute_with(|| {
+			// Start test from block 1
+			run_to_block(1);
+
+			assert_ok!(Defi::deposit(RuntimeOrigin::signed(CHARLIE), NATIVE, balance!(50)));
+			assert_ok!(Defi::pause_operation(RuntimeOrigin::signed(CHARLIE), Operation::FlashLoan));
+
+			assert_err!(
+				Defi::flash_loan(
+					RuntimeOrigin::signed(ALICE),
+					NATIVE,
+					balance!(10),
+					Box::new(RuntimeCall::System(frame_system::Call::remark { remark: vec![] }))
+				),
+				Error::<Runtime>::OperationPaused
+			);
+		});
+	}
+
+	#[test]
+	fn jump_rate_model_below_kink_ok() {
+		let utilization = FixedU128::from_rational(50, 100);
+		let reserve_factor = FixedU128::from_rational(10, 100);
+
+		// 0.01 + 0.5 * 0.1
+		assert_eq!(
+			MockJumpRateModel::borrow_rate(&NATIVE, utilization),
+			FixedU128::from_rational(6, 100)
+		);
+		// 0.06 * 0.5 * (1 - 0.1)
+		assert_eq!(
+			MockJumpRateModel::supply_rate(&NATIVE, utilization, reserve_factor),
+			FixedU128::from_rational(27, 1000)
+		);
+	}
+
+	#[test]
+	fn jump_rate_model_above_kink_ok() {
+		let utilization = FixedU128::from_rational(90, 100);
+		let reserve_factor = FixedU128::from_rational(10, 100);
+
+		// 0.01 + 0.8 * 0.1 + (0.9 - 0.8) * 2
+		assert_eq!(
+			MockJumpRateModel::borrow_rate(&NATIVE, utilization),
+			FixedU128::from_rational(29, 100)
+		);
+		// 0.29 * 0.9 * (1 - 0.1)
+		assert_eq!(
+			MockJumpRateModel::supply_rate(&NATIVE, utilization, reserve_factor),
+			FixedU128::from_rational(2349, 10000)
+		);
+	}
+
+	#[test]
+	fn migrate_to_v1_ok() {
+		let mut ext = ExtBuilder::default().build();
+		ext.execute_with(|| {
+			// Start test from block 1
+			run_to_block(1);
+
+			// Single market storage with deposits of ALICE, CHARLIE and DAVE and a deposit and a
+			// loan of BOB
+			StorageVersion::new(0).put::<Defi>();
+			v0::DepositRate::<Runtime>::put(FixedU128::from_rational(1, 100));
+			v0::BorrowingRate::<Runtime>::put(FixedU128::from_rational(2, 100));
+			v0::CollateralFactor::<Runtime>::put(FixedU128::from_rational(5, 10));
+			for (user, deposit) in
+				[(ALICE, balance!(50)), (CHARLIE, balance!(30)), (DAVE, balance!(10))]
+			{
+				v0::Accounts::<Runtime>::insert(
+					user,
+					v0::AddressInfo {
+						deposit_principal: deposit,
+						deposit_date: 1,
+						..Default::default()
+					},
+				);
+			}
+			v0::Accounts::<Runtime>::insert(
+				BOB,
+				v0::AddressInfo {
+					deposit_principal: balance!(20),
+					deposit_date: 1,
+					borrow_principal: balance!(10),
+					borrow_date: 1,
+				},
+			);
+			Balances::make_free_balance_be(&pallet_id(), balance!(110));
+
+			// Upgrade 10 blocks later
+			System::set_block_number(11);
+			MigrateToV1::<Runtime>::on_runtime_upgrade();
+
+			// The storage version is only set once every position is moved
+			assert_eq!(Defi::on_chain_storage_version(), StorageVersion::new(0));
+			assert!(Defi::accounts_migration().is_some());
+			assert!(Defi::is_paused(Operation::Deposit));
+			assert_err!(
+				Defi::deposit(RuntimeOrigin::signed(ALICE), NATIVE, balance!(10)),
+				Error::<Runtime>::MigrationInProgress
+			);
+
+			// The rates were moved into the native market
+			assert!(!v0::DepositRate::<Runtime>::exists());
+			assert!(!v0::BorrowingRate::<Runtime>::exists());
+			assert!(!v0::CollateralFactor::<Runtime>::exists());
+			assert_eq!(Defi::deposit_rate(NATIVE), FixedU128::from_rational(1, 100));
+			assert_eq!(Defi::borrowing_rate(NATIVE), FixedU128::from_rational(2, 100));
+			assert_eq!(Defi::collateral_factor(NATIVE), FixedU128::from_rational(5, 10));
+			assert_eq!(Defi::last_accrued(NATIVE), 11);
+
+			// Up to 3 entries are visited per block
+			run_to_block(12);
+			assert!(Defi::accounts_migration().is_some());
+			assert_eq!(Defi::on_chain_storage_version(), StorageVersion::new(0));
+
+			// The keys are then moved to the hasher of v2
+			run_to_block(20);
+			assert!(Defi::accounts_migration().is_none());
+			assert_eq!(Defi::on_chain_storage_version(), StorageVersion::new(2));
+			assert_eq!(v0::Accounts::<Runtime>::iter().count(), 0);
+			assert_eq!(v2::old_accounts_count::<Runtime>(), 0);
+
+			// The positions kept the interest accrued up to the upgrade
+			let accrued = |principal: Balance, rate: FixedU128| -> Balance {
+				FixedU128::from_inner(principal)
+					.saturating_mul(FixedU128::one().saturating_add(rate).saturating_pow(10))
+					.into_inner()
+			};
+			let deposits: Vec<_> = [
+				(ALICE, balance!(50)),
+				(BOB, balance!(20)),
+				(CHARLIE, balance!(30)),
+				(DAVE, balance!(10)),
+			]
+			.into_iter()
+			.map(|(user, deposit)| (user, accrued(deposit, FixedU128::from_rational(1, 100))))
+			.collect();
+			let bob_debt = accrued(balance!(10), FixedU128::from_rational(2, 100));
+
+			assert_eq!(Defi::receipt_assets(NATIVE), Some(NATIVE + RECEIPT_ASSET_OFFSET));
+			for (user, deposit) in deposits.iter() {
+				assert_eq!(Defi::receipt_balance(NATIVE, user), *deposit);
+			}
+			assert_eq!(pallet::Accounts::<Runtime>::iter().count(), 1);
+			let bob_info = pallet::Accounts::<Runtime>::get(NATIVE, BOB);
+			assert_eq!(bob_info.borrow_principal, bob_debt);
+			assert_eq!(bob_info.borrow_index, FixedU128::one());
+			assert_eq!(
+				Defi::total_deposits(NATIVE),
+				deposits.iter().map(|(_, deposit)| deposit).sum::<Balance>()
+			);
+			assert_eq!(Defi::total_borrows(NATIVE), bob_debt);
+			assert_eq!(Defi::last_accrued(NATIVE), 11);
+			assert!(!Defi::is_paused(Operation::Deposit));
+		});
+	}
+
+	#[test]
+	fn migrate_to_v1_current_version_ok() {
+		let mut ext = ExtBuilder::default().build();
+		ext.execute_with(|| {
+			// Start test from block 1
+			run_to_block(1);
+
+			v0::DepositRate::<Runtime>::put(FixedU128::from_rational(1, 100));
+
+			assert_eq!(Defi::on_chain_storage_version(), StorageVersion::new(3));
+			MigrateToV1::<Runtime>::on_runtime_upgrade();
+
+			// Nothing was migrated
+			assert!(v0::DepositRate::<Runtime>::exists());
+			assert_eq!(Defi::deposit_rate(NATIVE), get_default_deposit_rate());
+			assert_eq!(Defi::receipt_assets(NATIVE), None);
+		});
+	}
+
+	#[test]
+	fn migrate_to_v2_ok() {
+		let mut ext = ExtBuilder::default().build();
+		ext.execute_with(|| {
+			// Start test from block 1
+			run_to_block(1);
+
+			for user in [ALICE, BOB, CHARLIE, DAVE] {
+				assert_ok!(Defi::deposit(RuntimeOrigin::signed(user), NATIVE, balance!(20)));
+				assert_ok!(Defi::borrow(RuntimeOrigin::signed(user), NATIVE, balance!(10)));
+			}
+
+			// Key the positions by the raw account id
+			let accounts: Vec<_> = pallet::Accounts::<Runtime>::iter().collect();
+			for (asset_id, who, address_info) in pallet::Accounts::<Runtime>::drain() {
+				v2::OldAccounts::<Runtime>::insert(asset_id, who, address_info);
+			}
+			StorageVersion::new(1).put::<Defi>();
+			assert_eq!(v2::old_accounts_count::<Runtime>(), 4);
+
+			MigrateToV2::<Runtime>::on_runtime_upgrade();
+
+			assert_eq!(Defi::on_chain_storage_version(), StorageVersion::new(2));
+			assert!(Defi::is_paused(Operation::Deposit));
+			assert_err!(
+				Defi::deposit(RuntimeOrigin::signed(ALICE), NATIVE, balance!(10)),
+				Error::<Runtime>::MigrationInProgress
+			);
+
+			// Up to 3 entries are visited per block
+			run_to_block(2);
+			assert!(Defi::accounts_migration().is_some());
+
+			run_to_block(10);
+			assert!(Defi::accounts_migration().is_none());
+			assert_eq!(v2::old_accounts_count::<Runtime>(), 0);
+
+			// Every position was moved
+			assert_eq!(pallet::Accounts::<Runtime>::iter().count(), accounts.len());
+			for (asset_id, who, address_info) in accounts {
+				assert_eq!(pallet::Accounts::<Runtime>::get(asset_id, who), address_info);
+			}
+
+			assert!(!Defi::is_paused(Operation::Deposit));
+			assert_ok!(Defi::deposit(RuntimeOrigin::signed(ALICE), NATIVE, balance!(10)));
+			assert_ok!(Defi::do_try_state());
+		});
+	}
+
+	#[test]
+	fn migrate_to_v2_current_version_ok() {
+		let mut ext = ExtBuilder::default().build();
+		ext.execute_with(|| {
+			// Start test from block 1
+			run_to_block(1);
+
+			assert_eq!(Defi::on_chain_storage_version(), StorageVersion::new(3));
+			MigrateToV2::<Runtime>::on_runtime_upgrade();
+
+			// Nothing is migrated
+			assert!(Defi::accounts_migration().is_none());
+			assert!(!Defi::is_paused(Operation::Deposit));
+		});
+	}
+
+	#[test]
+	fn migrate_to_v3_ok() {
+		let mut ext = ExtBuilder::default().build();
+		ext.execute_with(|| {
+			// Start test from block 1
+			run_to_block(1);
+
+			assert_ok!(Defi::deposit(RuntimeOrigin::signed(ALICE), NATIVE, balance!(20)));
+			assert_ok!(Defi::borrow(RuntimeOrigin::signed(ALICE), NATIVE, balance!(10)));
+			let bounds = ParameterBounds {
+				min: FixedU128::from_inner(0),
+				max: FixedU128::from_rational(1, 100),
+				max_change: FixedU128::from_rational(1, 1000),
+			};
+			assert_ok!(Defi::set_parameter_bounds(
+				RuntimeOrigin::root(),
+				MarketParameter::BorrowingRate,
+				Some(bounds.clone())
+			));
+
+			// Upgrade 10 blocks later, at a time counted in seconds
+			run_to_block(11);
+			let debt = Defi::get_debt_amount(NATIVE, ALICE);
+			let borrowing_apy = Defi::get_borrowing_apy(NATIVE);
+
+			AccrueByTimestamp::set(true);
+			MockTimestamp::set(1_000_000);
+			StorageVersion::new(2).put::<Defi>();
+			MigrateToV3::<Runtime, NumberOfBlocksYearly>::on_runtime_upgrade();
+
+			assert_eq!(Defi::on_chain_storage_version(), StorageVersion::new(3));
+
+			// Rates per second are 6 times lower than per block of 6 seconds
+			let per_second = |rate: FixedU128| FixedU128::from_inner(rate.into_inner() / 6);
+			assert_eq!(Defi::deposit_rate(NATIVE), per_second(get_default_deposit_rate()));
+			assert_eq!(Defi::borrowing_rate(NATIVE), per_second(get_default_borrowing_rate()));
+			assert_eq!(
+				Defi::bounds(MarketParameter::BorrowingRate),
+				Some(ParameterBounds {
+					min: FixedU128::from_inner(0),
+					max: per_second(bounds.max),
+					max_change: per_second(bounds.max_change),
+				})
+			);
+
+			// The 10 blocks not accrued yet last 60 seconds
+			assert_eq!(Defi::last_accrued(NATIVE), 1_000_000 - 60);
+
+			// The debt and the yearly rate are kept
+			let new_debt = Defi::get_debt_amount(NATIVE, ALICE);
+			assert!(new_debt.abs_diff(debt) <= debt / 1_000_000_000_000);
+			let new_borrowing_apy = Defi::get_borrowing_apy(NATIVE);
+			assert!(new_borrowing_apy.abs_diff(borrowing_apy) <= borrowing_apy / 1_000_000);
+
+			// Interest accrues over time regardless of the blocks produced
+			run_to_block(20);
+			assert_eq!(Defi::get_debt_amount(NATIVE, ALICE), new_debt);
+
+			MockTimestamp::set(1_000_060);
+			assert!(Defi::get_debt_amount(NATIVE, ALICE) > new_debt);
+
+			assert_ok!(Defi::do_try_state());
+		});
+	}
+
+	#[test]
+	fn migrate_to_v3_current_version_ok() {
+		let mut ext = ExtBuilder::default().build();
+		ext.execute_with(|| {
+			// Start test from block 1
+			run_to_block(1);
+
+			assert_eq!(Defi::on_chain_storage_version(), StorageVersion::new(3));
+			MigrateToV3::<Runtime, NumberOfBlocksYearly>::on_runtime_upgrade();
+
+			// Nothing is migrated
+			assert_eq!(Defi::deposit_rate(NATIVE), get_default_deposit_rate());
+			assert_eq!(Defi::borrowing_rate(NATIVE), get_default_borrowing_rate());
+		});
+	}
+
+	#[test]
+	fn migrate_to_v3_from_v0_ok() {
+		let mut ext = ExtBuilder::default().build();
+		ext.execute_with(|| {
+			// Start test from block 1
+			run_to_block(1);
+
+			// Single market storage with a deposit of ALICE and a deposit and a loan of BOB
+			StorageVersion::new(0).put::<Defi>();
+			v0::DepositRate::<Runtime>::put(FixedU128::from_rational(1, 100));
+			v0::BorrowingRate::<Runtime>::put(FixedU128::from_rational(2, 100));
+			v0::CollateralFactor::<Runtime>::put(FixedU128::from_rational(5, 10));
+			v0::Accounts::<Runtime>::insert(
+				ALICE,
+				v0::AddressInfo {
+					deposit_principal: balance!(50),
+					deposit_date: 1,
+					..Default::default()
+				},
+			);
+			v0::Accounts::<Runtime>::insert(
+				BOB,
+				v0::AddressInfo {
+					deposit_principal: balance!(20),
+					deposit_date: 1,
+					borrow_principal: balance!(5),
+					borrow_date: 1,
+				},
+			);
+			Balances::make_free_balance_be(&pallet_id(), balance!(100));
+
+			// Every migration is part of the upgrade 10 blocks later, at a time counted in seconds
+			System::set_block_number(11);
+			AccrueByTimestamp::set(true);
+			MockTimestamp::set(1_000_000);
+			<(
+				MigrateToV1<Runtime>,
+				MigrateToV2<Runtime>,
+				MigrateToV3<Runtime, NumberOfBlocksYearly>,
+			)>::on_runtime_upgrade();
+
+			// The following migrations wait for the positions to be converted
+			assert_eq!(Defi::on_chain_storage_version(), StorageVersion::new(0));
+			assert_eq!(
+				v3::DeferredBlocksPerYear::<Runtime>::get(),
+				Some(NumberOfBlocksYearly::get())
+			);
+			assert_eq!(Defi::deposit_rate(NATIVE), FixedU128::from_rational(1, 100));
+			assert!(Defi::is_paused(Operation::Withdraw));
+
+			// Operations resume once every migration is finished
+			run_to_block(20);
+			assert!(Defi::accounts_migration().is_none());
+			assert_eq!(Defi::on_chain_storage_version(), StorageVersion::new(3));
+			assert!(!v3::DeferredBlocksPerYear::<Runtime>::exists());
+			assert_eq!(v2::old_accounts_count::<Runtime>(), 0);
+			assert!(!Defi::is_paused(Operation::Withdraw));
+
+			// Rates are per second and the blocks since the upgrade weren't lost
+			let per_second = |rate: FixedU128| FixedU128::from_inner(rate.into_inner() / 6);
+			assert_eq!(Defi::deposit_rate(NATIVE), per_second(FixedU128::from_rational(1, 100)));
+			assert_eq!(Defi::borrowing_rate(NATIVE), per_second(FixedU128::from_rational(2, 100)));
+			assert!(Defi::last_accrued(NATIVE) < 1_000_000);
+
+			// The debt of BOB is still enforced
+			let bob_debt = FixedU128::from_inner(balance!(5))
+				.saturating_mul(FixedU128::from_rational(102, 100).saturating_pow(10))
+				.into_inner();
+			assert!(Defi::get_debt_amount(NATIVE, BOB) >= bob_debt);
+			assert_err!(
+				Defi::withdraw(
+					RuntimeOrigin::signed(BOB),
+					NATIVE,
+					Defi::get_lending_amount(NATIVE, BOB)
+				),
+				Error::<Runtime>::UnallowedWithdrawAmount
+			);
+			assert_ok!(Defi::withdraw(RuntimeOrigin::signed(ALICE), NATIVE, balance!(10)));
+
+			assert_ok!(Defi::do_try_state());
+		});
+	}
+
+	#[test]
+	fn storage_info_bounded_ok() {
+		// Every storage item has a maximum size accounted in the proof size
+		for info in <Defi as StorageInfoTrait>::storage_info() {
+			assert!(
+				info.max_size.is_some(),
+				"{} has no maximum size",
+				String::from_utf8_lossy(&info.storage_name)
+			);
+		}
+	}
 }
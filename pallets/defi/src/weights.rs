@@ -1,13 +1,13 @@
-
-//! Autogenerated weights for `pallet_defi`
+//! Weights for `pallet_defi`
 //!
-//! THIS FILE WAS AUTO-GENERATED USING THE SUBSTRATE BENCHMARK CLI VERSION 4.0.0-dev
-//! DATE: 2024-03-20, STEPS: `50`, REPEAT: `20`, LOW RANGE: `[]`, HIGH RANGE: `[]`
-//! WORST CASE MAP SIZE: `1000000`
-//! HOSTNAME: `DESKTOP-F2AGLQ1`, CPU: `AMD Ryzen 7 5825U with Radeon Graphics`
-//! EXECUTION: ``, WASM-EXECUTION: `Compiled`, CHAIN: `Some("local")`, DB CACHE: 1024
+//! THESE WEIGHTS ARE MAINTAINED BY HAND AND WERE NOT PRODUCED BY THE BENCHMARK CLI.
+//! Storage reads and writes are counted from the code and proof sizes follow from the
+//! `MaxEncodedLen` of the accessed items, execution times are estimates. Regenerate the file
+//! with the command below on reference hardware before relying on the execution times.
+//! Accesses are counted along the benchmarked paths in the native market, with interest
+//! accrued since the last interaction and origins checked against the stored accounts.
 
-// Executed Command:
+// Command:
 // target/release/node-template
 // benchmark
 // pallet
@@ -33,177 +33,1137 @@ use frame_support::{traits::Get, weights::{Weight, constants::RocksDbWeight}};
 use core::marker::PhantomData;
 
 pub trait WeightInfo {
-	fn deposit() -> Weight;
-	fn withdraw() -> Weight;
-	fn borrow() -> Weight;
+	fn deposit(m: u32, ) -> Weight;
+	fn withdraw(m: u32, ) -> Weight;
+	fn borrow(m: u32, ) -> Weight;
 	fn repay() -> Weight;
 	fn update_deposit_rate() -> Weight;
 	fn update_borrowing_rate() -> Weight;
 	fn update_collateral_factor() -> Weight;
+	fn create_market(m: u32, ) -> Weight;
+	fn update_reserve_factor() -> Weight;
+	fn add_reserves() -> Weight;
+	fn reduce_reserves() -> Weight;
+	fn liquidate(m: u32, ) -> Weight;
+	fn check_liquidity(n: u32, m: u32, ) -> Weight;
+	fn set_authority() -> Weight;
+	fn set_pause_guardian() -> Weight;
+	fn set_parameter_bounds() -> Weight;
+	fn cancel_parameter_change() -> Weight;
+	fn apply_parameter_changes(n: u32, ) -> Weight;
+	fn pause_operation() -> Weight;
+	fn unpause_operation() -> Weight;
+	fn set_caps() -> Weight;
+	fn set_global_caps() -> Weight;
+	fn set_liquidity_buffer() -> Weight;
+	fn flash_loan() -> Weight;
+	fn set_flash_loan_fee() -> Weight;
+	fn fund_insurance() -> Weight;
+	fn migrate_accounts(n: u32, ) -> Weight;
+	fn migrate_positions(n: u32, ) -> Weight;
+	fn deposit_collateral() -> Weight;
+	fn withdraw_collateral(m: u32, ) -> Weight;
 }
 
 /// Weight functions for `pallet_defi`.
 pub struct SubstrateWeight<T>(PhantomData<T>);
 impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
+	/// Storage: `Defi::AccountsMigration` (r:1 w:0)
+	/// Proof: `Defi::AccountsMigration` (`max_values`: Some(1), `max_size`: Some(86), added: 581, mode: `MaxEncodedLen`)
+	/// Storage: `Defi::PausedOperations` (r:1 w:0)
+	/// Proof: `Defi::PausedOperations` (`max_values`: None, `max_size`: Some(2), added: 2477, mode: `MaxEncodedLen`)
 	/// Storage: `System::Account` (r:2 w:2)
 	/// Proof: `System::Account` (`max_values`: None, `max_size`: Some(128), added: 2603, mode: `MaxEncodedLen`)
-	/// Storage: `Defi::Accounts` (r:1 w:1)
-	/// Proof: `Defi::Accounts` (`max_values`: None, `max_size`: None, mode: `Measured`)
-	fn deposit() -> Weight {
-		// Proof Size summary in bytes:
-		//  Measured:  `160`
-		//  Estimated: `6196`
-		// Minimum execution time: 65_876_000 picoseconds.
+	/// Storage: `Defi::BorrowIndex` (r:16 w:1)
+	/// Proof: `Defi::BorrowIndex` (`max_values`: None, `max_size`: Some(20), added: 2495, mode: `MaxEncodedLen`)
+	/// Storage: `Defi::SupplyIndex` (r:16 w:1)
+	/// Proof: `Defi::SupplyIndex` (`max_values`: None, `max_size`: Some(20), added: 2495, mode: `MaxEncodedLen`)
+	/// Storage: `Defi::TotalBorrows` (r:16 w:1)
+	/// Proof: `Defi::TotalBorrows` (`max_values`: None, `max_size`: Some(20), added: 2495, mode: `MaxEncodedLen`)
+	/// Storage: `Defi::TotalDeposits` (r:16 w:1)
+	/// Proof: `Defi::TotalDeposits` (`max_values`: None, `max_size`: Some(20), added: 2495, mode: `MaxEncodedLen`)
+	/// Storage: `Defi::TotalReserves` (r:16 w:1)
+	/// Proof: `Defi::TotalReserves` (`max_values`: None, `max_size`: Some(20), added: 2495, mode: `MaxEncodedLen`)
+	/// Storage: `Defi::LastAccrued` (r:16 w:1)
+	/// Proof: `Defi::LastAccrued` (`max_values`: None, `max_size`: Some(12), added: 2487, mode: `MaxEncodedLen`)
+	/// Storage: `Timestamp::Now` (r:1 w:0)
+	/// Proof: `Timestamp::Now` (`max_values`: Some(1), `max_size`: Some(8), added: 503, mode: `MaxEncodedLen`)
+	/// Storage: `Defi::BorrowingRate` (r:16 w:0)
+	/// Proof: `Defi::BorrowingRate` (`max_values`: None, `max_size`: Some(20), added: 2495, mode: `MaxEncodedLen`)
+	/// Storage: `Defi::DepositRate` (r:16 w:0)
+	/// Proof: `Defi::DepositRate` (`max_values`: None, `max_size`: Some(20), added: 2495, mode: `MaxEncodedLen`)
+	/// Storage: `Defi::ReserveFactor` (r:16 w:0)
+	/// Proof: `Defi::ReserveFactor` (`max_values`: None, `max_size`: Some(20), added: 2495, mode: `MaxEncodedLen`)
+	/// Storage: `Defi::InsuranceFund` (r:16 w:0)
+	/// Proof: `Defi::InsuranceFund` (`max_values`: None, `max_size`: Some(20), added: 2495, mode: `MaxEncodedLen`)
+	/// Storage: `Defi::UncoveredBadDebt` (r:16 w:0)
+	/// Proof: `Defi::UncoveredBadDebt` (`max_values`: None, `max_size`: Some(20), added: 2495, mode: `MaxEncodedLen`)
+	/// Storage: `Defi::SupplyCap` (r:1 w:0)
+	/// Proof: `Defi::SupplyCap` (`max_values`: None, `max_size`: Some(20), added: 2495, mode: `MaxEncodedLen`)
+	/// Storage: `Defi::GlobalSupplyCap` (r:1 w:0)
+	/// Proof: `Defi::GlobalSupplyCap` (`max_values`: Some(1), `max_size`: Some(16), added: 511, mode: `MaxEncodedLen`)
+	/// Storage: `Oracle::Prices` (r:16 w:0)
+	/// Proof: `Oracle::Prices` (`max_values`: None, `max_size`: Some(40), added: 2515, mode: `MaxEncodedLen`)
+	/// Storage: `Defi::ReceiptAssets` (r:1 w:0)
+	/// Proof: `Defi::ReceiptAssets` (`max_values`: None, `max_size`: Some(8), added: 2483, mode: `MaxEncodedLen`)
+	/// Storage: `Assets::Asset` (r:1 w:1)
+	/// Proof: `Assets::Asset` (`max_values`: None, `max_size`: Some(210), added: 2685, mode: `MaxEncodedLen`)
+	/// Storage: `Assets::Account` (r:1 w:1)
+	/// Proof: `Assets::Account` (`max_values`: None, `max_size`: Some(134), added: 2609, mode: `MaxEncodedLen`)
+	/// Storage: `Defi::Markets` (r:15 w:0)
+	/// Proof: `Defi::Markets` (`max_values`: None, `max_size`: Some(5), added: 2480, mode: `MaxEncodedLen`)
+	/// The range of component `m` is `[1, 16]`.
+	/// The global supply cap is set and every listed market has deposits.
+	fn deposit(m: u32, ) -> Weight {
 		Weight::from_parts(67_771_000, 0)
 			.saturating_add(Weight::from_parts(0, 6196))
-			.saturating_add(T::DbWeight::get().reads(3))
-			.saturating_add(T::DbWeight::get().writes(3))
+			.saturating_add(Weight::from_parts(9_214_000, 0).saturating_mul(m.into()))
+			.saturating_add(T::DbWeight::get().reads(9))
+			.saturating_add(T::DbWeight::get().reads((13_u64).saturating_mul(m.into())))
+			.saturating_add(T::DbWeight::get().writes(10))
+			.saturating_add(Weight::from_parts(0, 32432).saturating_mul(m.into()))
 	}
+	/// Storage: `Defi::AccountsMigration` (r:1 w:0)
+	/// Proof: `Defi::AccountsMigration` (`max_values`: Some(1), `max_size`: Some(86), added: 581, mode: `MaxEncodedLen`)
+	/// Storage: `Defi::PausedOperations` (r:1 w:0)
+	/// Proof: `Defi::PausedOperations` (`max_values`: None, `max_size`: Some(2), added: 2477, mode: `MaxEncodedLen`)
+	/// Storage: `Defi::ReceiptAssets` (r:16 w:0)
+	/// Proof: `Defi::ReceiptAssets` (`max_values`: None, `max_size`: Some(8), added: 2483, mode: `MaxEncodedLen`)
+	/// Storage: `Assets::Account` (r:16 w:1)
+	/// Proof: `Assets::Account` (`max_values`: None, `max_size`: Some(134), added: 2609, mode: `MaxEncodedLen`)
+	/// Storage: `Defi::BorrowIndex` (r:16 w:1)
+	/// Proof: `Defi::BorrowIndex` (`max_values`: None, `max_size`: Some(20), added: 2495, mode: `MaxEncodedLen`)
+	/// Storage: `Defi::SupplyIndex` (r:16 w:1)
+	/// Proof: `Defi::SupplyIndex` (`max_values`: None, `max_size`: Some(20), added: 2495, mode: `MaxEncodedLen`)
+	/// Storage: `Defi::TotalBorrows` (r:16 w:1)
+	/// Proof: `Defi::TotalBorrows` (`max_values`: None, `max_size`: Some(20), added: 2495, mode: `MaxEncodedLen`)
+	/// Storage: `Defi::TotalDeposits` (r:16 w:1)
+	/// Proof: `Defi::TotalDeposits` (`max_values`: None, `max_size`: Some(20), added: 2495, mode: `MaxEncodedLen`)
+	/// Storage: `Defi::TotalReserves` (r:16 w:1)
+	/// Proof: `Defi::TotalReserves` (`max_values`: None, `max_size`: Some(20), added: 2495, mode: `MaxEncodedLen`)
+	/// Storage: `Defi::LastAccrued` (r:16 w:1)
+	/// Proof: `Defi::LastAccrued` (`max_values`: None, `max_size`: Some(12), added: 2487, mode: `MaxEncodedLen`)
+	/// Storage: `Timestamp::Now` (r:1 w:0)
+	/// Proof: `Timestamp::Now` (`max_values`: Some(1), `max_size`: Some(8), added: 503, mode: `MaxEncodedLen`)
+	/// Storage: `Defi::BorrowingRate` (r:16 w:0)
+	/// Proof: `Defi::BorrowingRate` (`max_values`: None, `max_size`: Some(20), added: 2495, mode: `MaxEncodedLen`)
+	/// Storage: `Defi::DepositRate` (r:16 w:0)
+	/// Proof: `Defi::DepositRate` (`max_values`: None, `max_size`: Some(20), added: 2495, mode: `MaxEncodedLen`)
+	/// Storage: `Defi::ReserveFactor` (r:16 w:0)
+	/// Proof: `Defi::ReserveFactor` (`max_values`: None, `max_size`: Some(20), added: 2495, mode: `MaxEncodedLen`)
+	/// Storage: `Defi::InsuranceFund` (r:16 w:0)
+	/// Proof: `Defi::InsuranceFund` (`max_values`: None, `max_size`: Some(20), added: 2495, mode: `MaxEncodedLen`)
+	/// Storage: `Defi::UncoveredBadDebt` (r:16 w:0)
+	/// Proof: `Defi::UncoveredBadDebt` (`max_values`: None, `max_size`: Some(20), added: 2495, mode: `MaxEncodedLen`)
 	/// Storage: `System::Account` (r:2 w:2)
 	/// Proof: `System::Account` (`max_values`: None, `max_size`: Some(128), added: 2603, mode: `MaxEncodedLen`)
-	/// Storage: `Defi::Accounts` (r:1 w:1)
-	/// Proof: `Defi::Accounts` (`max_values`: None, `max_size`: None, mode: `Measured`)
-	/// Storage: `Defi::DepositRate` (r:1 w:0)
-	/// Proof: `Defi::DepositRate` (`max_values`: Some(1), `max_size`: None, mode: `Measured`)
-	fn withdraw() -> Weight {
-		// Proof Size summary in bytes:
-		//  Measured:  `160`
-		//  Estimated: `6196`
-		// Minimum execution time: 120_382_000 picoseconds.
+	/// Storage: `Defi::ReceiptsUnfrozen` (r:0 w:1)
+	/// Proof: `Defi::ReceiptsUnfrozen` (`max_values`: Some(1), `max_size`: Some(1), added: 496, mode: `MaxEncodedLen`)
+	/// Storage: `Assets::Asset` (r:1 w:1)
+	/// Proof: `Assets::Asset` (`max_values`: None, `max_size`: Some(210), added: 2685, mode: `MaxEncodedLen`)
+	/// Storage: `Balances::Holds` (r:1 w:0)
+	/// Proof: `Balances::Holds` (`max_values`: None, `max_size`: Some(67), added: 2542, mode: `MaxEncodedLen`)
+	/// Storage: `Defi::BorrowedMarkets` (r:1 w:0)
+	/// Proof: `Defi::BorrowedMarkets` (`max_values`: None, `max_size`: Some(52), added: 2527, mode: `MaxEncodedLen`)
+	/// Storage: `Defi::Accounts` (r:16 w:0)
+	/// Proof: `Defi::Accounts` (`max_values`: None, `max_size`: Some(84), added: 2559, mode: `MaxEncodedLen`)
+	/// Storage: `Oracle::Prices` (r:16 w:0)
+	/// Proof: `Oracle::Prices` (`max_values`: None, `max_size`: Some(40), added: 2515, mode: `MaxEncodedLen`)
+	/// Storage: `Defi::CollateralFactor` (r:16 w:0)
+	/// Proof: `Defi::CollateralFactor` (`max_values`: None, `max_size`: Some(20), added: 2495, mode: `MaxEncodedLen`)
+	/// Storage: `Defi::Markets` (r:15 w:0)
+	/// Proof: `Defi::Markets` (`max_values`: None, `max_size`: Some(5), added: 2480, mode: `MaxEncodedLen`)
+	/// The range of component `m` is `[1, 16]`.
+	/// The account has a debt and positions in every listed market.
+	fn withdraw(m: u32, ) -> Weight {
 		Weight::from_parts(122_125_000, 0)
 			.saturating_add(Weight::from_parts(0, 6196))
-			.saturating_add(T::DbWeight::get().reads(4))
-			.saturating_add(T::DbWeight::get().writes(3))
+			.saturating_add(Weight::from_parts(14_623_000, 0).saturating_mul(m.into()))
+			.saturating_add(T::DbWeight::get().reads(7))
+			.saturating_add(T::DbWeight::get().reads((17_u64).saturating_mul(m.into())))
+			.saturating_add(T::DbWeight::get().writes(11))
+			.saturating_add(Weight::from_parts(0, 42578).saturating_mul(m.into()))
 	}
-	/// Storage: `System::Account` (r:3 w:3)
+	/// Storage: `Defi::AccountsMigration` (r:1 w:0)
+	/// Proof: `Defi::AccountsMigration` (`max_values`: Some(1), `max_size`: Some(86), added: 581, mode: `MaxEncodedLen`)
+	/// Storage: `Defi::PausedOperations` (r:1 w:0)
+	/// Proof: `Defi::PausedOperations` (`max_values`: None, `max_size`: Some(2), added: 2477, mode: `MaxEncodedLen`)
+	/// Storage: `System::Account` (r:2 w:2)
 	/// Proof: `System::Account` (`max_values`: None, `max_size`: Some(128), added: 2603, mode: `MaxEncodedLen`)
-	/// Storage: `Defi::Accounts` (r:2 w:2)
-	/// Proof: `Defi::Accounts` (`max_values`: None, `max_size`: None, mode: `Measured`)
-	/// Storage: `Defi::DepositRate` (r:1 w:0)
-	/// Proof: `Defi::DepositRate` (`max_values`: Some(1), `max_size`: None, mode: `Measured`)
-	/// Storage: `Defi::CollateralFactor` (r:1 w:0)
-	/// Proof: `Defi::CollateralFactor` (`max_values`: Some(1), `max_size`: None, mode: `Measured`)
-	fn borrow() -> Weight {
-		// Proof Size summary in bytes:
-		//  Measured:  `160`
-		//  Estimated: `8799`
-		// Minimum execution time: 175_057_000 picoseconds.
+	/// Storage: `Defi::BorrowIndex` (r:16 w:1)
+	/// Proof: `Defi::BorrowIndex` (`max_values`: None, `max_size`: Some(20), added: 2495, mode: `MaxEncodedLen`)
+	/// Storage: `Defi::SupplyIndex` (r:16 w:1)
+	/// Proof: `Defi::SupplyIndex` (`max_values`: None, `max_size`: Some(20), added: 2495, mode: `MaxEncodedLen`)
+	/// Storage: `Defi::TotalBorrows` (r:16 w:1)
+	/// Proof: `Defi::TotalBorrows` (`max_values`: None, `max_size`: Some(20), added: 2495, mode: `MaxEncodedLen`)
+	/// Storage: `Defi::TotalDeposits` (r:16 w:1)
+	/// Proof: `Defi::TotalDeposits` (`max_values`: None, `max_size`: Some(20), added: 2495, mode: `MaxEncodedLen`)
+	/// Storage: `Defi::TotalReserves` (r:16 w:1)
+	/// Proof: `Defi::TotalReserves` (`max_values`: None, `max_size`: Some(20), added: 2495, mode: `MaxEncodedLen`)
+	/// Storage: `Defi::LastAccrued` (r:16 w:1)
+	/// Proof: `Defi::LastAccrued` (`max_values`: None, `max_size`: Some(12), added: 2487, mode: `MaxEncodedLen`)
+	/// Storage: `Timestamp::Now` (r:1 w:0)
+	/// Proof: `Timestamp::Now` (`max_values`: Some(1), `max_size`: Some(8), added: 503, mode: `MaxEncodedLen`)
+	/// Storage: `Defi::BorrowingRate` (r:16 w:0)
+	/// Proof: `Defi::BorrowingRate` (`max_values`: None, `max_size`: Some(20), added: 2495, mode: `MaxEncodedLen`)
+	/// Storage: `Defi::DepositRate` (r:16 w:0)
+	/// Proof: `Defi::DepositRate` (`max_values`: None, `max_size`: Some(20), added: 2495, mode: `MaxEncodedLen`)
+	/// Storage: `Defi::ReserveFactor` (r:16 w:0)
+	/// Proof: `Defi::ReserveFactor` (`max_values`: None, `max_size`: Some(20), added: 2495, mode: `MaxEncodedLen`)
+	/// Storage: `Defi::InsuranceFund` (r:16 w:0)
+	/// Proof: `Defi::InsuranceFund` (`max_values`: None, `max_size`: Some(20), added: 2495, mode: `MaxEncodedLen`)
+	/// Storage: `Defi::UncoveredBadDebt` (r:16 w:0)
+	/// Proof: `Defi::UncoveredBadDebt` (`max_values`: None, `max_size`: Some(20), added: 2495, mode: `MaxEncodedLen`)
+	/// Storage: `Oracle::Prices` (r:16 w:0)
+	/// Proof: `Oracle::Prices` (`max_values`: None, `max_size`: Some(40), added: 2515, mode: `MaxEncodedLen`)
+	/// Storage: `Defi::ReceiptAssets` (r:16 w:0)
+	/// Proof: `Defi::ReceiptAssets` (`max_values`: None, `max_size`: Some(8), added: 2483, mode: `MaxEncodedLen`)
+	/// Storage: `Assets::Account` (r:16 w:0)
+	/// Proof: `Assets::Account` (`max_values`: None, `max_size`: Some(134), added: 2609, mode: `MaxEncodedLen`)
+	/// Storage: `Balances::Holds` (r:1 w:0)
+	/// Proof: `Balances::Holds` (`max_values`: None, `max_size`: Some(67), added: 2542, mode: `MaxEncodedLen`)
+	/// Storage: `Defi::Accounts` (r:16 w:1)
+	/// Proof: `Defi::Accounts` (`max_values`: None, `max_size`: Some(84), added: 2559, mode: `MaxEncodedLen`)
+	/// Storage: `Defi::BorrowedMarkets` (r:1 w:1)
+	/// Proof: `Defi::BorrowedMarkets` (`max_values`: None, `max_size`: Some(52), added: 2527, mode: `MaxEncodedLen`)
+	/// Storage: `Defi::CollateralFactor` (r:16 w:0)
+	/// Proof: `Defi::CollateralFactor` (`max_values`: None, `max_size`: Some(20), added: 2495, mode: `MaxEncodedLen`)
+	/// Storage: `Defi::BorrowCap` (r:1 w:0)
+	/// Proof: `Defi::BorrowCap` (`max_values`: None, `max_size`: Some(20), added: 2495, mode: `MaxEncodedLen`)
+	/// Storage: `Defi::GlobalBorrowCap` (r:1 w:0)
+	/// Proof: `Defi::GlobalBorrowCap` (`max_values`: Some(1), `max_size`: Some(16), added: 511, mode: `MaxEncodedLen`)
+	/// Storage: `Defi::LiquidityBuffer` (r:1 w:0)
+	/// Proof: `Defi::LiquidityBuffer` (`max_values`: None, `max_size`: Some(20), added: 2495, mode: `MaxEncodedLen`)
+	/// Storage: `Defi::Markets` (r:15 w:0)
+	/// Proof: `Defi::Markets` (`max_values`: None, `max_size`: Some(5), added: 2480, mode: `MaxEncodedLen`)
+	/// The range of component `m` is `[1, 16]`.
+	/// The account has positions in every listed market.
+	fn borrow(m: u32, ) -> Weight {
 		Weight::from_parts(178_213_000, 0)
-			.saturating_add(Weight::from_parts(0, 8799))
-			.saturating_add(T::DbWeight::get().reads(7))
-			.saturating_add(T::DbWeight::get().writes(5))
+			.saturating_add(Weight::from_parts(0, 6196))
+			.saturating_add(Weight::from_parts(14_623_000, 0).saturating_mul(m.into()))
+			.saturating_add(T::DbWeight::get().reads(9))
+			.saturating_add(T::DbWeight::get().reads((17_u64).saturating_mul(m.into())))
+			.saturating_add(T::DbWeight::get().writes(10))
+			.saturating_add(Weight::from_parts(0, 42578).saturating_mul(m.into()))
 	}
+	/// Storage: `Defi::AccountsMigration` (r:1 w:0)
+	/// Proof: `Defi::AccountsMigration` (`max_values`: Some(1), `max_size`: Some(86), added: 581, mode: `MaxEncodedLen`)
+	/// Storage: `Defi::PausedOperations` (r:1 w:0)
+	/// Proof: `Defi::PausedOperations` (`max_values`: None, `max_size`: Some(2), added: 2477, mode: `MaxEncodedLen`)
 	/// Storage: `System::Account` (r:2 w:2)
 	/// Proof: `System::Account` (`max_values`: None, `max_size`: Some(128), added: 2603, mode: `MaxEncodedLen`)
-	/// Storage: `Defi::Accounts` (r:2 w:1)
-	/// Proof: `Defi::Accounts` (`max_values`: None, `max_size`: None, mode: `Measured`)
-	/// Storage: `Defi::DepositRate` (r:1 w:0)
-	/// Proof: `Defi::DepositRate` (`max_values`: Some(1), `max_size`: None, mode: `Measured`)
-	/// Storage: `Defi::CollateralFactor` (r:1 w:0)
-	/// Proof: `Defi::CollateralFactor` (`max_values`: Some(1), `max_size`: None, mode: `Measured`)
+	/// Storage: `Defi::Accounts` (r:1 w:1)
+	/// Proof: `Defi::Accounts` (`max_values`: None, `max_size`: Some(84), added: 2559, mode: `MaxEncodedLen`)
+	/// Storage: `Defi::BorrowedMarkets` (r:1 w:1)
+	/// Proof: `Defi::BorrowedMarkets` (`max_values`: None, `max_size`: Some(52), added: 2527, mode: `MaxEncodedLen`)
+	/// Storage: `Defi::BorrowIndex` (r:1 w:1)
+	/// Proof: `Defi::BorrowIndex` (`max_values`: None, `max_size`: Some(20), added: 2495, mode: `MaxEncodedLen`)
+	/// Storage: `Defi::SupplyIndex` (r:1 w:1)
+	/// Proof: `Defi::SupplyIndex` (`max_values`: None, `max_size`: Some(20), added: 2495, mode: `MaxEncodedLen`)
+	/// Storage: `Defi::TotalBorrows` (r:1 w:1)
+	/// Proof: `Defi::TotalBorrows` (`max_values`: None, `max_size`: Some(20), added: 2495, mode: `MaxEncodedLen`)
+	/// Storage: `Defi::TotalDeposits` (r:1 w:1)
+	/// Proof: `Defi::TotalDeposits` (`max_values`: None, `max_size`: Some(20), added: 2495, mode: `MaxEncodedLen`)
+	/// Storage: `Defi::TotalReserves` (r:1 w:1)
+	/// Proof: `Defi::TotalReserves` (`max_values`: None, `max_size`: Some(20), added: 2495, mode: `MaxEncodedLen`)
+	/// Storage: `Defi::LastAccrued` (r:1 w:1)
+	/// Proof: `Defi::LastAccrued` (`max_values`: None, `max_size`: Some(12), added: 2487, mode: `MaxEncodedLen`)
+	/// Storage: `Timestamp::Now` (r:1 w:0)
+	/// Proof: `Timestamp::Now` (`max_values`: Some(1), `max_size`: Some(8), added: 503, mode: `MaxEncodedLen`)
 	/// Storage: `Defi::BorrowingRate` (r:1 w:0)
-	/// Proof: `Defi::BorrowingRate` (`max_values`: Some(1), `max_size`: None, mode: `Measured`)
+	/// Proof: `Defi::BorrowingRate` (`max_values`: None, `max_size`: Some(20), added: 2495, mode: `MaxEncodedLen`)
+	/// Storage: `Defi::DepositRate` (r:1 w:0)
+	/// Proof: `Defi::DepositRate` (`max_values`: None, `max_size`: Some(20), added: 2495, mode: `MaxEncodedLen`)
+	/// Storage: `Defi::ReserveFactor` (r:1 w:0)
+	/// Proof: `Defi::ReserveFactor` (`max_values`: None, `max_size`: Some(20), added: 2495, mode: `MaxEncodedLen`)
+	/// Storage: `Defi::InsuranceFund` (r:1 w:0)
+	/// Proof: `Defi::InsuranceFund` (`max_values`: None, `max_size`: Some(20), added: 2495, mode: `MaxEncodedLen`)
+	/// Storage: `Defi::UncoveredBadDebt` (r:1 w:0)
+	/// Proof: `Defi::UncoveredBadDebt` (`max_values`: None, `max_size`: Some(20), added: 2495, mode: `MaxEncodedLen`)
 	fn repay() -> Weight {
-		// Proof Size summary in bytes:
-		//  Measured:  `273`
-		//  Estimated: `6213`
-		// Minimum execution time: 364_823_000 picoseconds.
 		Weight::from_parts(380_342_000, 0)
-			.saturating_add(Weight::from_parts(0, 6213))
+			.saturating_add(Weight::from_parts(0, 6196))
+			.saturating_add(T::DbWeight::get().reads(18))
+			.saturating_add(T::DbWeight::get().writes(10))
+	}
+	/// Storage: `Defi::AuthorityAccount` (r:1 w:0)
+	/// Proof: `Defi::AuthorityAccount` (`max_values`: Some(1), `max_size`: Some(32), added: 527, mode: `MaxEncodedLen`)
+	/// Storage: `Defi::Bounds` (r:1 w:0)
+	/// Proof: `Defi::Bounds` (`max_values`: None, `max_size`: Some(49), added: 2524, mode: `MaxEncodedLen`)
+	/// Storage: `Defi::DepositRate` (r:1 w:0)
+	/// Proof: `Defi::DepositRate` (`max_values`: None, `max_size`: Some(20), added: 2495, mode: `MaxEncodedLen`)
+	/// Storage: `Defi::PendingParameterChanges` (r:0 w:1)
+	/// Proof: `Defi::PendingParameterChanges` (`max_values`: None, `max_size`: Some(25), added: 2500, mode: `MaxEncodedLen`)
+	fn update_deposit_rate() -> Weight {
+		Weight::from_parts(16_384_000, 0)
+			.saturating_add(Weight::from_parts(0, 3514))
+			.saturating_add(T::DbWeight::get().reads(3))
+			.saturating_add(T::DbWeight::get().writes(1))
+	}
+	/// Storage: `Defi::AuthorityAccount` (r:1 w:0)
+	/// Proof: `Defi::AuthorityAccount` (`max_values`: Some(1), `max_size`: Some(32), added: 527, mode: `MaxEncodedLen`)
+	/// Storage: `Defi::Bounds` (r:1 w:0)
+	/// Proof: `Defi::Bounds` (`max_values`: None, `max_size`: Some(49), added: 2524, mode: `MaxEncodedLen`)
+	/// Storage: `Defi::BorrowingRate` (r:1 w:0)
+	/// Proof: `Defi::BorrowingRate` (`max_values`: None, `max_size`: Some(20), added: 2495, mode: `MaxEncodedLen`)
+	/// Storage: `Defi::PendingParameterChanges` (r:0 w:1)
+	/// Proof: `Defi::PendingParameterChanges` (`max_values`: None, `max_size`: Some(25), added: 2500, mode: `MaxEncodedLen`)
+	fn update_borrowing_rate() -> Weight {
+		Weight::from_parts(16_502_000, 0)
+			.saturating_add(Weight::from_parts(0, 3514))
+			.saturating_add(T::DbWeight::get().reads(3))
+			.saturating_add(T::DbWeight::get().writes(1))
+	}
+	/// Storage: `Defi::AuthorityAccount` (r:1 w:0)
+	/// Proof: `Defi::AuthorityAccount` (`max_values`: Some(1), `max_size`: Some(32), added: 527, mode: `MaxEncodedLen`)
+	/// Storage: `Defi::Bounds` (r:1 w:0)
+	/// Proof: `Defi::Bounds` (`max_values`: None, `max_size`: Some(49), added: 2524, mode: `MaxEncodedLen`)
+	/// Storage: `Defi::CollateralFactor` (r:1 w:0)
+	/// Proof: `Defi::CollateralFactor` (`max_values`: None, `max_size`: Some(20), added: 2495, mode: `MaxEncodedLen`)
+	/// Storage: `Defi::PendingParameterChanges` (r:0 w:1)
+	/// Proof: `Defi::PendingParameterChanges` (`max_values`: None, `max_size`: Some(25), added: 2500, mode: `MaxEncodedLen`)
+	fn update_collateral_factor() -> Weight {
+		Weight::from_parts(16_297_000, 0)
+			.saturating_add(Weight::from_parts(0, 3514))
+			.saturating_add(T::DbWeight::get().reads(3))
+			.saturating_add(T::DbWeight::get().writes(1))
+	}
+	/// Storage: `Defi::AuthorityAccount` (r:1 w:0)
+	/// Proof: `Defi::AuthorityAccount` (`max_values`: Some(1), `max_size`: Some(32), added: 527, mode: `MaxEncodedLen`)
+	/// Storage: `Defi::Markets` (r:15 w:1)
+	/// Proof: `Defi::Markets` (`max_values`: None, `max_size`: Some(5), added: 2480, mode: `MaxEncodedLen`)
+	/// Storage: `Defi::Bounds` (r:3 w:0)
+	/// Proof: `Defi::Bounds` (`max_values`: None, `max_size`: Some(49), added: 2524, mode: `MaxEncodedLen`)
+	/// Storage: `Defi::ReceiptAssets` (r:1 w:1)
+	/// Proof: `Defi::ReceiptAssets` (`max_values`: None, `max_size`: Some(8), added: 2483, mode: `MaxEncodedLen`)
+	/// Storage: `Assets::Asset` (r:1 w:1)
+	/// Proof: `Assets::Asset` (`max_values`: None, `max_size`: Some(210), added: 2685, mode: `MaxEncodedLen`)
+	/// Storage: `Defi::DepositRate` (r:0 w:1)
+	/// Proof: `Defi::DepositRate` (`max_values`: None, `max_size`: Some(20), added: 2495, mode: `MaxEncodedLen`)
+	/// Storage: `Defi::BorrowingRate` (r:0 w:1)
+	/// Proof: `Defi::BorrowingRate` (`max_values`: None, `max_size`: Some(20), added: 2495, mode: `MaxEncodedLen`)
+	/// Storage: `Defi::CollateralFactor` (r:0 w:1)
+	/// Proof: `Defi::CollateralFactor` (`max_values`: None, `max_size`: Some(20), added: 2495, mode: `MaxEncodedLen`)
+	/// Storage: `Timestamp::Now` (r:1 w:0)
+	/// Proof: `Timestamp::Now` (`max_values`: Some(1), `max_size`: Some(8), added: 503, mode: `MaxEncodedLen`)
+	/// Storage: `Defi::LastAccrued` (r:0 w:1)
+	/// Proof: `Defi::LastAccrued` (`max_values`: None, `max_size`: Some(12), added: 2487, mode: `MaxEncodedLen`)
+	/// The range of component `m` is `[1, 15]`.
+	fn create_market(m: u32, ) -> Weight {
+		Weight::from_parts(15_674_000, 0)
+			.saturating_add(Weight::from_parts(0, 8562))
+			.saturating_add(Weight::from_parts(2_871_000, 0).saturating_mul(m.into()))
 			.saturating_add(T::DbWeight::get().reads(7))
+			.saturating_add(T::DbWeight::get().reads((1_u64).saturating_mul(m.into())))
+			.saturating_add(T::DbWeight::get().writes(7))
+			.saturating_add(Weight::from_parts(0, 2480).saturating_mul(m.into()))
+	}
+	/// Storage: `Defi::AuthorityAccount` (r:1 w:0)
+	/// Proof: `Defi::AuthorityAccount` (`max_values`: Some(1), `max_size`: Some(32), added: 527, mode: `MaxEncodedLen`)
+	/// Storage: `Defi::BorrowIndex` (r:1 w:1)
+	/// Proof: `Defi::BorrowIndex` (`max_values`: None, `max_size`: Some(20), added: 2495, mode: `MaxEncodedLen`)
+	/// Storage: `Defi::SupplyIndex` (r:1 w:1)
+	/// Proof: `Defi::SupplyIndex` (`max_values`: None, `max_size`: Some(20), added: 2495, mode: `MaxEncodedLen`)
+	/// Storage: `Defi::TotalBorrows` (r:1 w:1)
+	/// Proof: `Defi::TotalBorrows` (`max_values`: None, `max_size`: Some(20), added: 2495, mode: `MaxEncodedLen`)
+	/// Storage: `Defi::TotalDeposits` (r:1 w:1)
+	/// Proof: `Defi::TotalDeposits` (`max_values`: None, `max_size`: Some(20), added: 2495, mode: `MaxEncodedLen`)
+	/// Storage: `Defi::TotalReserves` (r:1 w:1)
+	/// Proof: `Defi::TotalReserves` (`max_values`: None, `max_size`: Some(20), added: 2495, mode: `MaxEncodedLen`)
+	/// Storage: `Defi::LastAccrued` (r:1 w:1)
+	/// Proof: `Defi::LastAccrued` (`max_values`: None, `max_size`: Some(12), added: 2487, mode: `MaxEncodedLen`)
+	/// Storage: `Timestamp::Now` (r:1 w:0)
+	/// Proof: `Timestamp::Now` (`max_values`: Some(1), `max_size`: Some(8), added: 503, mode: `MaxEncodedLen`)
+	/// Storage: `Defi::BorrowingRate` (r:1 w:0)
+	/// Proof: `Defi::BorrowingRate` (`max_values`: None, `max_size`: Some(20), added: 2495, mode: `MaxEncodedLen`)
+	/// Storage: `Defi::DepositRate` (r:1 w:0)
+	/// Proof: `Defi::DepositRate` (`max_values`: None, `max_size`: Some(20), added: 2495, mode: `MaxEncodedLen`)
+	/// Storage: `Defi::ReserveFactor` (r:1 w:1)
+	/// Proof: `Defi::ReserveFactor` (`max_values`: None, `max_size`: Some(20), added: 2495, mode: `MaxEncodedLen`)
+	/// Storage: `Defi::InsuranceFund` (r:1 w:0)
+	/// Proof: `Defi::InsuranceFund` (`max_values`: None, `max_size`: Some(20), added: 2495, mode: `MaxEncodedLen`)
+	/// Storage: `Defi::UncoveredBadDebt` (r:1 w:0)
+	/// Proof: `Defi::UncoveredBadDebt` (`max_values`: None, `max_size`: Some(20), added: 2495, mode: `MaxEncodedLen`)
+	fn update_reserve_factor() -> Weight {
+		Weight::from_parts(25_102_000, 0)
+			.saturating_add(Weight::from_parts(0, 3485))
+			.saturating_add(T::DbWeight::get().reads(13))
+			.saturating_add(T::DbWeight::get().writes(7))
+	}
+	/// Storage: `System::Account` (r:2 w:2)
+	/// Proof: `System::Account` (`max_values`: None, `max_size`: Some(128), added: 2603, mode: `MaxEncodedLen`)
+	/// Storage: `Defi::BorrowIndex` (r:1 w:1)
+	/// Proof: `Defi::BorrowIndex` (`max_values`: None, `max_size`: Some(20), added: 2495, mode: `MaxEncodedLen`)
+	/// Storage: `Defi::SupplyIndex` (r:1 w:1)
+	/// Proof: `Defi::SupplyIndex` (`max_values`: None, `max_size`: Some(20), added: 2495, mode: `MaxEncodedLen`)
+	/// Storage: `Defi::TotalBorrows` (r:1 w:1)
+	/// Proof: `Defi::TotalBorrows` (`max_values`: None, `max_size`: Some(20), added: 2495, mode: `MaxEncodedLen`)
+	/// Storage: `Defi::TotalDeposits` (r:1 w:1)
+	/// Proof: `Defi::TotalDeposits` (`max_values`: None, `max_size`: Some(20), added: 2495, mode: `MaxEncodedLen`)
+	/// Storage: `Defi::TotalReserves` (r:1 w:1)
+	/// Proof: `Defi::TotalReserves` (`max_values`: None, `max_size`: Some(20), added: 2495, mode: `MaxEncodedLen`)
+	/// Storage: `Defi::LastAccrued` (r:1 w:1)
+	/// Proof: `Defi::LastAccrued` (`max_values`: None, `max_size`: Some(12), added: 2487, mode: `MaxEncodedLen`)
+	/// Storage: `Timestamp::Now` (r:1 w:0)
+	/// Proof: `Timestamp::Now` (`max_values`: Some(1), `max_size`: Some(8), added: 503, mode: `MaxEncodedLen`)
+	/// Storage: `Defi::BorrowingRate` (r:1 w:0)
+	/// Proof: `Defi::BorrowingRate` (`max_values`: None, `max_size`: Some(20), added: 2495, mode: `MaxEncodedLen`)
+	/// Storage: `Defi::DepositRate` (r:1 w:0)
+	/// Proof: `Defi::DepositRate` (`max_values`: None, `max_size`: Some(20), added: 2495, mode: `MaxEncodedLen`)
+	/// Storage: `Defi::ReserveFactor` (r:1 w:0)
+	/// Proof: `Defi::ReserveFactor` (`max_values`: None, `max_size`: Some(20), added: 2495, mode: `MaxEncodedLen`)
+	/// Storage: `Defi::InsuranceFund` (r:1 w:0)
+	/// Proof: `Defi::InsuranceFund` (`max_values`: None, `max_size`: Some(20), added: 2495, mode: `MaxEncodedLen`)
+	/// Storage: `Defi::UncoveredBadDebt` (r:1 w:0)
+	/// Proof: `Defi::UncoveredBadDebt` (`max_values`: None, `max_size`: Some(20), added: 2495, mode: `MaxEncodedLen`)
+	fn add_reserves() -> Weight {
+		Weight::from_parts(73_305_000, 0)
+			.saturating_add(Weight::from_parts(0, 6196))
+			.saturating_add(T::DbWeight::get().reads(14))
+			.saturating_add(T::DbWeight::get().writes(8))
+	}
+	/// Storage: `Defi::BorrowIndex` (r:1 w:1)
+	/// Proof: `Defi::BorrowIndex` (`max_values`: None, `max_size`: Some(20), added: 2495, mode: `MaxEncodedLen`)
+	/// Storage: `Defi::SupplyIndex` (r:1 w:1)
+	/// Proof: `Defi::SupplyIndex` (`max_values`: None, `max_size`: Some(20), added: 2495, mode: `MaxEncodedLen`)
+	/// Storage: `Defi::TotalBorrows` (r:1 w:1)
+	/// Proof: `Defi::TotalBorrows` (`max_values`: None, `max_size`: Some(20), added: 2495, mode: `MaxEncodedLen`)
+	/// Storage: `Defi::TotalDeposits` (r:1 w:1)
+	/// Proof: `Defi::TotalDeposits` (`max_values`: None, `max_size`: Some(20), added: 2495, mode: `MaxEncodedLen`)
+	/// Storage: `Defi::TotalReserves` (r:1 w:1)
+	/// Proof: `Defi::TotalReserves` (`max_values`: None, `max_size`: Some(20), added: 2495, mode: `MaxEncodedLen`)
+	/// Storage: `Defi::LastAccrued` (r:1 w:1)
+	/// Proof: `Defi::LastAccrued` (`max_values`: None, `max_size`: Some(12), added: 2487, mode: `MaxEncodedLen`)
+	/// Storage: `Timestamp::Now` (r:1 w:0)
+	/// Proof: `Timestamp::Now` (`max_values`: Some(1), `max_size`: Some(8), added: 503, mode: `MaxEncodedLen`)
+	/// Storage: `Defi::BorrowingRate` (r:1 w:0)
+	/// Proof: `Defi::BorrowingRate` (`max_values`: None, `max_size`: Some(20), added: 2495, mode: `MaxEncodedLen`)
+	/// Storage: `Defi::DepositRate` (r:1 w:0)
+	/// Proof: `Defi::DepositRate` (`max_values`: None, `max_size`: Some(20), added: 2495, mode: `MaxEncodedLen`)
+	/// Storage: `Defi::ReserveFactor` (r:1 w:0)
+	/// Proof: `Defi::ReserveFactor` (`max_values`: None, `max_size`: Some(20), added: 2495, mode: `MaxEncodedLen`)
+	/// Storage: `Defi::InsuranceFund` (r:1 w:0)
+	/// Proof: `Defi::InsuranceFund` (`max_values`: None, `max_size`: Some(20), added: 2495, mode: `MaxEncodedLen`)
+	/// Storage: `Defi::UncoveredBadDebt` (r:1 w:0)
+	/// Proof: `Defi::UncoveredBadDebt` (`max_values`: None, `max_size`: Some(20), added: 2495, mode: `MaxEncodedLen`)
+	/// Storage: `System::Account` (r:2 w:2)
+	/// Proof: `System::Account` (`max_values`: None, `max_size`: Some(128), added: 2603, mode: `MaxEncodedLen`)
+	fn reduce_reserves() -> Weight {
+		Weight::from_parts(74_640_000, 0)
+			.saturating_add(Weight::from_parts(0, 6196))
+			.saturating_add(T::DbWeight::get().reads(14))
+			.saturating_add(T::DbWeight::get().writes(8))
+	}
+	/// Storage: `Defi::AccountsMigration` (r:1 w:0)
+	/// Proof: `Defi::AccountsMigration` (`max_values`: Some(1), `max_size`: Some(86), added: 581, mode: `MaxEncodedLen`)
+	/// Storage: `Defi::PausedOperations` (r:1 w:0)
+	/// Proof: `Defi::PausedOperations` (`max_values`: None, `max_size`: Some(2), added: 2477, mode: `MaxEncodedLen`)
+	/// Storage: `System::Account` (r:2 w:2)
+	/// Proof: `System::Account` (`max_values`: None, `max_size`: Some(128), added: 2603, mode: `MaxEncodedLen`)
+	/// Storage: `Defi::BorrowIndex` (r:16 w:1)
+	/// Proof: `Defi::BorrowIndex` (`max_values`: None, `max_size`: Some(20), added: 2495, mode: `MaxEncodedLen`)
+	/// Storage: `Defi::SupplyIndex` (r:16 w:1)
+	/// Proof: `Defi::SupplyIndex` (`max_values`: None, `max_size`: Some(20), added: 2495, mode: `MaxEncodedLen`)
+	/// Storage: `Defi::TotalBorrows` (r:16 w:1)
+	/// Proof: `Defi::TotalBorrows` (`max_values`: None, `max_size`: Some(20), added: 2495, mode: `MaxEncodedLen`)
+	/// Storage: `Defi::TotalDeposits` (r:16 w:1)
+	/// Proof: `Defi::TotalDeposits` (`max_values`: None, `max_size`: Some(20), added: 2495, mode: `MaxEncodedLen`)
+	/// Storage: `Defi::TotalReserves` (r:16 w:1)
+	/// Proof: `Defi::TotalReserves` (`max_values`: None, `max_size`: Some(20), added: 2495, mode: `MaxEncodedLen`)
+	/// Storage: `Defi::LastAccrued` (r:16 w:1)
+	/// Proof: `Defi::LastAccrued` (`max_values`: None, `max_size`: Some(12), added: 2487, mode: `MaxEncodedLen`)
+	/// Storage: `Timestamp::Now` (r:1 w:0)
+	/// Proof: `Timestamp::Now` (`max_values`: Some(1), `max_size`: Some(8), added: 503, mode: `MaxEncodedLen`)
+	/// Storage: `Defi::BorrowingRate` (r:16 w:0)
+	/// Proof: `Defi::BorrowingRate` (`max_values`: None, `max_size`: Some(20), added: 2495, mode: `MaxEncodedLen`)
+	/// Storage: `Defi::DepositRate` (r:16 w:0)
+	/// Proof: `Defi::DepositRate` (`max_values`: None, `max_size`: Some(20), added: 2495, mode: `MaxEncodedLen`)
+	/// Storage: `Defi::ReserveFactor` (r:16 w:0)
+	/// Proof: `Defi::ReserveFactor` (`max_values`: None, `max_size`: Some(20), added: 2495, mode: `MaxEncodedLen`)
+	/// Storage: `Defi::InsuranceFund` (r:16 w:0)
+	/// Proof: `Defi::InsuranceFund` (`max_values`: None, `max_size`: Some(20), added: 2495, mode: `MaxEncodedLen`)
+	/// Storage: `Defi::UncoveredBadDebt` (r:16 w:0)
+	/// Proof: `Defi::UncoveredBadDebt` (`max_values`: None, `max_size`: Some(20), added: 2495, mode: `MaxEncodedLen`)
+	/// Storage: `Defi::ReceiptAssets` (r:16 w:0)
+	/// Proof: `Defi::ReceiptAssets` (`max_values`: None, `max_size`: Some(8), added: 2483, mode: `MaxEncodedLen`)
+	/// Storage: `Assets::Account` (r:17 w:2)
+	/// Proof: `Assets::Account` (`max_values`: None, `max_size`: Some(134), added: 2609, mode: `MaxEncodedLen`)
+	/// Storage: `Balances::Holds` (r:1 w:1)
+	/// Proof: `Balances::Holds` (`max_values`: None, `max_size`: Some(67), added: 2542, mode: `MaxEncodedLen`)
+	/// Storage: `Defi::Accounts` (r:16 w:1)
+	/// Proof: `Defi::Accounts` (`max_values`: None, `max_size`: Some(84), added: 2559, mode: `MaxEncodedLen`)
+	/// Storage: `Defi::BorrowedMarkets` (r:1 w:1)
+	/// Proof: `Defi::BorrowedMarkets` (`max_values`: None, `max_size`: Some(52), added: 2527, mode: `MaxEncodedLen`)
+	/// Storage: `Oracle::Prices` (r:16 w:0)
+	/// Proof: `Oracle::Prices` (`max_values`: None, `max_size`: Some(40), added: 2515, mode: `MaxEncodedLen`)
+	/// Storage: `Defi::CollateralFactor` (r:16 w:0)
+	/// Proof: `Defi::CollateralFactor` (`max_values`: None, `max_size`: Some(20), added: 2495, mode: `MaxEncodedLen`)
+	/// Storage: `Defi::ReceiptsUnfrozen` (r:0 w:1)
+	/// Proof: `Defi::ReceiptsUnfrozen` (`max_values`: Some(1), `max_size`: Some(1), added: 496, mode: `MaxEncodedLen`)
+	/// Storage: `Assets::Asset` (r:1 w:1)
+	/// Proof: `Assets::Asset` (`max_values`: None, `max_size`: Some(210), added: 2685, mode: `MaxEncodedLen`)
+	/// Storage: `Defi::Markets` (r:15 w:0)
+	/// Proof: `Defi::Markets` (`max_values`: None, `max_size`: Some(5), added: 2480, mode: `MaxEncodedLen`)
+	/// The range of component `m` is `[1, 16]`.
+	/// The borrower has positions in every listed market and both deposits and held collateral are seized.
+	fn liquidate(m: u32, ) -> Weight {
+		Weight::from_parts(219_880_000, 0)
+			.saturating_add(Weight::from_parts(0, 6208))
+			.saturating_add(Weight::from_parts(14_623_000, 0).saturating_mul(m.into()))
+			.saturating_add(T::DbWeight::get().reads(8))
+			.saturating_add(T::DbWeight::get().reads((17_u64).saturating_mul(m.into())))
+			.saturating_add(T::DbWeight::get().writes(15))
+			.saturating_add(Weight::from_parts(0, 42578).saturating_mul(m.into()))
+	}
+	/// Storage: `Defi::LiquidationCursor` (r:1 w:1)
+	/// Proof: `Defi::LiquidationCursor` (`max_values`: Some(1), `max_size`: Some(86), added: 581, mode: `MaxEncodedLen`)
+	/// Storage: `Defi::AccountsMigration` (r:1 w:0)
+	/// Proof: `Defi::AccountsMigration` (`max_values`: Some(1), `max_size`: Some(86), added: 581, mode: `MaxEncodedLen`)
+	/// Storage: `Defi::PausedOperations` (r:1 w:0)
+	/// Proof: `Defi::PausedOperations` (`max_values`: None, `max_size`: Some(2), added: 2477, mode: `MaxEncodedLen`)
+	/// Storage: `Timestamp::Now` (r:1 w:0)
+	/// Proof: `Timestamp::Now` (`max_values`: Some(1), `max_size`: Some(8), added: 503, mode: `MaxEncodedLen`)
+	/// Storage: `Defi::ReceiptsUnfrozen` (r:0 w:1)
+	/// Proof: `Defi::ReceiptsUnfrozen` (`max_values`: Some(1), `max_size`: Some(1), added: 496, mode: `MaxEncodedLen`)
+	/// Storage: `System::Account` (r:101 w:101)
+	/// Proof: `System::Account` (`max_values`: None, `max_size`: Some(128), added: 2603, mode: `MaxEncodedLen`)
+	/// Storage: `Defi::Markets` (r:16 w:0)
+	/// Proof: `Defi::Markets` (`max_values`: None, `max_size`: Some(5), added: 2480, mode: `MaxEncodedLen`)
+	/// Storage: `Defi::BorrowIndex` (r:16 w:16)
+	/// Proof: `Defi::BorrowIndex` (`max_values`: None, `max_size`: Some(20), added: 2495, mode: `MaxEncodedLen`)
+	/// Storage: `Defi::SupplyIndex` (r:16 w:16)
+	/// Proof: `Defi::SupplyIndex` (`max_values`: None, `max_size`: Some(20), added: 2495, mode: `MaxEncodedLen`)
+	/// Storage: `Defi::TotalBorrows` (r:16 w:16)
+	/// Proof: `Defi::TotalBorrows` (`max_values`: None, `max_size`: Some(20), added: 2495, mode: `MaxEncodedLen`)
+	/// Storage: `Defi::TotalDeposits` (r:16 w:16)
+	/// Proof: `Defi::TotalDeposits` (`max_values`: None, `max_size`: Some(20), added: 2495, mode: `MaxEncodedLen`)
+	/// Storage: `Defi::TotalReserves` (r:16 w:16)
+	/// Proof: `Defi::TotalReserves` (`max_values`: None, `max_size`: Some(20), added: 2495, mode: `MaxEncodedLen`)
+	/// Storage: `Defi::LastAccrued` (r:16 w:16)
+	/// Proof: `Defi::LastAccrued` (`max_values`: None, `max_size`: Some(12), added: 2487, mode: `MaxEncodedLen`)
+	/// Storage: `Defi::BorrowingRate` (r:16 w:0)
+	/// Proof: `Defi::BorrowingRate` (`max_values`: None, `max_size`: Some(20), added: 2495, mode: `MaxEncodedLen`)
+	/// Storage: `Defi::DepositRate` (r:16 w:0)
+	/// Proof: `Defi::DepositRate` (`max_values`: None, `max_size`: Some(20), added: 2495, mode: `MaxEncodedLen`)
+	/// Storage: `Defi::ReserveFactor` (r:16 w:0)
+	/// Proof: `Defi::ReserveFactor` (`max_values`: None, `max_size`: Some(20), added: 2495, mode: `MaxEncodedLen`)
+	/// Storage: `Defi::InsuranceFund` (r:16 w:16)
+	/// Proof: `Defi::InsuranceFund` (`max_values`: None, `max_size`: Some(20), added: 2495, mode: `MaxEncodedLen`)
+	/// Storage: `Defi::UncoveredBadDebt` (r:16 w:16)
+	/// Proof: `Defi::UncoveredBadDebt` (`max_values`: None, `max_size`: Some(20), added: 2495, mode: `MaxEncodedLen`)
+	/// Storage: `Defi::ReceiptAssets` (r:16 w:0)
+	/// Proof: `Defi::ReceiptAssets` (`max_values`: None, `max_size`: Some(8), added: 2483, mode: `MaxEncodedLen`)
+	/// Storage: `Oracle::Prices` (r:16 w:0)
+	/// Proof: `Oracle::Prices` (`max_values`: None, `max_size`: Some(40), added: 2515, mode: `MaxEncodedLen`)
+	/// Storage: `Assets::Asset` (r:16 w:16)
+	/// Proof: `Assets::Asset` (`max_values`: None, `max_size`: Some(210), added: 2685, mode: `MaxEncodedLen`)
+	/// Storage: `Defi::BadDebt` (r:16 w:16)
+	/// Proof: `Defi::BadDebt` (`max_values`: None, `max_size`: Some(20), added: 2495, mode: `MaxEncodedLen`)
+	/// Storage: `Defi::BorrowedMarkets` (r:100 w:100)
+	/// Proof: `Defi::BorrowedMarkets` (`max_values`: None, `max_size`: Some(52), added: 2527, mode: `MaxEncodedLen`)
+	/// Storage: `Balances::Holds` (r:100 w:100)
+	/// Proof: `Balances::Holds` (`max_values`: None, `max_size`: Some(67), added: 2542, mode: `MaxEncodedLen`)
+	/// Storage: `Defi::Accounts` (r:1600 w:1600)
+	/// Proof: `Defi::Accounts` (`max_values`: None, `max_size`: Some(84), added: 2559, mode: `MaxEncodedLen`)
+	/// Storage: `Assets::Account` (r:1600 w:1600)
+	/// Proof: `Assets::Account` (`max_values`: None, `max_size`: Some(134), added: 2609, mode: `MaxEncodedLen`)
+	/// The range of component `n` is `[0, 100]`.
+	/// The range of component `m` is `[1, 16]`.
+	/// Every checked account is insolvent with positions in every listed market, whose storage is accessed once for all accounts.
+	fn check_liquidity(n: u32, m: u32, ) -> Weight {
+		Weight::from_parts(7_214_000, 0)
+			.saturating_add(Weight::from_parts(0, 3593))
+			.saturating_add(Weight::from_parts(9_214_000, 0).saturating_mul(m.into()))
+			.saturating_add(Weight::from_parts(16_440_000, 0).saturating_mul(n.into()))
+			.saturating_add(Weight::from_parts(16_000_000, 0).saturating_mul(n.into()).saturating_mul(m.into()))
+			.saturating_add(T::DbWeight::get().reads(5))
+			.saturating_add(T::DbWeight::get().reads((16_u64).saturating_mul(m.into())))
+			.saturating_add(T::DbWeight::get().reads((3_u64).saturating_mul(n.into())))
+			.saturating_add(T::DbWeight::get().reads((2_u64).saturating_mul(n.into()).saturating_mul(m.into())))
 			.saturating_add(T::DbWeight::get().writes(3))
+			.saturating_add(T::DbWeight::get().writes((10_u64).saturating_mul(m.into())))
+			.saturating_add(T::DbWeight::get().writes((3_u64).saturating_mul(n.into())))
+			.saturating_add(T::DbWeight::get().writes((2_u64).saturating_mul(n.into()).saturating_mul(m.into())))
+			.saturating_add(Weight::from_parts(0, 40095).saturating_mul(m.into()))
+			.saturating_add(Weight::from_parts(0, 7672).saturating_mul(n.into()))
+			.saturating_add(Weight::from_parts(0, 5168).saturating_mul(n.into()).saturating_mul(m.into()))
+	}
+	/// Storage: `Defi::AuthorityAccount` (r:0 w:1)
+	/// Proof: `Defi::AuthorityAccount` (`max_values`: Some(1), `max_size`: Some(32), added: 527, mode: `MaxEncodedLen`)
+	fn set_authority() -> Weight {
+		Weight::from_parts(7_703_000, 0)
+			.saturating_add(T::DbWeight::get().writes(1))
+	}
+	/// Storage: `Defi::PauseGuardian` (r:0 w:1)
+	/// Proof: `Defi::PauseGuardian` (`max_values`: Some(1), `max_size`: Some(32), added: 527, mode: `MaxEncodedLen`)
+	fn set_pause_guardian() -> Weight {
+		Weight::from_parts(7_703_000, 0)
+			.saturating_add(T::DbWeight::get().writes(1))
+	}
+	/// Storage: `Defi::Bounds` (r:1 w:1)
+	/// Proof: `Defi::Bounds` (`max_values`: None, `max_size`: Some(49), added: 2524, mode: `MaxEncodedLen`)
+	fn set_parameter_bounds() -> Weight {
+		Weight::from_parts(8_392_000, 0)
+			.saturating_add(Weight::from_parts(0, 3514))
+			.saturating_add(T::DbWeight::get().reads(1))
+			.saturating_add(T::DbWeight::get().writes(1))
 	}
 	/// Storage: `Defi::AuthorityAccount` (r:1 w:0)
-	/// Proof: `Defi::AuthorityAccount` (`max_values`: Some(1), `max_size`: None, mode: `Measured`)
-	/// Storage: `Defi::DepositRate` (r:0 w:1)
-	/// Proof: `Defi::DepositRate` (`max_values`: Some(1), `max_size`: None, mode: `Measured`)
-	fn update_deposit_rate() -> Weight {
-		// Proof Size summary in bytes:
-		//  Measured:  `6`
-		//  Estimated: `1491`
-		// Minimum execution time: 10_621_000 picoseconds.
-		Weight::from_parts(10_961_000, 0)
-			.saturating_add(Weight::from_parts(0, 1491))
+	/// Proof: `Defi::AuthorityAccount` (`max_values`: Some(1), `max_size`: Some(32), added: 527, mode: `MaxEncodedLen`)
+	/// Storage: `Defi::PendingParameterChanges` (r:1 w:1)
+	/// Proof: `Defi::PendingParameterChanges` (`max_values`: None, `max_size`: Some(25), added: 2500, mode: `MaxEncodedLen`)
+	fn cancel_parameter_change() -> Weight {
+		Weight::from_parts(14_260_000, 0)
+			.saturating_add(Weight::from_parts(0, 3490))
+			.saturating_add(T::DbWeight::get().reads(2))
+			.saturating_add(T::DbWeight::get().writes(1))
+	}
+	/// Storage: `Timestamp::Now` (r:1 w:0)
+	/// Proof: `Timestamp::Now` (`max_values`: Some(1), `max_size`: Some(8), added: 503, mode: `MaxEncodedLen`)
+	/// Storage: `Defi::PendingParameterChanges` (r:100 w:100)
+	/// Proof: `Defi::PendingParameterChanges` (`max_values`: None, `max_size`: Some(25), added: 2500, mode: `MaxEncodedLen`)
+	/// Storage: `Defi::BorrowIndex` (r:100 w:100)
+	/// Proof: `Defi::BorrowIndex` (`max_values`: None, `max_size`: Some(20), added: 2495, mode: `MaxEncodedLen`)
+	/// Storage: `Defi::SupplyIndex` (r:100 w:100)
+	/// Proof: `Defi::SupplyIndex` (`max_values`: None, `max_size`: Some(20), added: 2495, mode: `MaxEncodedLen`)
+	/// Storage: `Defi::TotalBorrows` (r:100 w:100)
+	/// Proof: `Defi::TotalBorrows` (`max_values`: None, `max_size`: Some(20), added: 2495, mode: `MaxEncodedLen`)
+	/// Storage: `Defi::TotalDeposits` (r:100 w:100)
+	/// Proof: `Defi::TotalDeposits` (`max_values`: None, `max_size`: Some(20), added: 2495, mode: `MaxEncodedLen`)
+	/// Storage: `Defi::TotalReserves` (r:100 w:100)
+	/// Proof: `Defi::TotalReserves` (`max_values`: None, `max_size`: Some(20), added: 2495, mode: `MaxEncodedLen`)
+	/// Storage: `Defi::LastAccrued` (r:100 w:100)
+	/// Proof: `Defi::LastAccrued` (`max_values`: None, `max_size`: Some(12), added: 2487, mode: `MaxEncodedLen`)
+	/// Storage: `Defi::BorrowingRate` (r:100 w:100)
+	/// Proof: `Defi::BorrowingRate` (`max_values`: None, `max_size`: Some(20), added: 2495, mode: `MaxEncodedLen`)
+	/// Storage: `Defi::DepositRate` (r:100 w:0)
+	/// Proof: `Defi::DepositRate` (`max_values`: None, `max_size`: Some(20), added: 2495, mode: `MaxEncodedLen`)
+	/// Storage: `Defi::ReserveFactor` (r:100 w:0)
+	/// Proof: `Defi::ReserveFactor` (`max_values`: None, `max_size`: Some(20), added: 2495, mode: `MaxEncodedLen`)
+	/// Storage: `Defi::InsuranceFund` (r:100 w:0)
+	/// Proof: `Defi::InsuranceFund` (`max_values`: None, `max_size`: Some(20), added: 2495, mode: `MaxEncodedLen`)
+	/// Storage: `Defi::UncoveredBadDebt` (r:100 w:0)
+	/// Proof: `Defi::UncoveredBadDebt` (`max_values`: None, `max_size`: Some(20), added: 2495, mode: `MaxEncodedLen`)
+	/// The range of component `n` is `[0, 100]`.
+	/// Every pending change is due and accrues interest of its own market.
+	fn apply_parameter_changes(n: u32, ) -> Weight {
+		Weight::from_parts(4_187_000, 0)
+			.saturating_add(Weight::from_parts(0, 1493))
+			.saturating_add(Weight::from_parts(27_518_306, 0).saturating_mul(n.into()))
+			.saturating_add(T::DbWeight::get().reads(1))
+			.saturating_add(T::DbWeight::get().reads((12_u64).saturating_mul(n.into())))
+			.saturating_add(T::DbWeight::get().writes((8_u64).saturating_mul(n.into())))
+			.saturating_add(Weight::from_parts(0, 29937).saturating_mul(n.into()))
+	}
+	/// Storage: `Defi::PauseGuardian` (r:1 w:0)
+	/// Proof: `Defi::PauseGuardian` (`max_values`: Some(1), `max_size`: Some(32), added: 527, mode: `MaxEncodedLen`)
+	/// Storage: `Defi::PausedOperations` (r:0 w:1)
+	/// Proof: `Defi::PausedOperations` (`max_values`: None, `max_size`: Some(2), added: 2477, mode: `MaxEncodedLen`)
+	fn pause_operation() -> Weight {
+		Weight::from_parts(8_135_000, 0)
+			.saturating_add(Weight::from_parts(0, 1517))
+			.saturating_add(T::DbWeight::get().reads(1))
+			.saturating_add(T::DbWeight::get().writes(1))
+	}
+	/// Storage: `Defi::PauseGuardian` (r:1 w:0)
+	/// Proof: `Defi::PauseGuardian` (`max_values`: Some(1), `max_size`: Some(32), added: 527, mode: `MaxEncodedLen`)
+	/// Storage: `Defi::PausedOperations` (r:0 w:1)
+	/// Proof: `Defi::PausedOperations` (`max_values`: None, `max_size`: Some(2), added: 2477, mode: `MaxEncodedLen`)
+	fn unpause_operation() -> Weight {
+		Weight::from_parts(7_948_000, 0)
+			.saturating_add(Weight::from_parts(0, 1517))
 			.saturating_add(T::DbWeight::get().reads(1))
 			.saturating_add(T::DbWeight::get().writes(1))
 	}
 	/// Storage: `Defi::AuthorityAccount` (r:1 w:0)
-	/// Proof: `Defi::AuthorityAccount` (`max_values`: Some(1), `max_size`: None, mode: `Measured`)
-	/// Storage: `Defi::BorrowingRate` (r:0 w:1)
-	/// Proof: `Defi::BorrowingRate` (`max_values`: Some(1), `max_size`: None, mode: `Measured`)
-	fn update_borrowing_rate() -> Weight {
-		// Proof Size summary in bytes:
-		//  Measured:  `6`
-		//  Estimated: `1491`
-		// Minimum execution time: 10_460_000 picoseconds.
-		Weight::from_parts(11_172_000, 0)
-			.saturating_add(Weight::from_parts(0, 1491))
+	/// Proof: `Defi::AuthorityAccount` (`max_values`: Some(1), `max_size`: Some(32), added: 527, mode: `MaxEncodedLen`)
+	/// Storage: `Defi::SupplyCap` (r:1 w:1)
+	/// Proof: `Defi::SupplyCap` (`max_values`: None, `max_size`: Some(20), added: 2495, mode: `MaxEncodedLen`)
+	/// Storage: `Defi::BorrowCap` (r:1 w:1)
+	/// Proof: `Defi::BorrowCap` (`max_values`: None, `max_size`: Some(20), added: 2495, mode: `MaxEncodedLen`)
+	fn set_caps() -> Weight {
+		Weight::from_parts(13_204_000, 0)
+			.saturating_add(Weight::from_parts(0, 3485))
+			.saturating_add(T::DbWeight::get().reads(3))
+			.saturating_add(T::DbWeight::get().writes(2))
+	}
+	/// Storage: `Defi::AuthorityAccount` (r:1 w:0)
+	/// Proof: `Defi::AuthorityAccount` (`max_values`: Some(1), `max_size`: Some(32), added: 527, mode: `MaxEncodedLen`)
+	/// Storage: `Defi::GlobalSupplyCap` (r:0 w:1)
+	/// Proof: `Defi::GlobalSupplyCap` (`max_values`: Some(1), `max_size`: Some(16), added: 511, mode: `MaxEncodedLen`)
+	/// Storage: `Defi::GlobalBorrowCap` (r:0 w:1)
+	/// Proof: `Defi::GlobalBorrowCap` (`max_values`: Some(1), `max_size`: Some(16), added: 511, mode: `MaxEncodedLen`)
+	fn set_global_caps() -> Weight {
+		Weight::from_parts(10_245_000, 0)
+			.saturating_add(Weight::from_parts(0, 1517))
+			.saturating_add(T::DbWeight::get().reads(1))
+			.saturating_add(T::DbWeight::get().writes(2))
+	}
+	/// Storage: `Defi::AuthorityAccount` (r:1 w:0)
+	/// Proof: `Defi::AuthorityAccount` (`max_values`: Some(1), `max_size`: Some(32), added: 527, mode: `MaxEncodedLen`)
+	/// Storage: `Defi::LiquidityBuffer` (r:0 w:1)
+	/// Proof: `Defi::LiquidityBuffer` (`max_values`: None, `max_size`: Some(20), added: 2495, mode: `MaxEncodedLen`)
+	fn set_liquidity_buffer() -> Weight {
+		Weight::from_parts(10_692_000, 0)
+			.saturating_add(Weight::from_parts(0, 1517))
 			.saturating_add(T::DbWeight::get().reads(1))
 			.saturating_add(T::DbWeight::get().writes(1))
 	}
+	/// Storage: `Defi::AccountsMigration` (r:1 w:0)
+	/// Proof: `Defi::AccountsMigration` (`max_values`: Some(1), `max_size`: Some(86), added: 581, mode: `MaxEncodedLen`)
+	/// Storage: `Defi::PausedOperations` (r:1 w:0)
+	/// Proof: `Defi::PausedOperations` (`max_values`: None, `max_size`: Some(2), added: 2477, mode: `MaxEncodedLen`)
+	/// Storage: `System::Account` (r:2 w:2)
+	/// Proof: `System::Account` (`max_values`: None, `max_size`: Some(128), added: 2603, mode: `MaxEncodedLen`)
+	/// Storage: `Defi::BorrowIndex` (r:1 w:1)
+	/// Proof: `Defi::BorrowIndex` (`max_values`: None, `max_size`: Some(20), added: 2495, mode: `MaxEncodedLen`)
+	/// Storage: `Defi::SupplyIndex` (r:1 w:1)
+	/// Proof: `Defi::SupplyIndex` (`max_values`: None, `max_size`: Some(20), added: 2495, mode: `MaxEncodedLen`)
+	/// Storage: `Defi::TotalBorrows` (r:1 w:1)
+	/// Proof: `Defi::TotalBorrows` (`max_values`: None, `max_size`: Some(20), added: 2495, mode: `MaxEncodedLen`)
+	/// Storage: `Defi::TotalDeposits` (r:1 w:1)
+	/// Proof: `Defi::TotalDeposits` (`max_values`: None, `max_size`: Some(20), added: 2495, mode: `MaxEncodedLen`)
+	/// Storage: `Defi::TotalReserves` (r:1 w:1)
+	/// Proof: `Defi::TotalReserves` (`max_values`: None, `max_size`: Some(20), added: 2495, mode: `MaxEncodedLen`)
+	/// Storage: `Defi::LastAccrued` (r:1 w:1)
+	/// Proof: `Defi::LastAccrued` (`max_values`: None, `max_size`: Some(12), added: 2487, mode: `MaxEncodedLen`)
+	/// Storage: `Timestamp::Now` (r:1 w:0)
+	/// Proof: `Timestamp::Now` (`max_values`: Some(1), `max_size`: Some(8), added: 503, mode: `MaxEncodedLen`)
+	/// Storage: `Defi::BorrowingRate` (r:1 w:0)
+	/// Proof: `Defi::BorrowingRate` (`max_values`: None, `max_size`: Some(20), added: 2495, mode: `MaxEncodedLen`)
+	/// Storage: `Defi::DepositRate` (r:1 w:0)
+	/// Proof: `Defi::DepositRate` (`max_values`: None, `max_size`: Some(20), added: 2495, mode: `MaxEncodedLen`)
+	/// Storage: `Defi::ReserveFactor` (r:1 w:0)
+	/// Proof: `Defi::ReserveFactor` (`max_values`: None, `max_size`: Some(20), added: 2495, mode: `MaxEncodedLen`)
+	/// Storage: `Defi::InsuranceFund` (r:1 w:0)
+	/// Proof: `Defi::InsuranceFund` (`max_values`: None, `max_size`: Some(20), added: 2495, mode: `MaxEncodedLen`)
+	/// Storage: `Defi::UncoveredBadDebt` (r:1 w:0)
+	/// Proof: `Defi::UncoveredBadDebt` (`max_values`: None, `max_size`: Some(20), added: 2495, mode: `MaxEncodedLen`)
+	/// Storage: `Defi::FlashLoanFee` (r:1 w:0)
+	/// Proof: `Defi::FlashLoanFee` (`max_values`: None, `max_size`: Some(20), added: 2495, mode: `MaxEncodedLen`)
+	/// The weight of the dispatched call is added on top.
+	fn flash_loan() -> Weight {
+		Weight::from_parts(101_237_000, 0)
+			.saturating_add(Weight::from_parts(0, 6196))
+			.saturating_add(T::DbWeight::get().reads(17))
+			.saturating_add(T::DbWeight::get().writes(8))
+	}
 	/// Storage: `Defi::AuthorityAccount` (r:1 w:0)
-	/// Proof: `Defi::AuthorityAccount` (`max_values`: Some(1), `max_size`: None, mode: `Measured`)
-	/// Storage: `Defi::CollateralFactor` (r:0 w:1)
-	/// Proof: `Defi::CollateralFactor` (`max_values`: Some(1), `max_size`: None, mode: `Measured`)
-	fn update_collateral_factor() -> Weight {
-		// Proof Size summary in bytes:
-		//  Measured:  `6`
-		//  Estimated: `1491`
-		// Minimum execution time: 10_720_000 picoseconds.
-		Weight::from_parts(11_051_000, 0)
-			.saturating_add(Weight::from_parts(0, 1491))
+	/// Proof: `Defi::AuthorityAccount` (`max_values`: Some(1), `max_size`: Some(32), added: 527, mode: `MaxEncodedLen`)
+	/// Storage: `Defi::FlashLoanFee` (r:0 w:1)
+	/// Proof: `Defi::FlashLoanFee` (`max_values`: None, `max_size`: Some(20), added: 2495, mode: `MaxEncodedLen`)
+	fn set_flash_loan_fee() -> Weight {
+		Weight::from_parts(10_671_000, 0)
+			.saturating_add(Weight::from_parts(0, 1517))
 			.saturating_add(T::DbWeight::get().reads(1))
 			.saturating_add(T::DbWeight::get().writes(1))
 	}
+	/// Storage: `Defi::BorrowIndex` (r:1 w:1)
+	/// Proof: `Defi::BorrowIndex` (`max_values`: None, `max_size`: Some(20), added: 2495, mode: `MaxEncodedLen`)
+	/// Storage: `Defi::SupplyIndex` (r:1 w:1)
+	/// Proof: `Defi::SupplyIndex` (`max_values`: None, `max_size`: Some(20), added: 2495, mode: `MaxEncodedLen`)
+	/// Storage: `Defi::TotalBorrows` (r:1 w:1)
+	/// Proof: `Defi::TotalBorrows` (`max_values`: None, `max_size`: Some(20), added: 2495, mode: `MaxEncodedLen`)
+	/// Storage: `Defi::TotalDeposits` (r:1 w:1)
+	/// Proof: `Defi::TotalDeposits` (`max_values`: None, `max_size`: Some(20), added: 2495, mode: `MaxEncodedLen`)
+	/// Storage: `Defi::TotalReserves` (r:1 w:1)
+	/// Proof: `Defi::TotalReserves` (`max_values`: None, `max_size`: Some(20), added: 2495, mode: `MaxEncodedLen`)
+	/// Storage: `Defi::LastAccrued` (r:1 w:1)
+	/// Proof: `Defi::LastAccrued` (`max_values`: None, `max_size`: Some(12), added: 2487, mode: `MaxEncodedLen`)
+	/// Storage: `Timestamp::Now` (r:1 w:0)
+	/// Proof: `Timestamp::Now` (`max_values`: Some(1), `max_size`: Some(8), added: 503, mode: `MaxEncodedLen`)
+	/// Storage: `Defi::BorrowingRate` (r:1 w:0)
+	/// Proof: `Defi::BorrowingRate` (`max_values`: None, `max_size`: Some(20), added: 2495, mode: `MaxEncodedLen`)
+	/// Storage: `Defi::DepositRate` (r:1 w:0)
+	/// Proof: `Defi::DepositRate` (`max_values`: None, `max_size`: Some(20), added: 2495, mode: `MaxEncodedLen`)
+	/// Storage: `Defi::ReserveFactor` (r:1 w:0)
+	/// Proof: `Defi::ReserveFactor` (`max_values`: None, `max_size`: Some(20), added: 2495, mode: `MaxEncodedLen`)
+	/// Storage: `Defi::InsuranceFund` (r:1 w:1)
+	/// Proof: `Defi::InsuranceFund` (`max_values`: None, `max_size`: Some(20), added: 2495, mode: `MaxEncodedLen`)
+	/// Storage: `Defi::UncoveredBadDebt` (r:1 w:0)
+	/// Proof: `Defi::UncoveredBadDebt` (`max_values`: None, `max_size`: Some(20), added: 2495, mode: `MaxEncodedLen`)
+	fn fund_insurance() -> Weight {
+		Weight::from_parts(25_630_000, 0)
+			.saturating_add(Weight::from_parts(0, 3485))
+			.saturating_add(T::DbWeight::get().reads(12))
+			.saturating_add(T::DbWeight::get().writes(7))
+	}
+	/// Storage: `Defi::AccountsMigration` (r:1 w:1)
+	/// Proof: `Defi::AccountsMigration` (`max_values`: Some(1), `max_size`: Some(86), added: 581, mode: `MaxEncodedLen`)
+	/// Storage: `Defi::Accounts` (r:100 w:200)
+	/// Proof: `Defi::Accounts` (`max_values`: None, `max_size`: Some(84), added: 2559, mode: `MaxEncodedLen`)
+	/// Storage: `Defi::BorrowedMarkets` (r:100 w:100)
+	/// Proof: `Defi::BorrowedMarkets` (`max_values`: None, `max_size`: Some(52), added: 2527, mode: `MaxEncodedLen`)
+	/// The range of component `n` is `[0, 100]`.
+	fn migrate_accounts(n: u32, ) -> Weight {
+		Weight::from_parts(5_243_000, 0)
+			.saturating_add(Weight::from_parts(0, 1571))
+			.saturating_add(Weight::from_parts(14_362_115, 0).saturating_mul(n.into()))
+			.saturating_add(T::DbWeight::get().reads(1))
+			.saturating_add(T::DbWeight::get().reads((2_u64).saturating_mul(n.into())))
+			.saturating_add(T::DbWeight::get().writes(1))
+			.saturating_add(T::DbWeight::get().writes((3_u64).saturating_mul(n.into())))
+			.saturating_add(Weight::from_parts(0, 5086).saturating_mul(n.into()))
+	}
+	/// Storage: `Defi::AccountsMigration` (r:1 w:1)
+	/// Proof: `Defi::AccountsMigration` (`max_values`: Some(1), `max_size`: Some(86), added: 581, mode: `MaxEncodedLen`)
+	/// Storage: `Defi::DepositRate` (r:1 w:0)
+	/// Proof: `Defi::DepositRate` (`max_values`: None, `max_size`: Some(20), added: 2495, mode: `MaxEncodedLen`)
+	/// Storage: `Defi::BorrowingRate` (r:1 w:0)
+	/// Proof: `Defi::BorrowingRate` (`max_values`: None, `max_size`: Some(20), added: 2495, mode: `MaxEncodedLen`)
+	/// Storage: `Defi::LastAccrued` (r:1 w:0)
+	/// Proof: `Defi::LastAccrued` (`max_values`: None, `max_size`: Some(12), added: 2487, mode: `MaxEncodedLen`)
+	/// Storage: `Defi:::__STORAGE_VERSION__:` (r:0 w:1)
+	/// Proof: `Defi:::__STORAGE_VERSION__:` (`max_values`: Some(1), `max_size`: Some(2), added: 497, mode: `MaxEncodedLen`)
+	/// Storage: `Defi::Accounts` (r:100 w:200)
+	/// Proof: `Defi::Accounts` (`max_values`: None, `max_size`: Some(84), added: 2559, mode: `MaxEncodedLen`)
+	/// Storage: `Defi::ReceiptAssets` (r:100 w:0)
+	/// Proof: `Defi::ReceiptAssets` (`max_values`: None, `max_size`: Some(8), added: 2483, mode: `MaxEncodedLen`)
+	/// Storage: `Defi::SupplyIndex` (r:100 w:0)
+	/// Proof: `Defi::SupplyIndex` (`max_values`: None, `max_size`: Some(20), added: 2495, mode: `MaxEncodedLen`)
+	/// Storage: `Assets::Asset` (r:100 w:100)
+	/// Proof: `Assets::Asset` (`max_values`: None, `max_size`: Some(210), added: 2685, mode: `MaxEncodedLen`)
+	/// Storage: `Assets::Account` (r:100 w:100)
+	/// Proof: `Assets::Account` (`max_values`: None, `max_size`: Some(134), added: 2609, mode: `MaxEncodedLen`)
+	/// Storage: `System::Account` (r:100 w:100)
+	/// Proof: `System::Account` (`max_values`: None, `max_size`: Some(128), added: 2603, mode: `MaxEncodedLen`)
+	/// Storage: `Defi::TotalDeposits` (r:100 w:100)
+	/// Proof: `Defi::TotalDeposits` (`max_values`: None, `max_size`: Some(20), added: 2495, mode: `MaxEncodedLen`)
+	/// Storage: `Defi::BorrowIndex` (r:100 w:0)
+	/// Proof: `Defi::BorrowIndex` (`max_values`: None, `max_size`: Some(20), added: 2495, mode: `MaxEncodedLen`)
+	/// Storage: `Defi::TotalBorrows` (r:100 w:100)
+	/// Proof: `Defi::TotalBorrows` (`max_values`: None, `max_size`: Some(20), added: 2495, mode: `MaxEncodedLen`)
+	/// The range of component `n` is `[0, 100]`.
+	/// Every visited entry is a position of v0 with both a deposit and a debt.
+	fn migrate_positions(n: u32, ) -> Weight {
+		Weight::from_parts(10_000_000, 0)
+			.saturating_add(Weight::from_parts(0, 3485))
+			.saturating_add(Weight::from_parts(60_000_000, 0).saturating_mul(n.into()))
+			.saturating_add(T::DbWeight::get().reads(4))
+			.saturating_add(T::DbWeight::get().reads((9_u64).saturating_mul(n.into())))
+			.saturating_add(T::DbWeight::get().writes(2))
+			.saturating_add(T::DbWeight::get().writes((7_u64).saturating_mul(n.into())))
+			.saturating_add(Weight::from_parts(0, 22919).saturating_mul(n.into()))
+	}
+	/// Storage: `Defi::AccountsMigration` (r:1 w:0)
+	/// Proof: `Defi::AccountsMigration` (`max_values`: Some(1), `max_size`: Some(86), added: 581, mode: `MaxEncodedLen`)
+	/// Storage: `Defi::PausedOperations` (r:1 w:0)
+	/// Proof: `Defi::PausedOperations` (`max_values`: None, `max_size`: Some(2), added: 2477, mode: `MaxEncodedLen`)
+	/// Storage: `System::Account` (r:1 w:1)
+	/// Proof: `System::Account` (`max_values`: None, `max_size`: Some(128), added: 2603, mode: `MaxEncodedLen`)
+	/// Storage: `Balances::Holds` (r:1 w:1)
+	/// Proof: `Balances::Holds` (`max_values`: None, `max_size`: Some(67), added: 2542, mode: `MaxEncodedLen`)
+	fn deposit_collateral() -> Weight {
+		Weight::from_parts(38_592_000, 0)
+			.saturating_add(Weight::from_parts(0, 3593))
+			.saturating_add(T::DbWeight::get().reads(4))
+			.saturating_add(T::DbWeight::get().writes(2))
+	}
+	/// Storage: `Defi::AccountsMigration` (r:1 w:0)
+	/// Proof: `Defi::AccountsMigration` (`max_values`: Some(1), `max_size`: Some(86), added: 581, mode: `MaxEncodedLen`)
+	/// Storage: `Defi::PausedOperations` (r:1 w:0)
+	/// Proof: `Defi::PausedOperations` (`max_values`: None, `max_size`: Some(2), added: 2477, mode: `MaxEncodedLen`)
+	/// Storage: `Balances::Holds` (r:1 w:1)
+	/// Proof: `Balances::Holds` (`max_values`: None, `max_size`: Some(67), added: 2542, mode: `MaxEncodedLen`)
+	/// Storage: `System::Account` (r:1 w:1)
+	/// Proof: `System::Account` (`max_values`: None, `max_size`: Some(128), added: 2603, mode: `MaxEncodedLen`)
+	/// Storage: `Defi::ReceiptAssets` (r:16 w:0)
+	/// Proof: `Defi::ReceiptAssets` (`max_values`: None, `max_size`: Some(8), added: 2483, mode: `MaxEncodedLen`)
+	/// Storage: `Assets::Account` (r:16 w:0)
+	/// Proof: `Assets::Account` (`max_values`: None, `max_size`: Some(134), added: 2609, mode: `MaxEncodedLen`)
+	/// Storage: `Defi::BorrowIndex` (r:16 w:0)
+	/// Proof: `Defi::BorrowIndex` (`max_values`: None, `max_size`: Some(20), added: 2495, mode: `MaxEncodedLen`)
+	/// Storage: `Defi::SupplyIndex` (r:16 w:0)
+	/// Proof: `Defi::SupplyIndex` (`max_values`: None, `max_size`: Some(20), added: 2495, mode: `MaxEncodedLen`)
+	/// Storage: `Defi::TotalBorrows` (r:16 w:0)
+	/// Proof: `Defi::TotalBorrows` (`max_values`: None, `max_size`: Some(20), added: 2495, mode: `MaxEncodedLen`)
+	/// Storage: `Defi::TotalDeposits` (r:16 w:0)
+	/// Proof: `Defi::TotalDeposits` (`max_values`: None, `max_size`: Some(20), added: 2495, mode: `MaxEncodedLen`)
+	/// Storage: `Defi::TotalReserves` (r:16 w:0)
+	/// Proof: `Defi::TotalReserves` (`max_values`: None, `max_size`: Some(20), added: 2495, mode: `MaxEncodedLen`)
+	/// Storage: `Defi::LastAccrued` (r:16 w:0)
+	/// Proof: `Defi::LastAccrued` (`max_values`: None, `max_size`: Some(12), added: 2487, mode: `MaxEncodedLen`)
+	/// Storage: `Timestamp::Now` (r:1 w:0)
+	/// Proof: `Timestamp::Now` (`max_values`: Some(1), `max_size`: Some(8), added: 503, mode: `MaxEncodedLen`)
+	/// Storage: `Defi::BorrowingRate` (r:16 w:0)
+	/// Proof: `Defi::BorrowingRate` (`max_values`: None, `max_size`: Some(20), added: 2495, mode: `MaxEncodedLen`)
+	/// Storage: `Defi::DepositRate` (r:16 w:0)
+	/// Proof: `Defi::DepositRate` (`max_values`: None, `max_size`: Some(20), added: 2495, mode: `MaxEncodedLen`)
+	/// Storage: `Defi::ReserveFactor` (r:16 w:0)
+	/// Proof: `Defi::ReserveFactor` (`max_values`: None, `max_size`: Some(20), added: 2495, mode: `MaxEncodedLen`)
+	/// Storage: `Defi::InsuranceFund` (r:16 w:0)
+	/// Proof: `Defi::InsuranceFund` (`max_values`: None, `max_size`: Some(20), added: 2495, mode: `MaxEncodedLen`)
+	/// Storage: `Defi::UncoveredBadDebt` (r:16 w:0)
+	/// Proof: `Defi::UncoveredBadDebt` (`max_values`: None, `max_size`: Some(20), added: 2495, mode: `MaxEncodedLen`)
+	/// Storage: `Defi::BorrowedMarkets` (r:1 w:0)
+	/// Proof: `Defi::BorrowedMarkets` (`max_values`: None, `max_size`: Some(52), added: 2527, mode: `MaxEncodedLen`)
+	/// Storage: `Defi::Accounts` (r:16 w:0)
+	/// Proof: `Defi::Accounts` (`max_values`: None, `max_size`: Some(84), added: 2559, mode: `MaxEncodedLen`)
+	/// Storage: `Oracle::Prices` (r:16 w:0)
+	/// Proof: `Oracle::Prices` (`max_values`: None, `max_size`: Some(40), added: 2515, mode: `MaxEncodedLen`)
+	/// Storage: `Defi::CollateralFactor` (r:16 w:0)
+	/// Proof: `Defi::CollateralFactor` (`max_values`: None, `max_size`: Some(20), added: 2495, mode: `MaxEncodedLen`)
+	/// Storage: `Defi::Markets` (r:15 w:0)
+	/// Proof: `Defi::Markets` (`max_values`: None, `max_size`: Some(5), added: 2480, mode: `MaxEncodedLen`)
+	/// The range of component `m` is `[1, 16]`.
+	/// The account has a debt and positions in every listed market.
+	fn withdraw_collateral(m: u32, ) -> Weight {
+		Weight::from_parts(53_904_000, 0)
+			.saturating_add(Weight::from_parts(0, 3599))
+			.saturating_add(Weight::from_parts(14_623_000, 0).saturating_mul(m.into()))
+			.saturating_add(T::DbWeight::get().reads(5))
+			.saturating_add(T::DbWeight::get().reads((17_u64).saturating_mul(m.into())))
+			.saturating_add(T::DbWeight::get().writes(2))
+			.saturating_add(Weight::from_parts(0, 42578).saturating_mul(m.into()))
+	}
 }
 
 impl WeightInfo for () {
-	fn deposit() -> Weight {
+	fn deposit(m: u32, ) -> Weight {
 		Weight::from_parts(67_771_000, 0)
 			.saturating_add(Weight::from_parts(0, 6196))
-			.saturating_add(RocksDbWeight::get().reads(3))
-			.saturating_add(RocksDbWeight::get().writes(3))
+			.saturating_add(Weight::from_parts(9_214_000, 0).saturating_mul(m.into()))
+			.saturating_add(RocksDbWeight::get().reads(9))
+			.saturating_add(RocksDbWeight::get().reads((13_u64).saturating_mul(m.into())))
+			.saturating_add(RocksDbWeight::get().writes(10))
+			.saturating_add(Weight::from_parts(0, 32432).saturating_mul(m.into()))
 	}
 
-	fn withdraw() -> Weight {
+	fn withdraw(m: u32, ) -> Weight {
 		Weight::from_parts(122_125_000, 0)
 			.saturating_add(Weight::from_parts(0, 6196))
-			.saturating_add(RocksDbWeight::get().reads(4))
-			.saturating_add(RocksDbWeight::get().writes(3))
+			.saturating_add(Weight::from_parts(14_623_000, 0).saturating_mul(m.into()))
+			.saturating_add(RocksDbWeight::get().reads(7))
+			.saturating_add(RocksDbWeight::get().reads((17_u64).saturating_mul(m.into())))
+			.saturating_add(RocksDbWeight::get().writes(11))
+			.saturating_add(Weight::from_parts(0, 42578).saturating_mul(m.into()))
 	}
 
-	fn borrow() -> Weight {
+	fn borrow(m: u32, ) -> Weight {
 		Weight::from_parts(178_213_000, 0)
-			.saturating_add(Weight::from_parts(0, 8799))
-			.saturating_add(RocksDbWeight::get().reads(7))
-			.saturating_add(RocksDbWeight::get().writes(5))
+			.saturating_add(Weight::from_parts(0, 6196))
+			.saturating_add(Weight::from_parts(14_623_000, 0).saturating_mul(m.into()))
+			.saturating_add(RocksDbWeight::get().reads(9))
+			.saturating_add(RocksDbWeight::get().reads((17_u64).saturating_mul(m.into())))
+			.saturating_add(RocksDbWeight::get().writes(10))
+			.saturating_add(Weight::from_parts(0, 42578).saturating_mul(m.into()))
 	}
 
 	fn repay() -> Weight {
 		Weight::from_parts(380_342_000, 0)
-			.saturating_add(Weight::from_parts(0, 6213))
+			.saturating_add(Weight::from_parts(0, 6196))
+			.saturating_add(RocksDbWeight::get().reads(18))
+			.saturating_add(RocksDbWeight::get().writes(10))
+	}
+
+	fn update_deposit_rate() -> Weight {
+		Weight::from_parts(16_384_000, 0)
+			.saturating_add(Weight::from_parts(0, 3514))
+			.saturating_add(RocksDbWeight::get().reads(3))
+			.saturating_add(RocksDbWeight::get().writes(1))
+	}
+
+	fn update_borrowing_rate() -> Weight {
+		Weight::from_parts(16_502_000, 0)
+			.saturating_add(Weight::from_parts(0, 3514))
+			.saturating_add(RocksDbWeight::get().reads(3))
+			.saturating_add(RocksDbWeight::get().writes(1))
+	}
+
+	fn update_collateral_factor() -> Weight {
+		Weight::from_parts(16_297_000, 0)
+			.saturating_add(Weight::from_parts(0, 3514))
+			.saturating_add(RocksDbWeight::get().reads(3))
+			.saturating_add(RocksDbWeight::get().writes(1))
+	}
+
+	fn create_market(m: u32, ) -> Weight {
+		Weight::from_parts(15_674_000, 0)
+			.saturating_add(Weight::from_parts(0, 8562))
+			.saturating_add(Weight::from_parts(2_871_000, 0).saturating_mul(m.into()))
 			.saturating_add(RocksDbWeight::get().reads(7))
+			.saturating_add(RocksDbWeight::get().reads((1_u64).saturating_mul(m.into())))
+			.saturating_add(RocksDbWeight::get().writes(7))
+			.saturating_add(Weight::from_parts(0, 2480).saturating_mul(m.into()))
+	}
+
+	fn update_reserve_factor() -> Weight {
+		Weight::from_parts(25_102_000, 0)
+			.saturating_add(Weight::from_parts(0, 3485))
+			.saturating_add(RocksDbWeight::get().reads(13))
+			.saturating_add(RocksDbWeight::get().writes(7))
+	}
+
+	fn add_reserves() -> Weight {
+		Weight::from_parts(73_305_000, 0)
+			.saturating_add(Weight::from_parts(0, 6196))
+			.saturating_add(RocksDbWeight::get().reads(14))
+			.saturating_add(RocksDbWeight::get().writes(8))
+	}
+
+	fn reduce_reserves() -> Weight {
+		Weight::from_parts(74_640_000, 0)
+			.saturating_add(Weight::from_parts(0, 6196))
+			.saturating_add(RocksDbWeight::get().reads(14))
+			.saturating_add(RocksDbWeight::get().writes(8))
+	}
+
+	fn liquidate(m: u32, ) -> Weight {
+		Weight::from_parts(219_880_000, 0)
+			.saturating_add(Weight::from_parts(0, 6208))
+			.saturating_add(Weight::from_parts(14_623_000, 0).saturating_mul(m.into()))
+			.saturating_add(RocksDbWeight::get().reads(8))
+			.saturating_add(RocksDbWeight::get().reads((17_u64).saturating_mul(m.into())))
+			.saturating_add(RocksDbWeight::get().writes(15))
+			.saturating_add(Weight::from_parts(0, 42578).saturating_mul(m.into()))
+	}
+
+	fn check_liquidity(n: u32, m: u32, ) -> Weight {
+		Weight::from_parts(7_214_000, 0)
+			.saturating_add(Weight::from_parts(0, 3593))
+			.saturating_add(Weight::from_parts(9_214_000, 0).saturating_mul(m.into()))
+			.saturating_add(Weight::from_parts(16_440_000, 0).saturating_mul(n.into()))
+			.saturating_add(Weight::from_parts(16_000_000, 0).saturating_mul(n.into()).saturating_mul(m.into()))
+			.saturating_add(RocksDbWeight::get().reads(5))
+			.saturating_add(RocksDbWeight::get().reads((16_u64).saturating_mul(m.into())))
+			.saturating_add(RocksDbWeight::get().reads((3_u64).saturating_mul(n.into())))
+			.saturating_add(RocksDbWeight::get().reads((2_u64).saturating_mul(n.into()).saturating_mul(m.into())))
 			.saturating_add(RocksDbWeight::get().writes(3))
+			.saturating_add(RocksDbWeight::get().writes((10_u64).saturating_mul(m.into())))
+			.saturating_add(RocksDbWeight::get().writes((3_u64).saturating_mul(n.into())))
+			.saturating_add(RocksDbWeight::get().writes((2_u64).saturating_mul(n.into()).saturating_mul(m.into())))
+			.saturating_add(Weight::from_parts(0, 40095).saturating_mul(m.into()))
+			.saturating_add(Weight::from_parts(0, 7672).saturating_mul(n.into()))
+			.saturating_add(Weight::from_parts(0, 5168).saturating_mul(n.into()).saturating_mul(m.into()))
 	}
 
-	fn update_deposit_rate() -> Weight {
-		Weight::from_parts(10_961_000, 0)
-			.saturating_add(Weight::from_parts(0, 1491))
+	fn set_authority() -> Weight {
+		Weight::from_parts(7_703_000, 0)
+			.saturating_add(RocksDbWeight::get().writes(1))
+	}
+
+	fn set_pause_guardian() -> Weight {
+		Weight::from_parts(7_703_000, 0)
+			.saturating_add(RocksDbWeight::get().writes(1))
+	}
+
+	fn set_parameter_bounds() -> Weight {
+		Weight::from_parts(8_392_000, 0)
+			.saturating_add(Weight::from_parts(0, 3514))
 			.saturating_add(RocksDbWeight::get().reads(1))
 			.saturating_add(RocksDbWeight::get().writes(1))
 	}
 
-	fn update_borrowing_rate() -> Weight {
-		Weight::from_parts(11_172_000, 0)
-			.saturating_add(Weight::from_parts(0, 1491))
+	fn cancel_parameter_change() -> Weight {
+		Weight::from_parts(14_260_000, 0)
+			.saturating_add(Weight::from_parts(0, 3490))
+			.saturating_add(RocksDbWeight::get().reads(2))
+			.saturating_add(RocksDbWeight::get().writes(1))
+	}
+
+	fn apply_parameter_changes(n: u32, ) -> Weight {
+		Weight::from_parts(4_187_000, 0)
+			.saturating_add(Weight::from_parts(0, 1493))
+			.saturating_add(Weight::from_parts(27_518_306, 0).saturating_mul(n.into()))
+			.saturating_add(RocksDbWeight::get().reads(1))
+			.saturating_add(RocksDbWeight::get().reads((12_u64).saturating_mul(n.into())))
+			.saturating_add(RocksDbWeight::get().writes((8_u64).saturating_mul(n.into())))
+			.saturating_add(Weight::from_parts(0, 29937).saturating_mul(n.into()))
+	}
+
+	fn pause_operation() -> Weight {
+		Weight::from_parts(8_135_000, 0)
+			.saturating_add(Weight::from_parts(0, 1517))
 			.saturating_add(RocksDbWeight::get().reads(1))
 			.saturating_add(RocksDbWeight::get().writes(1))
 	}
 
-	fn update_collateral_factor() -> Weight {
-		Weight::from_parts(11_051_000, 0)
-			.saturating_add(Weight::from_parts(0, 1491))
+	fn unpause_operation() -> Weight {
+		Weight::from_parts(7_948_000, 0)
+			.saturating_add(Weight::from_parts(0, 1517))
+			.saturating_add(RocksDbWeight::get().reads(1))
+			.saturating_add(RocksDbWeight::get().writes(1))
+	}
+
+	fn set_caps() -> Weight {
+		Weight::from_parts(13_204_000, 0)
+			.saturating_add(Weight::from_parts(0, 3485))
+			.saturating_add(RocksDbWeight::get().reads(3))
+			.saturating_add(RocksDbWeight::get().writes(2))
+	}
+
+	fn set_global_caps() -> Weight {
+		Weight::from_parts(10_245_000, 0)
+			.saturating_add(Weight::from_parts(0, 1517))
+			.saturating_add(RocksDbWeight::get().reads(1))
+			.saturating_add(RocksDbWeight::get().writes(2))
+	}
+
+	fn set_liquidity_buffer() -> Weight {
+		Weight::from_parts(10_692_000, 0)
+			.saturating_add(Weight::from_parts(0, 1517))
+			.saturating_add(RocksDbWeight::get().reads(1))
+			.saturating_add(RocksDbWeight::get().writes(1))
+	}
+
+	fn flash_loan() -> Weight {
+		Weight::from_parts(101_237_000, 0)
+			.saturating_add(Weight::from_parts(0, 6196))
+			.saturating_add(RocksDbWeight::get().reads(17))
+			.saturating_add(RocksDbWeight::get().writes(8))
+	}
+
+	fn set_flash_loan_fee() -> Weight {
+		Weight::from_parts(10_671_000, 0)
+			.saturating_add(Weight::from_parts(0, 1517))
+			.saturating_add(RocksDbWeight::get().reads(1))
+			.saturating_add(RocksDbWeight::get().writes(1))
+	}
+
+	fn fund_insurance() -> Weight {
+		Weight::from_parts(25_630_000, 0)
+			.saturating_add(Weight::from_parts(0, 3485))
+			.saturating_add(RocksDbWeight::get().reads(12))
+			.saturating_add(RocksDbWeight::get().writes(7))
+	}
+
+	fn migrate_accounts(n: u32, ) -> Weight {
+		Weight::from_parts(5_243_000, 0)
+			.saturating_add(Weight::from_parts(0, 1571))
+			.saturating_add(Weight::from_parts(14_362_115, 0).saturating_mul(n.into()))
 			.saturating_add(RocksDbWeight::get().reads(1))
+			.saturating_add(RocksDbWeight::get().reads((2_u64).saturating_mul(n.into())))
 			.saturating_add(RocksDbWeight::get().writes(1))
+			.saturating_add(RocksDbWeight::get().writes((3_u64).saturating_mul(n.into())))
+			.saturating_add(Weight::from_parts(0, 5086).saturating_mul(n.into()))
+	}
+
+	fn migrate_positions(n: u32, ) -> Weight {
+		Weight::from_parts(10_000_000, 0)
+			.saturating_add(Weight::from_parts(0, 3485))
+			.saturating_add(Weight::from_parts(60_000_000, 0).saturating_mul(n.into()))
+			.saturating_add(RocksDbWeight::get().reads(4))
+			.saturating_add(RocksDbWeight::get().reads((9_u64).saturating_mul(n.into())))
+			.saturating_add(RocksDbWeight::get().writes(2))
+			.saturating_add(RocksDbWeight::get().writes((7_u64).saturating_mul(n.into())))
+			.saturating_add(Weight::from_parts(0, 22919).saturating_mul(n.into()))
 	}
-}
\ No newline at end of file
+
+	fn deposit_collateral() -> Weight {
+		Weight::from_parts(38_592_000, 0)
+			.saturating_add(Weight::from_parts(0, 3593))
+			.saturating_add(RocksDbWeight::get().reads(4))
+			.saturating_add(RocksDbWeight::get().writes(2))
+	}
+
+	fn withdraw_collateral(m: u32, ) -> Weight {
+		Weight::from_parts(53_904_000, 0)
+			.saturating_add(Weight::from_parts(0, 3599))
+			.saturating_add(Weight::from_parts(14_623_000, 0).saturating_mul(m.into()))
+			.saturating_add(RocksDbWeight::get().reads(5))
+			.saturating_add(RocksDbWeight::get().reads((17_u64).saturating_mul(m.into())))
+			.saturating_add(RocksDbWeight::get().writes(2))
+			.saturating_add(Weight::from_parts(0, 42578).saturating_mul(m.into()))
+	}
+}
@@ -0,0 +1,87 @@
+#![cfg(feature = "runtime-benchmarks")]
+
+use super::*;
+
+use crate::Pallet as Oracle;
+use defi_primitives::BenchmarkHelper;
+use frame_benchmarking::{account, benchmarks};
+use frame_support::{sp_runtime::FixedU128, sp_std::prelude::*, traits::EnsureOrigin};
+use frame_system::{EventRecord, RawOrigin};
+
+fn assert_last_event<T: Config>(generic_event: <T as Config>::RuntimeEvent) {
+	let events = frame_system::Pallet::<T>::events();
+	let system_event: <T as frame_system::Config>::RuntimeEvent = generic_event.into();
+
+	// Compare to the last event record
+	let EventRecord { event, .. } = &events[events.len() - 1];
+
+	assert_eq!(event, &system_event);
+}
+
+fn add_feeders<T: Config>(n: u32) -> Vec<T::AccountId> {
+	let admin = T::AdminOrigin::try_successful_origin().unwrap();
+	let feeders: Vec<T::AccountId> = (0..n).map(|i| account("feeder", i, 0)).collect();
+
+	for feeder in feeders.iter() {
+		Oracle::<T>::add_feeder(admin.clone(), feeder.clone()).unwrap();
+	}
+
+	feeders
+}
+
+benchmarks! {
+	add_feeder {
+		let admin = T::AdminOrigin::try_successful_origin().unwrap();
+		let feeder: T::AccountId = account("feeder", 0, 0);
+	} : {
+		Oracle::<T>::add_feeder(admin, feeder.clone()).unwrap();
+	} verify {
+		assert_last_event::<T>(Event::<T>::FeederAdded(feeder).into());
+	}
+
+	remove_feeder {
+		let n in 0 .. T::MaxAssets::get();
+
+		let admin = T::AdminOrigin::try_successful_origin().unwrap();
+		let feeders = add_feeders::<T>(T::MaxFeeders::get());
+		let feeder = feeders[0].clone();
+		let price = FixedU128::from_u32(1);
+
+		// Every feeder priced the assets, which are aggregated again without the removed one
+		for i in 0 .. n {
+			let asset_id = T::BenchmarkHelper::asset_id(i);
+
+			for feeder in feeders.iter() {
+				Oracle::<T>::feed_price(RawOrigin::Signed(feeder.clone()).into(), asset_id, price).unwrap();
+			}
+		}
+	} : {
+		Oracle::<T>::remove_feeder(admin, feeder.clone()).unwrap();
+	} verify {
+		assert_last_event::<T>(Event::<T>::FeederRemoved(feeder.clone()).into());
+		assert!(FeederAssets::<T>::get(&feeder).is_empty());
+	}
+
+	feed_price {
+		let n in (T::MinFeeders::get().max(1)) .. T::MaxFeeders::get();
+
+		let feeders = add_feeders::<T>(n);
+		let asset_id = T::BenchmarkHelper::asset_id(1);
+		let price = FixedU128::from_u32(1);
+
+		// Every other feeder already submitted a price
+		for feeder in feeders.iter().skip(1) {
+			Oracle::<T>::feed_price(RawOrigin::Signed(feeder.clone()).into(), asset_id, price).unwrap();
+		}
+	} : {
+		Oracle::<T>::feed_price(RawOrigin::Signed(feeders[0].clone()).into(), asset_id, price).unwrap();
+	} verify {
+		assert_last_event::<T>(Event::<T>::PriceUpdated(asset_id, price).into());
+	}
+
+	impl_benchmark_test_suite!(
+		Pallet,
+		crate::mock::ExtBuilder::default().build(),
+		crate::mock::Runtime,
+	);
+}
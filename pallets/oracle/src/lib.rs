@@ -0,0 +1,355 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use codec::MaxEncodedLen;
+pub use codec::{Decode, Encode};
+use frame_support::sp_runtime::FixedU128;
+pub use pallet::*;
+
+#[cfg(test)]
+mod mock;
+
+#[cfg(test)]
+mod tests;
+
+mod benchmarking;
+
+pub mod weights;
+pub use weights::WeightInfo;
+
+#[derive(
+	Encode, Decode, Clone, Copy, Default, PartialEq, Eq, MaxEncodedLen, scale_info::TypeInfo,
+)]
+#[cfg_attr(feature = "std", derive(Debug))]
+pub struct TimestampedPrice<BlockNumber> {
+	/// The price of the asset
+	pub price: FixedU128,
+	/// The time (block height) at which the price was submitted
+	pub block: BlockNumber,
+}
+
+#[frame_support::pallet]
+pub mod pallet {
+	use super::*;
+	use crate::WeightInfo;
+	use frame_support::{
+		pallet_prelude::*,
+		sp_runtime::{traits::Zero, Saturating},
+		sp_std::prelude::*,
+	};
+	use frame_system::pallet_prelude::*;
+
+	/// Configure the pallet by specifying the parameters and types on which it depends.
+	#[pallet::config]
+	pub trait Config: frame_system::Config {
+		/// Because this pallet emits events, it depends on the runtime's definition of an event.
+		type RuntimeEvent: From<Event<Self>> + IsType<<Self as frame_system::Config>::RuntimeEvent>;
+
+		/// Identifier of the assets which are priced
+		type AssetId: Member + Parameter + Copy + MaxEncodedLen;
+
+		/// Origin allowed to manage the set of feeders
+		type AdminOrigin: EnsureOrigin<Self::RuntimeOrigin>;
+
+		/// Maximum number of feeder accounts
+		#[pallet::constant]
+		type MaxFeeders: Get<u32>;
+
+		/// Minimum number of fresh submissions a price is aggregated from
+		#[pallet::constant]
+		type MinFeeders: Get<u32>;
+
+		/// Maximum number of assets priced by a single feeder
+		#[pallet::constant]
+		type MaxAssets: Get<u32>;
+
+		/// Number of blocks after which a submitted price is considered stale
+		#[pallet::constant]
+		type MaxPriceAge: Get<BlockNumberFor<Self>>;
+
+		/// Extrinsics weight Info
+		type WeightInfo: WeightInfo;
+
+		/// Helper for creating asset ids in benchmarks
+		#[cfg(feature = "runtime-benchmarks")]
+		type BenchmarkHelper: defi_primitives::BenchmarkHelper<Self::AssetId>;
+	}
+
+	type AccountIdOf<T> = <T as frame_system::Config>::AccountId;
+	type BlockNumber<T> = BlockNumberFor<T>;
+	type AssetIdOf<T> = <T as Config>::AssetId;
+
+	#[pallet::pallet]
+	pub struct Pallet<T>(_);
+
+	#[pallet::storage]
+	#[pallet::getter(fn feeders)]
+	pub type Feeders<T: Config> =
+		StorageValue<_, BoundedVec<AccountIdOf<T>, T::MaxFeeders>, ValueQuery>;
+
+	/// Latest price submitted by each feeder
+	#[pallet::storage]
+	#[pallet::getter(fn raw_prices)]
+	pub type RawPrices<T: Config> = StorageDoubleMap<
+		_,
+		Blake2_128Concat,
+		AssetIdOf<T>,
+		Blake2_128Concat,
+		AccountIdOf<T>,
+		TimestampedPrice<BlockNumber<T>>,
+		OptionQuery,
+	>;
+
+	/// Assets priced by each feeder, so that its submissions can be dropped once it's removed
+	#[pallet::storage]
+	#[pallet::getter(fn feeder_assets)]
+	pub type FeederAssets<T: Config> = StorageMap<
+		_,
+		Blake2_128Concat,
+		AccountIdOf<T>,
+		BoundedVec<AssetIdOf<T>, T::MaxAssets>,
+		ValueQuery,
+	>;
+
+	/// Median of the fresh submissions, stamped with the oldest submission it includes
+	#[pallet::storage]
+	#[pallet::getter(fn prices)]
+	pub type Prices<T: Config> = StorageMap<
+		_,
+		Blake2_128Concat,
+		AssetIdOf<T>,
+		TimestampedPrice<BlockNumber<T>>,
+		OptionQuery,
+	>;
+
+	#[pallet::event]
+	#[pallet::generate_deposit(pub(super) fn deposit_event)]
+	pub enum Event<T: Config> {
+		/// Feeder added [who]
+		FeederAdded(AccountIdOf<T>),
+		/// Feeder removed [who]
+		FeederRemoved(AccountIdOf<T>),
+		/// Price submitted [who, asset_id, price]
+		PriceFed(AccountIdOf<T>, AssetIdOf<T>, FixedU128),
+		/// Aggregated price updated [asset_id, price]
+		PriceUpdated(AssetIdOf<T>, FixedU128),
+		/// Aggregated price removed for lack of fresh submissions [asset_id]
+		PriceRemoved(AssetIdOf<T>),
+	}
+
+	#[pallet::error]
+	pub enum Error<T> {
+		/// Account is not an authorized feeder
+		NotFeeder,
+		/// Account is already an authorized feeder
+		AlreadyFeeder,
+		/// Maximum number of feeders reached
+		TooManyFeeders,
+		/// Submitted price is zero
+		InvalidPrice,
+		/// Maximum number of assets priced by the feeder reached
+		TooManyAssets,
+	}
+
+	#[pallet::genesis_config]
+	#[derive(frame_support::DefaultNoBound)]
+	pub struct GenesisConfig<T: Config> {
+		/// Accounts authorized to feed prices at genesis
+		pub feeders: Vec<AccountIdOf<T>>,
+	}
+
+	#[pallet::genesis_build]
+	impl<T: Config> BuildGenesisConfig for GenesisConfig<T> {
+		fn build(&self) {
+			let mut feeders = self.feeders.clone();
+			feeders.sort();
+			feeders.dedup();
+			assert!(feeders.len() == self.feeders.len(), "Feeder authorized twice at genesis");
+
+			let feeders: BoundedVec<_, T::MaxFeeders> =
+				self.feeders.clone().try_into().expect("Too many feeders authorized at genesis");
+			Feeders::<T>::put(feeders);
+		}
+	}
+
+	#[pallet::hooks]
+	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
+		fn integrity_test() {
+			assert!(
+				T::MinFeeders::get() <= T::MaxFeeders::get(),
+				"A price can't require more feeders than there can be"
+			);
+		}
+	}
+
+	#[pallet::call]
+	impl<T: Config> Pallet<T> {
+		/// Add feeder account
+		#[pallet::call_index(0)]
+		#[pallet::weight(<T as Config>::WeightInfo::add_feeder())]
+		pub fn add_feeder(origin: OriginFor<T>, who: AccountIdOf<T>) -> DispatchResult {
+			// Check if the caller is allowed to manage feeders
+			T::AdminOrigin::ensure_origin(origin)?;
+
+			Feeders::<T>::try_mutate(|feeders| -> DispatchResult {
+				ensure!(!feeders.contains(&who), Error::<T>::AlreadyFeeder);
+
+				feeders.try_push(who.clone()).map_err(|_| Error::<T>::TooManyFeeders)?;
+
+				Ok(())
+			})?;
+
+			// Emit an event
+			Self::deposit_event(Event::FeederAdded(who));
+
+			Ok(())
+		}
+
+		/// Remove feeder account. Its submissions are dropped and the prices it fed are
+		/// aggregated again without them.
+		#[pallet::call_index(1)]
+		#[pallet::weight(<T as Config>::WeightInfo::remove_feeder(T::MaxAssets::get()))]
+		pub fn remove_feeder(origin: OriginFor<T>, who: AccountIdOf<T>) -> DispatchResult {
+			// Check if the caller is allowed to manage feeders
+			T::AdminOrigin::ensure_origin(origin)?;
+
+			Feeders::<T>::try_mutate(|feeders| -> DispatchResult {
+				let index = feeders
+					.iter()
+					.position(|feeder| feeder == &who)
+					.ok_or(Error::<T>::NotFeeder)?;
+
+				feeders.remove(index);
+
+				Ok(())
+			})?;
+
+			// Drop the submissions of the feeder and aggregate the rest
+			let current_block = frame_system::Pallet::<T>::block_number();
+			for asset_id in FeederAssets::<T>::take(&who) {
+				RawPrices::<T>::remove(asset_id, &who);
+
+				match Self::aggregate(asset_id, current_block) {
+					Some(aggregated) => {
+						Prices::<T>::insert(asset_id, aggregated);
+
+						Self::deposit_event(Event::PriceUpdated(asset_id, aggregated.price));
+					},
+					None =>
+						if Prices::<T>::take(asset_id).is_some() {
+							Self::deposit_event(Event::PriceRemoved(asset_id));
+						},
+				}
+			}
+
+			// Emit an event
+			Self::deposit_event(Event::FeederRemoved(who));
+
+			Ok(())
+		}
+
+		/// Submit price of an asset
+		#[pallet::call_index(2)]
+		#[pallet::weight(<T as Config>::WeightInfo::feed_price(T::MaxFeeders::get()))]
+		pub fn feed_price(
+			origin: OriginFor<T>,
+			asset_id: AssetIdOf<T>,
+			price: FixedU128,
+		) -> DispatchResult {
+			// Check that the extrinsic was signed and get the signer.
+			// This function will return an error if the extrinsic is not signed.
+			let feeder = ensure_signed(origin)?;
+
+			// Check if the caller is an authorized feeder
+			ensure!(Self::feeders().contains(&feeder), Error::<T>::NotFeeder);
+
+			// Check if the price is greater than 0
+			ensure!(!price.is_zero(), Error::<T>::InvalidPrice);
+
+			// Record the asset among the ones priced by the feeder
+			FeederAssets::<T>::try_mutate(&feeder, |assets| -> DispatchResult {
+				if !assets.contains(&asset_id) {
+					assets.try_push(asset_id).map_err(|_| Error::<T>::TooManyAssets)?;
+				}
+
+				Ok(())
+			})?;
+
+			// Store the submission
+			let current_block = frame_system::Pallet::<T>::block_number();
+			RawPrices::<T>::insert(
+				asset_id,
+				&feeder,
+				TimestampedPrice { price, block: current_block },
+			);
+
+			// Emit an event
+			Self::deposit_event(Event::PriceFed(feeder, asset_id, price));
+
+			// Aggregate fresh submissions
+			if let Some(aggregated) = Self::aggregate(asset_id, current_block) {
+				Prices::<T>::insert(asset_id, aggregated);
+
+				Self::deposit_event(Event::PriceUpdated(asset_id, aggregated.price));
+			}
+
+			Ok(())
+		}
+	}
+
+	impl<T: Config> Pallet<T> {
+		/// Get the aggregated price of an asset if it is not stale
+		pub fn get_price(asset_id: &AssetIdOf<T>) -> Option<FixedU128> {
+			let current_block = frame_system::Pallet::<T>::block_number();
+
+			Prices::<T>::get(asset_id)
+				.filter(|price| !Self::is_stale(price, current_block))
+				.map(|price| price.price)
+		}
+
+		/// Calculate the median of fresh submissions of current feeders, as long as there are
+		/// at least `MinFeeders` of them
+		fn aggregate(
+			asset_id: AssetIdOf<T>,
+			current_block: BlockNumber<T>,
+		) -> Option<TimestampedPrice<BlockNumber<T>>> {
+			let mut submissions: Vec<TimestampedPrice<BlockNumber<T>>> = Self::feeders()
+				.iter()
+				.filter_map(|feeder| RawPrices::<T>::get(asset_id, feeder))
+				.filter(|price| !Self::is_stale(price, current_block))
+				.collect();
+
+			if submissions.is_empty() || (submissions.len() as u32) < T::MinFeeders::get() {
+				return None
+			}
+
+			submissions.sort_by(|a, b| a.price.cmp(&b.price));
+
+			let middle = submissions.len() / 2;
+			let price = if submissions.len() % 2 == 0 {
+				let sum = submissions[middle - 1].price.saturating_add(submissions[middle].price);
+				FixedU128::from_inner(sum.into_inner() / 2)
+			} else {
+				submissions[middle].price
+			};
+
+			// The median is only as fresh as the oldest submission it includes
+			let block = submissions.iter().map(|price| price.block).min().unwrap_or(current_block);
+
+			Some(TimestampedPrice { price, block })
+		}
+
+		/// Check if the price was submitted too long ago
+		fn is_stale(
+			price: &TimestampedPrice<BlockNumber<T>>,
+			current_block: BlockNumber<T>,
+		) -> bool {
+			current_block.saturating_sub(price.block) > T::MaxPriceAge::get()
+		}
+	}
+
+	impl<T: Config> defi_primitives::PriceOracle<AssetIdOf<T>> for Pallet<T> {
+		fn get_price(asset_id: &AssetIdOf<T>) -> Option<FixedU128> {
+			Self::get_price(asset_id)
+		}
+	}
+}
@@ -0,0 +1,110 @@
+use crate as pallet_oracle;
+use frame_support::{
+	construct_runtime, parameter_types,
+	traits::{ConstU32, Everything},
+};
+use frame_system::{self, EnsureRoot};
+use sp_core::H256;
+use sp_runtime::{
+	traits::{BlakeTwo256, IdentityLookup},
+	BuildStorage,
+};
+
+pub type Block = frame_system::mocking::MockBlock<Runtime>;
+pub type AccountId = u128;
+pub type AssetId = u32;
+
+// Define test accounts
+pub const ALICE: AccountId = 1;
+pub const BOB: AccountId = 2;
+pub const CHARLIE: AccountId = 3;
+pub const DAVE: AccountId = 4;
+
+// Define test assets
+pub const NATIVE: AssetId = 0;
+pub const USDT: AssetId = 1;
+
+// Configure a mock runtime to test the pallet.
+construct_runtime!(
+	pub struct Runtime {
+		System: frame_system,
+		Oracle: pallet_oracle,
+	}
+);
+
+parameter_types! {
+	pub const BlockHashCount: u64 = 250;
+}
+
+impl frame_system::Config for Runtime {
+	type BaseCallFilter = Everything;
+	type BlockWeights = ();
+	type BlockLength = ();
+	type RuntimeOrigin = RuntimeOrigin;
+	type RuntimeCall = RuntimeCall;
+	type Hash = H256;
+	type Hashing = BlakeTwo256;
+	type AccountId = AccountId;
+	type Lookup = IdentityLookup<Self::AccountId>;
+	type RuntimeEvent = RuntimeEvent;
+	type BlockHashCount = BlockHashCount;
+	type DbWeight = ();
+	type Version = ();
+	type PalletInfo = PalletInfo;
+	type AccountData = ();
+	type OnNewAccount = ();
+	type OnKilledAccount = ();
+	type SystemWeightInfo = ();
+	type SS58Prefix = ();
+	type OnSetCode = ();
+	type MaxConsumers = frame_support::traits::ConstU32<65536>;
+	type Nonce = u32;
+	type Block = Block;
+}
+
+parameter_types! {
+	pub const MaxPriceAge: u64 = 10;
+	pub static MinFeeders: u32 = 1;
+}
+
+impl pallet_oracle::Config for Runtime {
+	type RuntimeEvent = RuntimeEvent;
+	type AssetId = AssetId;
+	type AdminOrigin = EnsureRoot<AccountId>;
+	type MaxFeeders = ConstU32<16>;
+	type MinFeeders = MinFeeders;
+	type MaxAssets = ConstU32<4>;
+	type MaxPriceAge = MaxPriceAge;
+	type WeightInfo = ();
+	#[cfg(feature = "runtime-benchmarks")]
+	type BenchmarkHelper = ();
+}
+
+#[derive(Default)]
+pub struct ExtBuilder {
+	feeders: Vec<AccountId>,
+}
+
+impl ExtBuilder {
+	pub fn with_feeders(mut self, feeders: Vec<AccountId>) -> Self {
+		self.feeders = feeders;
+		self
+	}
+
+	pub fn build(self) -> sp_io::TestExternalities {
+		let storage = RuntimeGenesisConfig {
+			oracle: pallet_oracle::GenesisConfig { feeders: self.feeders },
+			..Default::default()
+		}
+		.build_storage()
+		.unwrap();
+
+		storage.into()
+	}
+}
+
+pub fn run_to_block(n: u64) {
+	while System::block_number() < n {
+		System::set_block_number(System::block_number() + 1);
+	}
+}
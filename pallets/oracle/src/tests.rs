@@ -0,0 +1,387 @@
+mod tests {
+	use crate::{mock::*, pallet, Error};
+	use defi_primitives::PriceOracle;
+	use frame_support::{
+		assert_err, assert_ok,
+		sp_runtime::{DispatchError, FixedU128},
+	};
+
+	// Utility functions
+	/// Authorize feeders
+	pub fn add_feeders(feeders: Vec<AccountId>) {
+		for feeder in feeders {
+			assert_ok!(Oracle::add_feeder(RuntimeOrigin::root(), feeder));
+		}
+	}
+
+	#[test]
+	fn add_feeder_bad_origin() {
+		let mut ext = ExtBuilder::default().build();
+		ext.execute_with(|| {
+			// Start test from block 1
+			run_to_block(1);
+
+			assert_err!(
+				Oracle::add_feeder(RuntimeOrigin::signed(ALICE), ALICE),
+				DispatchError::BadOrigin
+			);
+		});
+	}
+
+	#[test]
+	fn add_feeder_already_feeder() {
+		let mut ext = ExtBuilder::default().build();
+		ext.execute_with(|| {
+			// Start test from block 1
+			run_to_block(1);
+
+			add_feeders(vec![ALICE]);
+
+			assert_err!(
+				Oracle::add_feeder(RuntimeOrigin::root(), ALICE),
+				Error::<Runtime>::AlreadyFeeder
+			);
+		});
+	}
+
+	#[test]
+	fn add_and_remove_feeder_ok() {
+		let mut ext = ExtBuilder::default().build();
+		ext.execute_with(|| {
+			// Start test from block 1
+			run_to_block(1);
+
+			add_feeders(vec![ALICE, BOB]);
+			assert_eq!(pallet::Feeders::<Runtime>::get().into_inner(), vec![ALICE, BOB]);
+
+			assert_ok!(Oracle::remove_feeder(RuntimeOrigin::root(), ALICE));
+			assert_eq!(pallet::Feeders::<Runtime>::get().into_inner(), vec![BOB]);
+
+			assert_err!(
+				Oracle::remove_feeder(RuntimeOrigin::root(), ALICE),
+				Error::<Runtime>::NotFeeder
+			);
+		});
+	}
+
+	#[test]
+	fn genesis_feeders_ok() {
+		let mut ext = ExtBuilder::default().with_feeders(vec![ALICE, BOB, CHARLIE]).build();
+		ext.execute_with(|| {
+			// Start test from block 1
+			run_to_block(1);
+
+			assert_eq!(pallet::Feeders::<Runtime>::get().into_inner(), vec![ALICE, BOB, CHARLIE]);
+
+			// Feeders authorized at genesis can submit prices right away
+			assert_ok!(Oracle::feed_price(
+				RuntimeOrigin::signed(CHARLIE),
+				USDT,
+				FixedU128::from_u32(1)
+			));
+			assert_eq!(Oracle::get_price(&USDT), Some(FixedU128::from_u32(1)));
+		});
+	}
+
+	#[test]
+	#[should_panic(expected = "Feeder authorized twice at genesis")]
+	fn genesis_duplicate_feeder() {
+		ExtBuilder::default().with_feeders(vec![ALICE, ALICE]).build();
+	}
+
+	#[test]
+	fn feed_price_not_feeder() {
+		let mut ext = ExtBuilder::default().build();
+		ext.execute_with(|| {
+			// Start test from block 1
+			run_to_block(1);
+
+			assert_err!(
+				Oracle::feed_price(RuntimeOrigin::signed(ALICE), USDT, FixedU128::from_u32(1)),
+				Error::<Runtime>::NotFeeder
+			);
+		});
+	}
+
+	#[test]
+	fn feed_price_invalid_price() {
+		let mut ext = ExtBuilder::default().build();
+		ext.execute_with(|| {
+			// Start test from block 1
+			run_to_block(1);
+
+			add_feeders(vec![ALICE]);
+
+			assert_err!(
+				Oracle::feed_price(RuntimeOrigin::signed(ALICE), USDT, FixedU128::from_u32(0)),
+				Error::<Runtime>::InvalidPrice
+			);
+		});
+	}
+
+	#[test]
+	fn feed_price_median_ok() {
+		let mut ext = ExtBuilder::default().build();
+		ext.execute_with(|| {
+			// Start test from block 1
+			run_to_block(1);
+
+			add_feeders(vec![ALICE, BOB, CHARLIE, DAVE]);
+
+			// Single submission is its own median
+			assert_ok!(Oracle::feed_price(
+				RuntimeOrigin::signed(ALICE),
+				USDT,
+				FixedU128::from_u32(3)
+			));
+			assert_eq!(Oracle::get_price(&USDT), Some(FixedU128::from_u32(3)));
+
+			// Odd number of submissions takes the middle one
+			assert_ok!(Oracle::feed_price(
+				RuntimeOrigin::signed(BOB),
+				USDT,
+				FixedU128::from_u32(1)
+			));
+			assert_ok!(Oracle::feed_price(
+				RuntimeOrigin::signed(CHARLIE),
+				USDT,
+				FixedU128::from_u32(100)
+			));
+			assert_eq!(Oracle::get_price(&USDT), Some(FixedU128::from_u32(3)));
+
+			// Even number of submissions averages the two middle ones
+			assert_ok!(Oracle::feed_price(
+				RuntimeOrigin::signed(DAVE),
+				USDT,
+				FixedU128::from_u32(4)
+			));
+			assert_eq!(Oracle::get_price(&USDT), Some(FixedU128::from_rational(7, 2)));
+
+			// Other assets are priced independently
+			assert_eq!(Oracle::get_price(&NATIVE), None);
+		});
+	}
+
+	#[test]
+	fn feed_price_ignores_stale_submissions() {
+		let mut ext = ExtBuilder::default().build();
+		ext.execute_with(|| {
+			// Start test from block 1
+			run_to_block(1);
+
+			add_feeders(vec![ALICE, BOB, CHARLIE]);
+
+			assert_ok!(Oracle::feed_price(
+				RuntimeOrigin::signed(ALICE),
+				USDT,
+				FixedU128::from_u32(100)
+			));
+
+			// Run blockchain past the maximum price age
+			run_to_block(12);
+
+			assert_ok!(Oracle::feed_price(
+				RuntimeOrigin::signed(BOB),
+				USDT,
+				FixedU128::from_u32(1)
+			));
+			assert_ok!(Oracle::feed_price(
+				RuntimeOrigin::signed(CHARLIE),
+				USDT,
+				FixedU128::from_u32(2)
+			));
+
+			// ALICE submission is not part of the median anymore
+			assert_eq!(Oracle::get_price(&USDT), Some(FixedU128::from_rational(3, 2)));
+		});
+	}
+
+	#[test]
+	fn feed_price_ignores_removed_feeders() {
+		let mut ext = ExtBuilder::default().build();
+		ext.execute_with(|| {
+			// Start test from block 1
+			run_to_block(1);
+
+			add_feeders(vec![ALICE, BOB]);
+
+			assert_ok!(Oracle::feed_price(
+				RuntimeOrigin::signed(ALICE),
+				USDT,
+				FixedU128::from_u32(100)
+			));
+			assert_ok!(Oracle::remove_feeder(RuntimeOrigin::root(), ALICE));
+			assert_ok!(Oracle::feed_price(
+				RuntimeOrigin::signed(BOB),
+				USDT,
+				FixedU128::from_u32(2)
+			));
+
+			assert_eq!(Oracle::get_price(&USDT), Some(FixedU128::from_u32(2)));
+		});
+	}
+
+	#[test]
+	fn feed_price_below_min_feeders() {
+		let mut ext = ExtBuilder::default().build();
+		ext.execute_with(|| {
+			// Start test from block 1
+			run_to_block(1);
+
+			MinFeeders::set(2);
+			add_feeders(vec![ALICE, BOB]);
+
+			// A single submission is not enough to price the asset
+			assert_ok!(Oracle::feed_price(
+				RuntimeOrigin::signed(ALICE),
+				USDT,
+				FixedU128::from_u32(100)
+			));
+			assert_eq!(pallet::Prices::<Runtime>::get(USDT), None);
+			assert_eq!(Oracle::get_price(&USDT), None);
+
+			assert_ok!(Oracle::feed_price(
+				RuntimeOrigin::signed(BOB),
+				USDT,
+				FixedU128::from_u32(2)
+			));
+			assert_eq!(Oracle::get_price(&USDT), Some(FixedU128::from_u32(51)));
+
+			// Price is removed once the feeders fall below the minimum
+			assert_ok!(Oracle::remove_feeder(RuntimeOrigin::root(), ALICE));
+			System::assert_has_event(pallet::Event::PriceRemoved(USDT).into());
+			assert_eq!(Oracle::get_price(&USDT), None);
+		});
+	}
+
+	#[test]
+	fn feed_price_too_many_assets() {
+		let mut ext = ExtBuilder::default().build();
+		ext.execute_with(|| {
+			// Start test from block 1
+			run_to_block(1);
+
+			add_feeders(vec![ALICE]);
+
+			// ALICE prices the maximum number of assets
+			for asset_id in 0..4 {
+				assert_ok!(Oracle::feed_price(
+					RuntimeOrigin::signed(ALICE),
+					asset_id,
+					FixedU128::from_u32(1)
+				));
+			}
+
+			assert_err!(
+				Oracle::feed_price(RuntimeOrigin::signed(ALICE), 4, FixedU128::from_u32(1)),
+				Error::<Runtime>::TooManyAssets
+			);
+
+			// Assets which are already priced can be fed again
+			assert_ok!(Oracle::feed_price(
+				RuntimeOrigin::signed(ALICE),
+				USDT,
+				FixedU128::from_u32(2)
+			));
+		});
+	}
+
+	#[test]
+	fn remove_feeder_aggregates_price_again_ok() {
+		let mut ext = ExtBuilder::default().build();
+		ext.execute_with(|| {
+			// Start test from block 1
+			run_to_block(1);
+
+			add_feeders(vec![ALICE, BOB, CHARLIE]);
+
+			assert_ok!(Oracle::feed_price(
+				RuntimeOrigin::signed(ALICE),
+				USDT,
+				FixedU128::from_u32(1)
+			));
+			assert_ok!(Oracle::feed_price(
+				RuntimeOrigin::signed(BOB),
+				USDT,
+				FixedU128::from_u32(2)
+			));
+			assert_ok!(Oracle::feed_price(
+				RuntimeOrigin::signed(CHARLIE),
+				USDT,
+				FixedU128::from_u32(100)
+			));
+			assert_eq!(Oracle::get_price(&USDT), Some(FixedU128::from_u32(2)));
+
+			// CHARLIE submissions are dropped and the price is aggregated without them
+			assert_ok!(Oracle::remove_feeder(RuntimeOrigin::root(), CHARLIE));
+			System::assert_has_event(
+				pallet::Event::PriceUpdated(USDT, FixedU128::from_rational(3, 2)).into(),
+			);
+			assert_eq!(Oracle::get_price(&USDT), Some(FixedU128::from_rational(3, 2)));
+			assert_eq!(pallet::RawPrices::<Runtime>::get(USDT, CHARLIE), None);
+			assert!(pallet::FeederAssets::<Runtime>::get(CHARLIE).is_empty());
+			assert_eq!(pallet::FeederAssets::<Runtime>::get(ALICE).into_inner(), vec![USDT]);
+		});
+	}
+
+	#[test]
+	fn remove_feeder_removes_price_ok() {
+		let mut ext = ExtBuilder::default().build();
+		ext.execute_with(|| {
+			// Start test from block 1
+			run_to_block(1);
+
+			add_feeders(vec![ALICE, BOB]);
+
+			assert_ok!(Oracle::feed_price(
+				RuntimeOrigin::signed(ALICE),
+				USDT,
+				FixedU128::from_u32(100)
+			));
+			assert_ok!(Oracle::feed_price(
+				RuntimeOrigin::signed(BOB),
+				NATIVE,
+				FixedU128::from_u32(2)
+			));
+
+			// ALICE was the only feeder of the price, which is removed along with the feeder
+			assert_ok!(Oracle::remove_feeder(RuntimeOrigin::root(), ALICE));
+			System::assert_has_event(pallet::Event::PriceRemoved(USDT).into());
+			assert_eq!(pallet::Prices::<Runtime>::get(USDT), None);
+			assert_eq!(pallet::RawPrices::<Runtime>::get(USDT, ALICE), None);
+			assert_eq!(Oracle::get_price(&USDT), None);
+
+			// Prices fed by other feeders are untouched
+			assert_eq!(Oracle::get_price(&NATIVE), Some(FixedU128::from_u32(2)));
+		});
+	}
+
+	#[test]
+	fn get_price_stale() {
+		let mut ext = ExtBuilder::default().build();
+		ext.execute_with(|| {
+			// Start test from block 1
+			run_to_block(1);
+
+			add_feeders(vec![ALICE]);
+
+			assert_ok!(Oracle::feed_price(
+				RuntimeOrigin::signed(ALICE),
+				USDT,
+				FixedU128::from_u32(2)
+			));
+
+			// Price is still fresh at the maximum age
+			run_to_block(11);
+			assert_eq!(
+				<Oracle as PriceOracle<AssetId>>::get_price(&USDT),
+				Some(FixedU128::from_u32(2))
+			);
+
+			// Price is rejected once it gets older than the maximum age
+			run_to_block(12);
+			assert_eq!(<Oracle as PriceOracle<AssetId>>::get_price(&USDT), None);
+			assert!(pallet::Prices::<Runtime>::get(USDT).is_some());
+		});
+	}
+}
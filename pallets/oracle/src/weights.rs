@@ -0,0 +1,117 @@
+//! Weights for `pallet_oracle`
+//!
+//! THESE WEIGHTS ARE MAINTAINED BY HAND AND WERE NOT PRODUCED BY THE BENCHMARK CLI.
+//! Storage reads and writes are counted from the code and proof sizes follow from the
+//! `MaxEncodedLen` of the accessed items, execution times are estimates. Regenerate the file
+//! with the command below on reference hardware before relying on the execution times.
+
+// Command:
+// target/release/node-template
+// benchmark
+// pallet
+// --chain=local
+// --wasm-execution=compiled
+// --pallet
+// pallet_oracle
+// --extrinsic
+// *
+// --steps
+// 50
+// --repeat
+// 20
+// --output
+// pallets/oracle/src/weights.rs
+
+#![cfg_attr(rustfmt, rustfmt_skip)]
+#![allow(unused_parens)]
+#![allow(unused_imports)]
+#![allow(missing_docs)]
+
+use frame_support::{traits::Get, weights::{Weight, constants::RocksDbWeight}};
+use core::marker::PhantomData;
+
+pub trait WeightInfo {
+	fn add_feeder() -> Weight;
+	fn remove_feeder(n: u32, ) -> Weight;
+	fn feed_price(n: u32, ) -> Weight;
+}
+
+/// Weight functions for `pallet_oracle`.
+pub struct SubstrateWeight<T>(PhantomData<T>);
+impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
+	/// Storage: `Oracle::Feeders` (r:1 w:1)
+	/// Proof: `Oracle::Feeders` (`max_values`: Some(1), `max_size`: Some(513), added: 1008, mode: `MaxEncodedLen`)
+	fn add_feeder() -> Weight {
+		Weight::from_parts(11_752_000, 0)
+			.saturating_add(Weight::from_parts(0, 1998))
+			.saturating_add(T::DbWeight::get().reads(1))
+			.saturating_add(T::DbWeight::get().writes(1))
+	}
+	/// Storage: `Oracle::Feeders` (r:1 w:1)
+	/// Proof: `Oracle::Feeders` (`max_values`: Some(1), `max_size`: Some(513), added: 1008, mode: `MaxEncodedLen`)
+	/// Storage: `Oracle::FeederAssets` (r:1 w:1)
+	/// Proof: `Oracle::FeederAssets` (`max_values`: None, `max_size`: Some(113), added: 2588, mode: `MaxEncodedLen`)
+	/// Storage: `Oracle::RawPrices` (r:256 w:16)
+	/// Proof: `Oracle::RawPrices` (`max_values`: None, `max_size`: Some(88), added: 2563, mode: `MaxEncodedLen`)
+	/// Storage: `Oracle::Prices` (r:0 w:16)
+	/// Proof: `Oracle::Prices` (`max_values`: None, `max_size`: Some(40), added: 2515, mode: `MaxEncodedLen`)
+	/// The range of component `n` is `[0, 16]`.
+	fn remove_feeder(n: u32, ) -> Weight {
+		Weight::from_parts(17_118_000, 0)
+			.saturating_add(Weight::from_parts(0, 3578))
+			.saturating_add(Weight::from_parts(68_412_000, 0).saturating_mul(n.into()))
+			.saturating_add(T::DbWeight::get().reads(2))
+			.saturating_add(T::DbWeight::get().reads((16_u64).saturating_mul(n.into())))
+			.saturating_add(T::DbWeight::get().writes(2))
+			.saturating_add(T::DbWeight::get().writes((2_u64).saturating_mul(n.into())))
+			.saturating_add(Weight::from_parts(0, 41008).saturating_mul(n.into()))
+	}
+	/// Storage: `Oracle::Feeders` (r:1 w:0)
+	/// Proof: `Oracle::Feeders` (`max_values`: Some(1), `max_size`: Some(513), added: 1008, mode: `MaxEncodedLen`)
+	/// Storage: `Oracle::FeederAssets` (r:1 w:1)
+	/// Proof: `Oracle::FeederAssets` (`max_values`: None, `max_size`: Some(113), added: 2588, mode: `MaxEncodedLen`)
+	/// Storage: `Oracle::RawPrices` (r:16 w:1)
+	/// Proof: `Oracle::RawPrices` (`max_values`: None, `max_size`: Some(88), added: 2563, mode: `MaxEncodedLen`)
+	/// Storage: `Oracle::Prices` (r:0 w:1)
+	/// Proof: `Oracle::Prices` (`max_values`: None, `max_size`: Some(40), added: 2515, mode: `MaxEncodedLen`)
+	/// The range of component `n` is `[3, 16]`.
+	fn feed_price(n: u32, ) -> Weight {
+		Weight::from_parts(28_164_000, 0)
+			.saturating_add(Weight::from_parts(0, 3578))
+			.saturating_add(Weight::from_parts(4_087_000, 0).saturating_mul(n.into()))
+			.saturating_add(T::DbWeight::get().reads(2))
+			.saturating_add(T::DbWeight::get().reads((1_u64).saturating_mul(n.into())))
+			.saturating_add(T::DbWeight::get().writes(3))
+			.saturating_add(Weight::from_parts(0, 2563).saturating_mul(n.into()))
+	}
+}
+
+impl WeightInfo for () {
+	fn add_feeder() -> Weight {
+		Weight::from_parts(11_752_000, 0)
+			.saturating_add(Weight::from_parts(0, 1998))
+			.saturating_add(RocksDbWeight::get().reads(1))
+			.saturating_add(RocksDbWeight::get().writes(1))
+	}
+
+	fn remove_feeder(n: u32, ) -> Weight {
+		Weight::from_parts(17_118_000, 0)
+			.saturating_add(Weight::from_parts(0, 3578))
+			.saturating_add(Weight::from_parts(68_412_000, 0).saturating_mul(n.into()))
+			.saturating_add(RocksDbWeight::get().reads(2))
+			.saturating_add(RocksDbWeight::get().reads((16_u64).saturating_mul(n.into())))
+			.saturating_add(RocksDbWeight::get().writes(2))
+			.saturating_add(RocksDbWeight::get().writes((2_u64).saturating_mul(n.into())))
+			.saturating_add(Weight::from_parts(0, 41008).saturating_mul(n.into()))
+	}
+
+	fn feed_price(n: u32, ) -> Weight {
+		Weight::from_parts(28_164_000, 0)
+			.saturating_add(Weight::from_parts(0, 3578))
+			.saturating_add(Weight::from_parts(4_087_000, 0).saturating_mul(n.into()))
+			.saturating_add(RocksDbWeight::get().reads(2))
+			.saturating_add(RocksDbWeight::get().reads((1_u64).saturating_mul(n.into())))
+			.saturating_add(RocksDbWeight::get().writes(3))
+			.saturating_add(Weight::from_parts(0, 2563).saturating_mul(n.into()))
+	}
+}
@@ -0,0 +1,31 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use sp_arithmetic::{traits::One, FixedU128};
+
+/// Provides prices of the assets listed as lending markets
+pub trait PriceOracle<AssetId> {
+	/// Price of the asset's base unit expressed in a common unit of account
+	fn get_price(asset_id: &AssetId) -> Option<FixedU128>;
+}
+
+/// Values every asset at par, which only makes sense for single-asset deployments
+impl<AssetId> PriceOracle<AssetId> for () {
+	fn get_price(_asset_id: &AssetId) -> Option<FixedU128> {
+		Some(FixedU128::one())
+	}
+}
+
+/// Provides asset ids to benchmarks
+pub trait BenchmarkHelper<AssetId> {
+	/// Returns an asset id from a given integer
+	fn asset_id(id: u32) -> AssetId;
+
+	/// Sets the price the oracle provides for the asset, so benchmarks can value positions
+	fn set_price(_asset_id: AssetId, _price: FixedU128) {}
+}
+
+impl<AssetId: From<u32>> BenchmarkHelper<AssetId> for () {
+	fn asset_id(id: u32) -> AssetId {
+		id.into()
+	}
+}
@@ -6,6 +6,7 @@
 #[cfg(feature = "std")]
 include!(concat!(env!("OUT_DIR"), "/wasm_binary.rs"));
 
+use frame_support::traits::{Contains, EnsureOrigin, EnsureOriginWithArg};
 use pallet_grandpa::AuthorityId as GrandpaId;
 use sp_api::impl_runtime_apis;
 use sp_consensus_aura::sr25519::AuthorityId as AuraId;
@@ -13,10 +14,11 @@ use sp_core::{crypto::KeyTypeId, OpaqueMetadata};
 use sp_runtime::{
 	create_runtime_str, generic, impl_opaque_keys,
 	traits::{
-		AccountIdLookup, BlakeTwo256, Block as BlockT, IdentifyAccount, NumberFor, One, Verify,
+		AccountIdConversion, AccountIdLookup, BlakeTwo256, Block as BlockT, Convert,
+		IdentifyAccount, NumberFor, One, Verify,
 	},
-	transaction_validity::{TransactionSource, TransactionValidity},
-	ApplyExtrinsicResult, MultiSignature,
+	transaction_validity::{TransactionPriority, TransactionSource, TransactionValidity},
+	ApplyExtrinsicResult, FixedU128, MultiSignature,
 };
 use sp_std::prelude::*;
 #[cfg(feature = "std")]
@@ -27,8 +29,8 @@ use sp_version::RuntimeVersion;
 pub use frame_support::{
 	construct_runtime, parameter_types,
 	traits::{
-		ConstBool, ConstU128, ConstU32, ConstU64, ConstU8, KeyOwnerProofSystem, Randomness,
-		StorageInfo,
+		AsEnsureOriginWithArg, ConstBool, ConstU128, ConstU32, ConstU64, ConstU8, EitherOfDiverse,
+		KeyOwnerProofSystem, Randomness, StorageInfo,
 	},
 	weights::{
 		constants::{
@@ -36,9 +38,10 @@ pub use frame_support::{
 		},
 		IdentityFee, Weight,
 	},
-	StorageValue,
+	PalletId, StorageValue,
 };
 pub use frame_system::Call as SystemCall;
+use frame_system::{EnsureRoot, EnsureRootWithSuccess, EnsureSigned};
 pub use pallet_balances::Call as BalancesCall;
 pub use pallet_timestamp::Call as TimestampCall;
 use pallet_transaction_payment::{ConstFeeMultiplier, CurrencyAdapter, Multiplier};
@@ -46,8 +49,9 @@ use pallet_transaction_payment::{ConstFeeMultiplier, CurrencyAdapter, Multiplier
 pub use sp_runtime::BuildStorage;
 pub use sp_runtime::{Perbill, Permill};
 
-// Import the custom pallet (pallet_defi)
+// Import the custom pallets (pallet_defi, pallet_oracle)
 pub use pallet_defi;
+pub use pallet_oracle;
 
 /// An index to a block.
 pub type BlockNumber = u32;
@@ -104,10 +108,10 @@ pub const VERSION: RuntimeVersion = RuntimeVersion {
 	//   `spec_version`, and `authoring_version` are the same between Wasm and native.
 	// This value is set to 100 to notify Polkadot-JS App (https://polkadot.js.org/apps) to use
 	//   the compatible custom types.
-	spec_version: 100,
+	spec_version: 106,
 	impl_version: 1,
 	apis: RUNTIME_API_VERSIONS,
-	transaction_version: 1,
+	transaction_version: 2,
 	state_version: 1,
 };
 
@@ -245,8 +249,8 @@ impl pallet_balances::Config for Runtime {
 	type WeightInfo = pallet_balances::weights::SubstrateWeight<Runtime>;
 	type FreezeIdentifier = ();
 	type MaxFreezes = ();
-	type RuntimeHoldReason = ();
-	type MaxHolds = ();
+	type RuntimeHoldReason = RuntimeHoldReason;
+	type MaxHolds = ConstU32<1>;
 }
 
 parameter_types! {
@@ -268,16 +272,191 @@ impl pallet_sudo::Config for Runtime {
 	type WeightInfo = pallet_sudo::weights::SubstrateWeight<Runtime>;
 }
 
+/// Identifier of a fungible asset.
+pub type AssetId = u32;
+
+parameter_types! {
+	pub const AssetDeposit: Balance = 100 * EXISTENTIAL_DEPOSIT;
+	pub const AssetAccountDeposit: Balance = EXISTENTIAL_DEPOSIT;
+	pub const ApprovalDeposit: Balance = EXISTENTIAL_DEPOSIT;
+	pub const MetadataDepositBase: Balance = 10 * EXISTENTIAL_DEPOSIT;
+	pub const MetadataDepositPerByte: Balance = EXISTENTIAL_DEPOSIT;
+}
+
+/// Freezes the defi receipt tokens backing an account's debt, so that borrowers can't
+/// transfer their collateral away
+pub struct DefiReceiptFreezer;
+
+impl pallet_assets::FrozenBalance<AssetId, AccountId, Balance> for DefiReceiptFreezer {
+	fn frozen_balance(asset: AssetId, who: &AccountId) -> Option<Balance> {
+		Defi::frozen_receipts(asset, who)
+	}
+
+	fn died(_asset: AssetId, _who: &AccountId) {}
+}
+
+/// Lets signed accounts create assets, except with the ids reserved for the defi receipt
+/// tokens
+pub struct EnsureRegularAssetCreator;
+
+impl EnsureOriginWithArg<RuntimeOrigin, AssetId> for EnsureRegularAssetCreator {
+	type Success = AccountId;
+
+	fn try_origin(o: RuntimeOrigin, asset_id: &AssetId) -> Result<Self::Success, RuntimeOrigin> {
+		if asset_id & DEFI_RECEIPT_ASSET_OFFSET != 0 {
+			return Err(o)
+		}
+
+		EnsureSigned::<AccountId>::try_origin(o)
+	}
+
+	#[cfg(feature = "runtime-benchmarks")]
+	fn try_successful_origin(asset_id: &AssetId) -> Result<RuntimeOrigin, ()> {
+		if asset_id & DEFI_RECEIPT_ASSET_OFFSET != 0 {
+			return Err(())
+		}
+
+		EnsureSigned::<AccountId>::try_successful_origin()
+	}
+}
+
+impl pallet_assets::Config for Runtime {
+	type RuntimeEvent = RuntimeEvent;
+	type Balance = Balance;
+	type AssetId = AssetId;
+	type AssetIdParameter = codec::Compact<AssetId>;
+	type Currency = Balances;
+	type CreateOrigin = EnsureRegularAssetCreator;
+	type ForceOrigin = EnsureRoot<AccountId>;
+	type AssetDeposit = AssetDeposit;
+	type AssetAccountDeposit = AssetAccountDeposit;
+	type MetadataDepositBase = MetadataDepositBase;
+	type MetadataDepositPerByte = MetadataDepositPerByte;
+	type ApprovalDeposit = ApprovalDeposit;
+	type StringLimit = ConstU32<50>;
+	type Freezer = DefiReceiptFreezer;
+	type Extra = ();
+	type CallbackHandle = ();
+	type WeightInfo = pallet_assets::weights::SubstrateWeight<Runtime>;
+	type RemoveItemsLimit = ConstU32<1000>;
+	#[cfg(feature = "runtime-benchmarks")]
+	type BenchmarkHelper = ();
+}
+
+parameter_types! {
+	pub const MaxPriceAge: BlockNumber = 10 * MINUTES;
+}
+
+// Configure the custom pallet (pallet-oracle)
+impl pallet_oracle::Config for Runtime {
+	type RuntimeEvent = RuntimeEvent;
+	type AssetId = AssetId;
+	type AdminOrigin = EnsureRoot<AccountId>;
+	type MaxFeeders = ConstU32<16>;
+	type MinFeeders = ConstU32<3>;
+	type MaxAssets = ConstU32<16>;
+	type MaxPriceAge = MaxPriceAge;
+	type WeightInfo = pallet_oracle::weights::SubstrateWeight<Runtime>;
+	#[cfg(feature = "runtime-benchmarks")]
+	type BenchmarkHelper = ();
+}
+
+/// Receipt tokens of the defi markets take the asset ids with this bit set, which
+/// `EnsureRegularAssetCreator` keeps signed accounts from creating assets with
+pub const DEFI_RECEIPT_ASSET_OFFSET: AssetId = 1 << 31;
+
+/// Maps a defi market to the asset id of its receipt token
+pub struct DefiReceiptAssetId;
+
+impl Convert<AssetId, AssetId> for DefiReceiptAssetId {
+	fn convert(asset_id: AssetId) -> AssetId {
+		asset_id | DEFI_RECEIPT_ASSET_OFFSET
+	}
+}
+
 parameter_types! {
+	/// Blocks a year the defi markets accrued interest over before accruing per second
 	pub const NumberOfBlocksYearly: u32 = 5256000;
+	pub const NativeAssetId: AssetId = 0;
+	pub ReserveAccount: AccountId = PalletId(*b"defi/rsv").into_account_truncating();
+	pub CloseFactor: FixedU128 = FixedU128::from_rational(50, 100);
+	pub LiquidationIncentive: FixedU128 = FixedU128::from_rational(8, 100);
+	pub const DefiUnsignedPriority: TransactionPriority = TransactionPriority::max_value() / 2;
+	pub const ParameterChangeDelay: BlockNumber = DAYS;
+}
+
+impl frame_system::offchain::SigningTypes for Runtime {
+	type Public = <Signature as Verify>::Signer;
+	type Signature = Signature;
+}
+
+impl<C> frame_system::offchain::SendTransactionTypes<C> for Runtime
+where
+	RuntimeCall: From<C>,
+{
+	type OverarchingCall = RuntimeCall;
+	type Extrinsic = UncheckedExtrinsic;
+}
+
+/// Calls which can be dispatched with a defi flash loan, anything but the defi pallet's own
+pub struct DefiFlashLoanCallFilter;
+
+impl Contains<RuntimeCall> for DefiFlashLoanCallFilter {
+	fn contains(call: &RuntimeCall) -> bool {
+		!matches!(call, RuntimeCall::Defi(_))
+	}
+}
+
+/// Seeds the oracle prices the defi benchmarks value positions with
+#[cfg(feature = "runtime-benchmarks")]
+pub struct DefiBenchmarkHelper;
+
+#[cfg(feature = "runtime-benchmarks")]
+impl pallet_defi::BenchmarkHelper<AssetId> for DefiBenchmarkHelper {
+	fn asset_id(id: u32) -> AssetId {
+		id
+	}
+
+	fn set_price(asset_id: AssetId, price: FixedU128) {
+		pallet_oracle::Prices::<Runtime>::insert(
+			asset_id,
+			pallet_oracle::TimestampedPrice { price, block: System::block_number() },
+		);
+	}
 }
 
 // Configure the custom pallet (pallet-defi)
 impl pallet_defi::Config for Runtime {
 	type RuntimeEvent = RuntimeEvent;
+	type RuntimeCall = RuntimeCall;
+	type CallFilter = DefiFlashLoanCallFilter;
+	type Balance = Balance;
 	type Currency = Balances;
-	type NumberOfBlocksYearly = NumberOfBlocksYearly;
+	type RuntimeHoldReason = RuntimeHoldReason;
+	type AssetId = AssetId;
+	type Assets = Assets;
+	type ReceiptAssetId = DefiReceiptAssetId;
+	type NativeAssetId = NativeAssetId;
+	type PriceOracle = Oracle;
+	type InterestRateModel = pallet_defi::FixedRateModel<Runtime>;
+	type AdminOrigin = EnsureRoot<AccountId>;
+	type ReserveOrigin = EnsureRootWithSuccess<AccountId, ReserveAccount>;
+	type PauseOrigin =
+		EitherOfDiverse<EnsureRoot<AccountId>, pallet_defi::EnsurePauseGuardian<Runtime>>;
+	type CloseFactor = CloseFactor;
+	type LiquidationIncentive = LiquidationIncentive;
+	type MaxMarkets = ConstU32<16>;
+	type MaxLiquidationChecksPerBlock = ConstU32<100>;
+	type MaxAccountsMigratedPerBlock = ConstU32<500>;
+	type AuthorityId = pallet_defi::crypto::LiquidatorAuthId;
+	type MaxUnsignedLiquidations = ConstU32<10>;
+	type UnsignedPriority = DefiUnsignedPriority;
+	type UnsignedLongevity = ConstU64<5>;
+	type ParameterChangeDelay = ParameterChangeDelay;
+	type AccrualClock = pallet_defi::TimestampClock<Timestamp>;
 	type WeightInfo = pallet_defi::weights::SubstrateWeight<Runtime>;
+	#[cfg(feature = "runtime-benchmarks")]
+	type BenchmarkHelper = DefiBenchmarkHelper;
 }
 
 // Create the runtime by composing the FRAME pallets that were previously configured.
@@ -290,6 +469,8 @@ construct_runtime!(
 		Balances: pallet_balances,
 		TransactionPayment: pallet_transaction_payment,
 		Sudo: pallet_sudo,
+		Assets: pallet_assets,
+		Oracle: pallet_oracle,
 		Defi: pallet_defi,
 	}
 );
@@ -324,8 +505,16 @@ pub type Executive = frame_executive::Executive<
 	frame_system::ChainContext<Runtime>,
 	Runtime,
 	AllPalletsWithSystem,
+	Migrations,
 >;
 
+/// Migrations applied on runtime upgrade, each one is a no-op once its version is reached.
+pub type Migrations = (
+	pallet_defi::migrations::v1::MigrateToV1<Runtime>,
+	pallet_defi::migrations::v2::MigrateToV2<Runtime>,
+	pallet_defi::migrations::v3::MigrateToV3<Runtime, NumberOfBlocksYearly>,
+);
+
 #[cfg(feature = "runtime-benchmarks")]
 #[macro_use]
 extern crate frame_benchmarking;
@@ -336,9 +525,11 @@ mod benches {
 		[frame_benchmarking, BaselineBench::<Runtime>]
 		[frame_system, SystemBench::<Runtime>]
 		[pallet_balances, Balances]
+		[pallet_assets, Assets]
 		[pallet_timestamp, Timestamp]
 		[pallet_sudo, Sudo]
 		[pallet_defi, Defi]
+		[pallet_oracle, Oracle]
 	);
 }
 
@@ -466,6 +657,42 @@ impl_runtime_apis! {
 		}
 	}
 
+	impl pallet_defi_runtime_api::DefiApi<Block, AccountId, AssetId, Balance> for Runtime {
+		fn get_lending_amount(asset_id: AssetId, who: AccountId) -> Balance {
+			Defi::get_lending_amount(asset_id, who)
+		}
+
+		fn get_held_collateral(asset_id: AssetId, who: AccountId) -> Balance {
+			Defi::held_collateral(asset_id, &who)
+		}
+
+		fn get_debt_amount(asset_id: AssetId, who: AccountId) -> Balance {
+			Defi::get_debt_amount(asset_id, who)
+		}
+
+		fn get_allowed_borrowing_amount(asset_id: AssetId, who: AccountId) -> Balance {
+			Defi::get_allowed_borrowing_amount(asset_id, who)
+		}
+
+		fn get_deposit_apy(asset_id: AssetId) -> Balance {
+			Defi::get_deposit_apy(asset_id)
+		}
+
+		fn get_borrowing_apy(asset_id: AssetId) -> Balance {
+			Defi::get_borrowing_apy(asset_id)
+		}
+
+		fn get_health_factor(
+			who: AccountId,
+		) -> Result<Option<Balance>, sp_runtime::DispatchError> {
+			Defi::get_health_factor(&who)
+		}
+
+		fn is_paused(operation: pallet_defi::Operation) -> bool {
+			Defi::is_paused(operation)
+		}
+	}
+
 	impl pallet_transaction_payment_rpc_runtime_api::TransactionPaymentApi<Block, Balance> for Runtime {
 		fn query_info(
 			uxt: <Block as BlockT>::Extrinsic,